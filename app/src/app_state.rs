@@ -1,35 +1,107 @@
 use anyhow::Result;
+use iced::widget::image;
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Instant;
 
 use crate::app_modal::{ModalAsk, ModalAskMessage};
 use crate::app_status::{Status, StatusMessage};
-use cleaner::Cleaner;
+use cleaner::{
+    AppCatalog, CancelToken, CatalogDetails, CatalogSort, Cleaner, IdentitySuggestion, OrphanGroup,
+    PermissionReport, Refusal, TrashFailure, TrashRemedy, TrashReport,
+};
+use status::StatusEmitter;
+use status::line_format::LineFormat;
+use status::log_file_handler::LogFileHandler;
+use status::status_event::OperationId;
 
 #[derive(Debug, Clone)]
 pub enum AppMessage {
     DropFile(PathBuf),
     InputFile,
-    ScanApp(Result<Cleaner, String>),
+    /// Scans carry their generation, results of a superseded scan are dropped
+    ScanApp(u64, Result<Cleaner, String>),
 
     ModalAsk(ModalAskMessage),
-    ConfirmKill(Result<Cleaner, String>),
+    ConfirmKill(u64, Result<Cleaner, String>),
 
-    UpdateCleaner(Cleaner),
+    UpdateCleaner(u64, Cleaner),
+    Cancel,
     OpenSelectedPath(usize),
 
     BrowseOutput,
     OutputFile(Result<Arc<PathBuf>, String>),
     ExportFile,
+    BomLogsSaved,
 
     TrashApp,
-    DeletedApp(Result<Vec<(PathBuf, String)>, String>),
+    DeletedApp(TrashReport),
+    /// Size of what is left in the list, for the scan of that generation
+    TotalSize(u64, u64),
+    RetryWithAdmin,
+    RevealInFinder(PathBuf),
     ClearList,
     Status(StatusMessage),
 
+    /// Tagged with the scan generation the failed operation was started in
+    Failed(u64, Arc<anyhow::Error>),
+    OpenFullDiskAccess,
+    PermissionsProbed(PermissionReport),
+
+    FindOrphans,
+    OrphansFound(Vec<OrphanGroup>),
+    ToggleOrphan(usize),
+    TrashOrphans,
+
+    ShowIdentityForm,
+    IdentitySuggestions(Vec<IdentitySuggestion>),
+    IdentityInput(IdentityField, String),
+    PickSuggestion(String),
+    ScanIdentity,
+
+    ShowCatalog,
+    CatalogLoaded(AppCatalog),
+    CatalogDetailsLoaded(Vec<(PathBuf, CatalogDetails)>),
+    CatalogQuery(String),
+    CatalogSortBy(CatalogSort),
+    CatalogClicked(PathBuf),
+
     NoOperations,
 }
 
+#[derive(Debug, Clone, Copy)]
+pub enum IdentityField {
+    Name,
+    BundleId,
+    Executable,
+}
+
+/// What the user knows about an app that was already deleted
+#[derive(Debug, Clone, Default)]
+pub struct IdentityForm {
+    pub name: String,
+    pub bundle_id: String,
+    pub executable: String,
+    pub suggestions: Vec<IdentitySuggestion>,
+}
+
+/// Installed applications to pick from instead of dropping one
+#[derive(Debug, Clone, Default)]
+pub struct CatalogView {
+    pub catalog: AppCatalog,
+    pub loading: bool,
+    /// Built once, a new handle every view would upload the icon again
+    pub icons: HashMap<PathBuf, image::Handle>,
+    /// Entries whose details are being loaded
+    pub pending: HashSet<PathBuf>,
+    pub query: String,
+    pub sort: CatalogSort,
+    pub ascending: bool,
+    /// Last clicked entry, a second click on it soon after opens it
+    pub last_click: Option<(PathBuf, Instant)>,
+}
+
 #[derive(Clone)]
 pub struct AppState {
     pub input_file: PathBuf,
@@ -40,6 +112,33 @@ pub struct AppState {
     pub selected_file: Option<usize>,
     pub show_modal_ask: ModalAsk,
     pub pending_cleaner: Option<Cleaner>,
+    pub app_icon: Option<image::Handle>,
+    pub show_full_disk_access: bool,
+    pub trash_failures: Vec<(PathBuf, TrashFailure)>,
+    /// Items the safety guard kept out of the Trash, they are never retried
+    pub trash_refused: Vec<(PathBuf, Refusal)>,
+    /// One action per remedy of the items that failed to move to the Trash
+    pub trash_remedies: Vec<(TrashRemedy, PathBuf)>,
+    /// Listing leftovers of deleted apps instead of the files of one app
+    pub orphan_mode: bool,
+    /// Leftover groups and whether they are selected for trashing
+    pub orphans: Vec<(OrphanGroup, bool)>,
+    /// Shown instead of the list while entering a bundle id by hand
+    pub identity_form: Option<IdentityForm>,
+    /// Shown instead of the list while browsing the installed apps
+    pub catalog: Option<CatalogView>,
+    /// Protected locations that can be read, kept across resets
+    pub permissions: PermissionReport,
+    /// Stops the running scan or operation, None when nothing runs
+    pub cancel: Option<CancelToken>,
+    /// Bumped by every new scan and by cancelling, kept across resets
+    pub scan_generation: u64,
+    /// Events of the dropped app share it, from resolving to the end of the scan
+    pub operation: OperationId,
+    /// Scope of the app in the running operation, its steps report in scopes of it
+    pub reporter: Option<StatusEmitter>,
+    /// Every status event as JSON lines, None when the file could not be opened
+    pub status_log: Option<Arc<LogFileHandler>>,
 }
 
 impl AppState {
@@ -50,6 +149,7 @@ impl AppState {
             message: None,
             event: None,
             show_percentage: false,
+            last_step: None,
         };
 
         let cleaner = Cleaner::default();
@@ -57,6 +157,23 @@ impl AppState {
 
         let show_modal_ask = ModalAsk::default();
         let pending_cleaner = None;
+        let app_icon = None;
+        let show_full_disk_access = false;
+        let trash_failures = Vec::new();
+        let trash_refused = Vec::new();
+        let trash_remedies = Vec::new();
+        let orphan_mode = false;
+        let orphans = Vec::new();
+        let identity_form = None;
+        let catalog = None;
+        let permissions = PermissionReport::default();
+        let cancel = None;
+        let scan_generation = 0;
+        let operation = OperationId::default();
+        let reporter = None;
+        let status_log = LogFileHandler::new(&Self::status_log_path(), LineFormat::Json)
+            .ok()
+            .map(Arc::new);
 
         Self {
             input_file,
@@ -66,14 +183,70 @@ impl AppState {
             selected_file,
             show_modal_ask,
             pending_cleaner,
+            app_icon,
+            show_full_disk_access,
+            trash_failures,
+            trash_refused,
+            trash_remedies,
+            orphan_mode,
+            orphans,
+            identity_form,
+            catalog,
+            permissions,
+            cancel,
+            scan_generation,
+            operation,
+            reporter,
+            status_log,
         }
     }
 
+    /// ~/Library/Logs/Bristo/status.log
+    pub fn status_log_path() -> PathBuf {
+        let home = std::env::var("HOME").unwrap_or_else(|_| "/Users/Unknown".to_string());
+        PathBuf::from(home).join("Library/Logs/Bristo/status.log")
+    }
+
     pub fn reset(&mut self) {
         self.input_file.clear();
         self.output_file.clear();
         self.cleaner.reset();
         self.selected_file = None;
+        self.app_icon = None;
+        self.show_full_disk_access = false;
+        self.trash_failures.clear();
+        self.trash_refused.clear();
+        self.trash_remedies.clear();
+        self.orphan_mode = false;
+        self.orphans.clear();
+        self.identity_form = None;
+        self.catalog = None;
         self.status.reset();
+        self.cancel_running();
+    }
+
+    /// Token of a new operation, the one running before is cancelled
+    pub fn start_operation(&mut self) -> CancelToken {
+        if let Some(token) = self.cancel.take() {
+            token.cancel();
+        }
+        let token = CancelToken::new();
+        self.cancel = Some(token.clone());
+        token
+    }
+
+    /// Like start_operation, results of older scans are ignored from now on
+    pub fn start_scan(&mut self) -> (u64, CancelToken) {
+        self.scan_generation += 1;
+        (self.scan_generation, self.start_operation())
+    }
+
+    /// Stop what is running and drop the results of the current scan
+    pub fn cancel_running(&mut self) {
+        if let Some(token) = self.cancel.take() {
+            token.cancel();
+        }
+        self.reporter = None;
+        self.scan_generation += 1;
     }
 }
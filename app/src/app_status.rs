@@ -1,5 +1,6 @@
 use iced::{Element, Task};
-use status::status_event::StatusEvent;
+use status::formatter::{Compact, StatusFormatter};
+use status::status_event::{StatusEvent, Step};
 
 #[derive(Debug, Clone)]
 pub enum StatusMessage {
@@ -11,35 +12,29 @@ pub struct Status {
     pub message: Option<String>,
     pub event: Option<StatusEvent>,
     pub show_percentage: bool,
+    /// Last step that was running, where a failure happened
+    pub last_step: Option<Step>,
 }
 
 impl Status {
     fn update_status_message(&mut self) {
-        if let Some(ref event) = self.event {
-            let mut status = format!("{}", event);
+        let formatter = Compact::new().with_percentage(self.show_percentage);
 
-            if let (Some(c), Some(t)) = (event.current, event.total)
-                && self.show_percentage
-                && t > 0
-            {
-                let pct = format!(" ({:.1}%)", ((c.min(t)) as f32 / t as f32) * 100.0);
-                status.push_str(&pct);
-            }
-
-            self.message = Some(status);
-        } else {
-            self.message = None;
-        }
+        self.message = self.event.as_ref().map(|event| formatter.format(event));
     }
 
     pub fn reset(&mut self) {
         self.message = None;
         self.event = None;
+        self.last_step = None;
     }
 
     pub fn update(&mut self, msg: StatusMessage) -> Task<StatusMessage> {
         match msg {
             StatusMessage::Event(event) => {
+                if let Some(step) = event.step().filter(|s| !s.is_terminal()) {
+                    self.last_step = Some(step);
+                }
                 self.event = Some(event);
                 self.update_status_message();
                 Task::none()
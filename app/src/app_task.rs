@@ -1,17 +1,29 @@
-// use std::path::Path;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use anyhow::{Result, anyhow};
+use futures::Stream;
 use rfd::AsyncFileDialog;
 
-use cleaner::Cleaner;
+use cleaner::{
+    AdminElevator, AppCatalog, AppInfo, BundleKind, CancelToken, CatalogDetails, Cleaner,
+    IdentitySuggestion, OrphanGroup, PermissionReport, TrashFailure, TrashReport, total_size_of,
+};
 use status::StatusEmitter;
+use status::status_channel::StatusReceiver;
+use status::status_event::StatusEvent;
+
+/// Events of an operation as they come, the stream ends with the operation
+pub fn status_stream(rx: StatusReceiver) -> impl Stream<Item = StatusEvent> {
+    futures::stream::unfold(rx, |mut rx| async move {
+        rx.recv().await.map(|event| (event, rx))
+    })
+}
 
 pub async fn set_input_path() -> Result<Arc<PathBuf>> {
     let file = AsyncFileDialog::new()
         .set_title("Browse App")
-        .add_filter("Application", &["app"])
+        .add_filter("Application", &BundleKind::EXTENSIONS)
         .pick_file()
         .await
         .ok_or_else(|| anyhow!("No application selected"))?;
@@ -33,6 +45,48 @@ pub async fn add_app(path: PathBuf, status: Option<StatusEmitter>) -> Result<Cle
     tokio::task::spawn_blocking(move || Cleaner::new_app(&path, status.as_ref()))
         .await
         .map_err(|e| anyhow::anyhow!("Add application failed: {}", e))?
+        .map_err(anyhow::Error::from)
+}
+
+/// Size on disk of the listed paths, the walk runs on a blocking thread
+pub async fn total_size_async(paths: Vec<PathBuf>) -> u64 {
+    tokio::task::spawn_blocking(move || {
+        let paths: Vec<&Path> = paths.iter().map(PathBuf::as_path).collect();
+        total_size_of(&paths)
+    })
+    .await
+    .unwrap_or_default()
+}
+
+pub async fn add_identity_async(
+    name: String,
+    bundle_id: String,
+    executable: String,
+) -> Result<Cleaner> {
+    tokio::task::spawn_blocking(move || Cleaner::from_identity(&name, &bundle_id, &executable))
+        .await
+        .map_err(|e| anyhow::anyhow!("Add bundle id failed: {}", e))?
+        .map_err(anyhow::Error::from)
+}
+
+pub async fn identity_suggestions_async() -> Vec<IdentitySuggestion> {
+    // Suggestions are only a help, the form works without them
+    tokio::task::spawn_blocking(Cleaner::identity_suggestions)
+        .await
+        .unwrap_or_default()
+}
+
+pub async fn load_catalog_async() -> Result<AppCatalog> {
+    tokio::task::spawn_blocking(AppCatalog::new)
+        .await
+        .map_err(|e| anyhow::anyhow!("Loading applications failed: {}", e))
+}
+
+pub async fn load_catalog_details_async(infos: Vec<AppInfo>) -> Vec<(PathBuf, CatalogDetails)> {
+    // Details are only shown, an entry without them stays listed
+    tokio::task::spawn_blocking(move || CatalogDetails::load_all(&infos))
+        .await
+        .unwrap_or_default()
 }
 
 pub async fn kill_app_process_async(
@@ -42,34 +96,89 @@ pub async fn kill_app_process_async(
     tokio::task::spawn_blocking(move || cleaner.kill_app_process(status.as_ref()))
         .await
         .map_err(|e| anyhow::anyhow!("Confirm and kill process failed: {}", e))?
+        .map_err(anyhow::Error::from)
 }
 
 pub async fn scan_app_async(
     mut cleaner: Cleaner,
     status: Option<StatusEmitter>,
+    cancel: CancelToken,
 ) -> Result<Cleaner> {
     tokio::task::spawn_blocking(move || {
-        let _ = cleaner.scan_app_data(status.as_ref());
-        cleaner
+        // A failed scan is reported, not shown as a list that found nothing
+        let scanned = cleaner.scan_app_data(status.as_ref(), &cancel).map(|_| ());
+        scanned.map(|()| cleaner)
     })
     .await
-    .map_err(|e| anyhow::anyhow!("Scan failed: {}", e))
+    .map_err(|e| anyhow::anyhow!("Scan failed: {}", e))?
+    .map_err(anyhow::Error::from)
 }
 
 pub async fn open_loc_async(path: PathBuf) -> Result<()> {
     tokio::task::spawn_blocking(move || Cleaner::show_in_finder(&path))
         .await
         .map_err(|e| anyhow::anyhow!("Open location failed: {}", e))?
+        .map_err(anyhow::Error::from)
 }
 
-pub async fn save_bom_logs_async(cleaner: Cleaner, log_dir: PathBuf) -> Result<()> {
-    tokio::task::spawn_blocking(move || cleaner.save_bom_logs(&log_dir))
+pub async fn save_bom_logs_async(
+    cleaner: Cleaner,
+    log_dir: PathBuf,
+    cancel: CancelToken,
+) -> Result<()> {
+    tokio::task::spawn_blocking(move || cleaner.save_bom_logs(&log_dir, &cancel))
         .await
         .map_err(|e| anyhow::anyhow!("Save bom  logs failed: {}", e))?
+        .map_err(anyhow::Error::from)
+}
+
+pub async fn trash_app_async(
+    cleaner: Cleaner,
+    status: Option<StatusEmitter>,
+    cancel: CancelToken,
+) -> Result<TrashReport> {
+    tokio::task::spawn_blocking(move || cleaner.trash_all(status.as_ref(), &cancel))
+        .await
+        .map_err(|e| anyhow::anyhow!("Move to trash failed: {}", e))?
+        .map_err(anyhow::Error::from)
+}
+
+pub async fn scan_orphans_async(status: Option<StatusEmitter>) -> Result<Vec<OrphanGroup>> {
+    tokio::task::spawn_blocking(move || Cleaner::scan_orphans(status.as_ref()))
+        .await
+        .map_err(|e| anyhow::anyhow!("Leftovers scan failed: {}", e))
 }
 
-pub async fn trash_app_async(cleaner: Cleaner) -> Result<Vec<(PathBuf, String)>> {
-    tokio::task::spawn_blocking(move || Cleaner::trash_all(&cleaner))
+pub async fn trash_paths_async(
+    paths: Vec<PathBuf>,
+    status: Option<StatusEmitter>,
+    cancel: CancelToken,
+) -> Result<TrashReport> {
+    tokio::task::spawn_blocking(move || Cleaner::trash_paths(&paths, status.as_ref(), &cancel))
         .await
         .map_err(|e| anyhow::anyhow!("Move to trash failed: {}", e))?
+        .map_err(anyhow::Error::from)
+}
+
+pub async fn remove_with_admin_async(
+    failed: Vec<(PathBuf, TrashFailure)>,
+) -> Result<Vec<(PathBuf, TrashFailure)>> {
+    tokio::task::spawn_blocking(move || Cleaner::remove_with_admin(failed, &AdminElevator))
+        .await
+        .map_err(|e| anyhow::anyhow!("Remove with admin rights failed: {}", e))?
+        .map_err(anyhow::Error::from)
+}
+
+pub async fn open_full_disk_access_async() -> Result<()> {
+    tokio::task::spawn_blocking(Cleaner::open_full_disk_access_settings)
+        .await
+        .map_err(|e| anyhow::anyhow!("Open settings failed: {}", e))?
+        .map_err(anyhow::Error::from)
+}
+
+pub async fn probe_permissions_async() -> PermissionReport {
+    // A failed probe is the same as not knowing, no banner is shown then
+    tokio::task::spawn_blocking(Cleaner::probe_permissions)
+        .await
+        .unwrap_or_default()
 }
@@ -1,56 +1,134 @@
 use futures::StreamExt;
+use iced::widget::image;
 use iced::{Event, Subscription, Task, window};
-use std::collections::HashMap;
 use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
-use tokio_stream::wrappers::ReceiverStream;
+use std::time::{Duration, Instant};
 
+use cleaner::{CatalogSort, CleanerError, Refusal, TrashFailure, TrashRemedy, TrashReport};
 use common_debug::debug_dev;
-use status::setup_status_emitter;
-use status::status_event::StatusEvent;
+use status::status_channel::StatusReceiver;
+use status::status_event::{Phase, StatusEvent};
+use status::{StatusEmitter, StatusHandler, setup_status_emitter_with};
 
 // use crate::app_modal::modal_process_kill_dialog;
 use crate::app_modal::ModalAskMessage;
-use crate::app_state::{AppMessage, AppState};
+use crate::app_state::{AppMessage, AppState, CatalogView, IdentityField, IdentityForm};
 use crate::app_status::StatusMessage;
+use crate::app_task::identity_suggestions_async;
 use crate::app_task::kill_app_process_async;
+use crate::app_task::load_catalog_async;
+use crate::app_task::load_catalog_details_async;
+use crate::app_task::open_full_disk_access_async;
+use crate::app_task::probe_permissions_async;
+use crate::app_task::remove_with_admin_async;
 use crate::app_task::save_bom_logs_async;
 use crate::app_task::scan_app_async;
+use crate::app_task::scan_orphans_async;
 use crate::app_task::set_input_path;
 use crate::app_task::set_output_path;
+use crate::app_task::status_stream;
+use crate::app_task::total_size_async;
 use crate::app_task::trash_app_async;
-use crate::app_task::{add_app, open_loc_async};
+use crate::app_task::trash_paths_async;
+use crate::app_task::{add_app, add_identity_async, open_loc_async};
+
+/// Two clicks on the same application within this time open it
+const DOUBLE_CLICK: Duration = Duration::from_millis(400);
+
+/// Rows of the catalog shown before scrolling, their details are loaded first
+const CATALOG_PAGE: usize = 40;
+
+/// Load the details of the first `limit` rows shown that do not have them yet
+fn load_catalog_details(view: &mut CatalogView, limit: usize) -> Task<AppMessage> {
+    let infos: Vec<_> = view
+        .catalog
+        .missing_details(&view.query, limit)
+        .into_iter()
+        .filter(|info| view.pending.insert(info.path.clone()))
+        .collect();
+
+    if infos.is_empty() {
+        return Task::none();
+    }
+    Task::perform(
+        load_catalog_details_async(infos),
+        AppMessage::CatalogDetailsLoaded,
+    )
+}
+
+/// Sorting by size or last use needs the details of every entry
+fn catalog_sort_limit(sort: CatalogSort) -> usize {
+    match sort {
+        CatalogSort::Size | CatalogSort::LastOpened => usize::MAX,
+        CatalogSort::Name | CatalogSort::Version => CATALOG_PAGE,
+    }
+}
+
+/// Emitter of a new operation, its events also go to the status log
+fn status_emitter(state: &AppState) -> (StatusEmitter, StatusReceiver) {
+    let others: Vec<Arc<dyn StatusHandler>> = state
+        .status_log
+        .iter()
+        .map(|log| log.clone() as Arc<dyn StatusHandler>)
+        .collect();
+    setup_status_emitter_with(10, others)
+}
+
+/// Scope of the app under the root emitter of the operation, resolving, killing
+/// and scanning report in step scopes of it, like "Safari › Scanning files".
+/// Its status task ends once the operation drops the emitter
+fn start_app_operation(state: &mut AppState, app: String) -> Task<AppMessage> {
+    let (reporter, rx) = status_emitter(state);
+    state.operation = reporter.operation();
+    state.reporter = Some(reporter.child(app, 1.0));
+
+    Task::run(
+        status_stream(rx).map(|event| AppMessage::Status(StatusMessage::Event(event))),
+        |msg| msg,
+    )
+}
+
+/// Event made by the window itself, like the end of a scan, logged like the emitted ones
+fn gui_event(state: &AppState, event: StatusEvent) -> Task<AppMessage> {
+    if let Some(log) = &state.status_log {
+        log.handle_event(event.clone());
+    }
+    Task::done(AppMessage::Status(StatusMessage::Event(event)))
+}
 
 pub fn update(state: &mut AppState, message: AppMessage) -> Task<AppMessage> {
     match message {
         AppMessage::DropFile(path) => {
             state.reset();
-
-            let (reporter, rx) = setup_status_emitter(10);
+            let (generation, _) = state.start_scan();
+            let name = path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let status_task = start_app_operation(state, name);
+
+            let reporter = state
+                .reporter
+                .as_ref()
+                .map(|r| r.child("Resolving app", 0.1));
 
             state.input_file = path.clone();
             let add_app =
                 Task::perform(
-                    add_app(state.input_file.clone(), Some(reporter)),
-                    |res| match res {
-                        Ok(cleaner) => AppMessage::ConfirmKill(Ok(cleaner)),
-                        Err(err) => {
-                            let event = StatusEvent::new()
-                                .with_stage("Failed:")
-                                .with_message(err.to_string());
-                            AppMessage::Status(StatusMessage::Event(event))
-                        }
+                    add_app(state.input_file.clone(), reporter),
+                    move |res| match res {
+                        Ok(cleaner) => AppMessage::ConfirmKill(generation, Ok(cleaner)),
+                        Err(err) => AppMessage::Failed(generation, Arc::new(err)),
                     },
                 );
-            let rx_stream = ReceiverStream::new(rx);
 
-            let status_task = Task::run(
-                rx_stream.map(|event| AppMessage::Status(StatusMessage::Event(event))),
-                |msg| msg,
-            );
+            // Access may have been granted since the last probe
+            let probe_task =
+                Task::perform(probe_permissions_async(), AppMessage::PermissionsProbed);
 
-            Task::batch(vec![add_app, status_task])
+            Task::batch(vec![probe_task, add_app, status_task])
         }
 
         AppMessage::InputFile => {
@@ -65,8 +143,14 @@ pub fn update(state: &mut AppState, message: AppMessage) -> Task<AppMessage> {
             })
         }
 
-        AppMessage::ConfirmKill(result) => {
+        AppMessage::ConfirmKill(generation, result) => {
+            if generation != state.scan_generation {
+                return Task::none();
+            }
+
             if let Ok(cleaner) = result {
+                state.identity_form = None;
+
                 if !cleaner.app_data.app_process.is_empty() {
                     state.pending_cleaner = Some(cleaner);
 
@@ -77,7 +161,7 @@ pub fn update(state: &mut AppState, message: AppMessage) -> Task<AppMessage> {
                     ));
                     Task::none()
                 } else {
-                    Task::done(AppMessage::ScanApp(Ok(cleaner)))
+                    Task::done(AppMessage::ScanApp(generation, Ok(cleaner)))
                 }
             } else {
                 Task::none()
@@ -90,72 +174,93 @@ pub fn update(state: &mut AppState, message: AppMessage) -> Task<AppMessage> {
                     .show_modal_ask
                     .update(ModalAskMessage::ConfirmMsg(answer));
 
-                let cleaner = state.pending_cleaner.take().unwrap();
+                // Cancelled while the question was shown
+                let Some(cleaner) = state.pending_cleaner.take() else {
+                    return Task::none();
+                };
+                let generation = state.scan_generation;
                 if !answer {
-                    return Task::done(AppMessage::ScanApp(Ok(cleaner)));
+                    return Task::done(AppMessage::ScanApp(generation, Ok(cleaner)));
                 }
 
-                let (reporter, rx) = setup_status_emitter(10);
+                let reporter = state
+                    .reporter
+                    .as_ref()
+                    .map(|r| r.child("Killing processes", 0.1));
                 let cleaner_arc = Arc::new(cleaner);
 
-                let confirm_task = Task::perform(
-                    kill_app_process_async(cleaner_arc.clone(), Some(reporter)),
+                Task::perform(
+                    kill_app_process_async(cleaner_arc.clone(), reporter),
                     move |res| match res {
-                        Ok(()) => AppMessage::ScanApp(Ok(
-                            Arc::try_unwrap(cleaner_arc).unwrap_or_else(|c| (*c).clone())
-                        )),
-                        Err(err) => AppMessage::ScanApp(Err(err.to_string())),
+                        Ok(()) => AppMessage::ScanApp(
+                            generation,
+                            Ok(Arc::try_unwrap(cleaner_arc).unwrap_or_else(|c| (*c).clone())),
+                        ),
+                        Err(err) => AppMessage::ScanApp(generation, Err(err.to_string())),
                     },
-                );
-
-                let status_task = Task::run(
-                    ReceiverStream::new(rx)
-                        .map(|event| AppMessage::Status(StatusMessage::Event(event))),
-                    |msg| msg,
-                );
-
-                Task::batch(vec![confirm_task, status_task])
+                )
             }
         },
 
-        AppMessage::ScanApp(cleaner) => {
-            if let Ok(app_input) = cleaner {
-                let (reporter, rx) = setup_status_emitter(10);
-
-                let scan_task =
-                    Task::perform(scan_app_async(app_input, Some(reporter)), |res| match res {
-                        Ok(cleaner) => AppMessage::UpdateCleaner(cleaner),
-                        Err(err) => {
-                            let event = StatusEvent::new()
-                                .with_stage("Failed:")
-                                .with_message(err.to_string());
-                            AppMessage::Status(StatusMessage::Event(event))
-                        }
-                    });
-
-                let rx_stream = ReceiverStream::new(rx);
-
-                let progress_task = Task::run(
-                    rx_stream.map(|event| AppMessage::Status(StatusMessage::Event(event))),
-                    |msg| msg,
-                );
-
-                return Task::batch(vec![scan_task, progress_task]);
+        AppMessage::ScanApp(generation, cleaner) => {
+            // A newer scan started or this one was cancelled
+            let Some(cancel) = state.cancel.clone() else {
+                return Task::none();
+            };
+            if generation != state.scan_generation {
+                return Task::none();
             }
-            Task::none()
+
+            let app_input = match cleaner {
+                Ok(app_input) => app_input,
+                // Killing the processes failed, there is no scan, the status line tells why
+                Err(reason) => {
+                    let err = anyhow::anyhow!(reason);
+                    return Task::done(AppMessage::Failed(generation, Arc::new(err)));
+                }
+            };
+
+            // What is left of the operation
+            let reporter = state
+                .reporter
+                .as_ref()
+                .map(|r| r.child("Scanning files", 1.0));
+
+            Task::perform(
+                scan_app_async(app_input, reporter, cancel),
+                move |res| match res {
+                    Ok(cleaner) => AppMessage::UpdateCleaner(generation, cleaner),
+                    Err(err) => AppMessage::Failed(generation, Arc::new(err)),
+                },
+            )
         }
 
-        AppMessage::UpdateCleaner(cleaner) => {
+        AppMessage::UpdateCleaner(generation, cleaner) => {
+            // Results of a superseded scan
+            if generation != state.scan_generation {
+                return Task::none();
+            }
+            state.cancel = None;
+            // The status events of the operation end here
+            state.reporter = None;
             state.cleaner = cleaner;
+
+            // Build the handle once, a new handle every view would upload the icon again
+            state.app_icon =
+                state.cleaner.app_data.icon.as_ref().map(|icon| {
+                    image::Handle::from_rgba(icon.width, icon.height, icon.rgba.clone())
+                });
+
             let founded = state
                 .cleaner
                 .app_data
                 .all_associate_entries_enumerate()
                 .len();
             let event = StatusEvent::new()
-                .with_stage("Completed:")
+                .with_operation(state.operation)
+                .with_phase(Phase::Done)
                 .with_message(format!("{} item founded", founded));
-            Task::done(AppMessage::Status(StatusMessage::Event(event)))
+            gui_event(state, event)
         }
 
         AppMessage::OpenSelectedPath(index) => {
@@ -200,75 +305,176 @@ pub fn update(state: &mut AppState, message: AppMessage) -> Task<AppMessage> {
                 Path::new(&home).join("Desktop")
             };
             let cleaner = state.cleaner.clone();
-            Task::perform(save_bom_logs_async(cleaner, output_dir), |res| match res {
-                Ok(()) => {
-                    let event = StatusEvent::new().with_message("Bom file saved".to_string());
-                    AppMessage::Status(StatusMessage::Event(event))
-                }
-                Err(err) => {
-                    let event = StatusEvent::new().with_message(err.to_string());
-                    AppMessage::Status(StatusMessage::Event(event))
-                }
-            })
+            let cancel = state.start_operation();
+            let generation = state.scan_generation;
+            let event = StatusEvent::new().with_phase(Phase::ExportingBom { saved: None });
+            Task::batch(vec![
+                gui_event(state, event),
+                Task::perform(
+                    save_bom_logs_async(cleaner, output_dir, cancel),
+                    move |res| match res {
+                        Ok(()) => AppMessage::BomLogsSaved,
+                        Err(err) => AppMessage::Failed(generation, Arc::new(err)),
+                    },
+                ),
+            ])
+        }
+
+        AppMessage::BomLogsSaved => {
+            state.cancel = None;
+            let saved = state.cleaner.app_data.log.bom_file.len();
+            let event = StatusEvent::new().with_phase(Phase::ExportingBom { saved: Some(saved) });
+            gui_event(state, event)
         }
 
         AppMessage::TrashApp => {
             let cleaner = state.cleaner.clone();
-            Task::perform(trash_app_async(cleaner), |res| match res {
-                Ok(failed) => AppMessage::DeletedApp(Ok(failed)),
-                Err(err) => AppMessage::DeletedApp(Err(err.to_string())),
-            })
+            let cancel = state.start_operation();
+            let generation = state.scan_generation;
+            let (reporter, rx) = status_emitter(state);
+
+            let trash_task = Task::perform(
+                trash_app_async(cleaner, Some(reporter), cancel),
+                move |res| match res {
+                    Ok(report) => AppMessage::DeletedApp(report),
+                    Err(err) => AppMessage::Failed(generation, Arc::new(err)),
+                },
+            );
+            let progress_task = Task::run(
+                status_stream(rx).map(|event| AppMessage::Status(StatusMessage::Event(event))),
+                |msg| msg,
+            );
+
+            Task::batch(vec![trash_task, progress_task])
         }
 
-        AppMessage::DeletedApp(result) => {
-            match result {
-                Ok(failed_paths) => {
-                    if failed_paths.is_empty() {
-                        state.reset();
-                        state.status.message = Some("App moved to Trash".to_string());
-                    } else {
-                        let failed_clone = failed_paths.clone();
-                        state.cleaner.app_data.associate_files = failed_paths
-                            .into_iter()
-                            .map(|(path, _reason)| {
-                                let label = path
-                                    .file_name()
-                                    .map(|n| n.to_string_lossy().to_string())
-                                    .unwrap_or_else(|| path.to_string_lossy().to_string());
-                                (path, label)
-                            })
-                            .collect();
-
-                        // Build the message from the actual failed paths
-                        // group by reason
-                        let mut grouped_reason: HashMap<String, Vec<PathBuf>> = HashMap::new();
-
-                        for (path, reason) in failed_clone {
-                            grouped_reason.entry(reason).or_default().push(path);
-                        }
-
-                        // build short grouped report message
-                        let report = grouped_reason
-                            .iter()
-                            .map(|(reason, paths)| {
-                                format!("{} items failed: {}", paths.len(), reason)
-                            })
-                            .collect::<Vec<_>>()
-                            .join("\n");
-
-                        state.status.message = Some(report);
-                    }
+        AppMessage::DeletedApp(report) => {
+            state.cancel = None;
+
+            if state.orphan_mode {
+                // Trashed groups leave the list, the failed, refused and skipped ones stay
+                state.orphans.retain(|(group, _)| {
+                    group.paths.iter().any(|p| {
+                        report.failed.iter().any(|(f, _)| f == p)
+                            || report.refused.iter().any(|(r, _)| r == p)
+                            || report.skipped.contains(p)
+                    })
+                });
+            }
+
+            if report.is_clean() {
+                if state.orphan_mode {
+                    state.trash_failures.clear();
+                    state.trash_refused.clear();
+                    state.trash_remedies.clear();
+                    state.status.message = Some("Leftovers moved to Trash".to_string());
+                } else {
+                    state.reset();
+                    state.status.message = Some("App moved to Trash".to_string());
                 }
-                Err(err_msg) => {
-                    let event = StatusEvent::new()
-                        .with_stage("Failed:")
-                        .with_message(err_msg);
-                    let _ = state.status.update(StatusMessage::Event(event));
+                return Task::none();
+            }
+
+            // The size of what is left is walked off the UI thread
+            let size_task = if state.orphan_mode {
+                Task::none()
+            } else {
+                state.cleaner.app_data.retain_unremoved(&report);
+                let generation = state.scan_generation;
+                Task::perform(
+                    total_size_async(state.cleaner.app_data.associate_paths()),
+                    move |size| AppMessage::TotalSize(generation, size),
+                )
+            };
+            let TrashReport {
+                failed,
+                refused,
+                skipped,
+            } = report;
+            state.trash_failures = failed.clone();
+            state.trash_refused = refused.clone();
+
+            // Group by reason, keeping the order they failed in
+            let mut grouped_reason: Vec<(TrashFailure, Vec<PathBuf>)> = Vec::new();
+            for (path, failure) in failed {
+                match grouped_reason.iter_mut().find(|(f, _)| *f == failure) {
+                    Some((_, paths)) => paths.push(path),
+                    None => grouped_reason.push((failure, vec![path])),
                 }
             }
+
+            // One action per remedy, acting on the first item that needs it
+            state.trash_remedies.clear();
+            for (failure, paths) in &grouped_reason {
+                let remedy = failure.remedy();
+                if !state.trash_remedies.iter().any(|(r, _)| *r == remedy) {
+                    state.trash_remedies.push((remedy, paths[0].clone()));
+                }
+            }
+            state.show_full_disk_access = state
+                .trash_remedies
+                .iter()
+                .any(|(r, _)| *r == TrashRemedy::OpenFullDiskAccess);
+
+            // Refused items first, they were kept on purpose and never tried
+            let mut grouped_refusal: Vec<(Refusal, usize)> = Vec::new();
+            for (_, refusal) in &refused {
+                match grouped_refusal.iter_mut().find(|(r, _)| r == refusal) {
+                    Some((_, count)) => *count += 1,
+                    None => grouped_refusal.push((*refusal, 1)),
+                }
+            }
+
+            // build short grouped report message
+            let report = grouped_refusal
+                .iter()
+                .map(|(refusal, count)| {
+                    format!("{} items kept: {}. {}", count, refusal, refusal.hint())
+                })
+                .chain(grouped_reason.iter().map(|(failure, paths)| {
+                    format!(
+                        "{} items failed: {}. {}",
+                        paths.len(),
+                        failure,
+                        failure.hint()
+                    )
+                }))
+                .chain(
+                    (!skipped.is_empty())
+                        .then(|| format!("{} items left, the move was cancelled", skipped.len())),
+                )
+                .collect::<Vec<_>>()
+                .join("\n");
+
+            state.status.message = Some(report);
+            size_task
+        }
+
+        AppMessage::TotalSize(generation, size) => {
+            if generation == state.scan_generation {
+                state.cleaner.app_data.total_size = size;
+            }
             Task::none()
         }
 
+        AppMessage::RetryWithAdmin => {
+            let failed = state.trash_failures.clone();
+            let refused = state.trash_refused.clone();
+            let generation = state.scan_generation;
+            Task::perform(remove_with_admin_async(failed), move |res| match res {
+                Ok(failed) => AppMessage::DeletedApp(TrashReport {
+                    failed,
+                    refused,
+                    ..Default::default()
+                }),
+                Err(err) => AppMessage::Failed(generation, Arc::new(err)),
+            })
+        }
+
+        AppMessage::RevealInFinder(path) => {
+            Task::perform(open_loc_async(path), |_| AppMessage::NoOperations)
+        }
+
         AppMessage::ClearList => {
             state.reset();
             Task::none()
@@ -276,6 +482,302 @@ pub fn update(state: &mut AppState, message: AppMessage) -> Task<AppMessage> {
 
         AppMessage::Status(msg) => state.status.update(msg).map(AppMessage::Status),
 
+        AppMessage::Cancel => {
+            state.cancel_running();
+            state.pending_cleaner = None;
+            state.show_modal_ask.hide();
+            state.status.message = Some("Cancelled".to_string());
+            Task::none()
+        }
+
+        AppMessage::Failed(generation, err) => {
+            // A newer scan or a reset came since, the token is not this operation's
+            if generation != state.scan_generation {
+                return Task::none();
+            }
+            // Cancel already told the user
+            if matches!(
+                err.downcast_ref::<CleanerError>(),
+                Some(CleanerError::Cancelled)
+            ) {
+                return Task::none();
+            }
+            state.cancel = None;
+            state.reporter = None;
+
+            // Explain what went wrong and how to fix it when the error is known
+            let message = match err.downcast_ref::<CleanerError>() {
+                Some(cleaner_err) => {
+                    state.show_full_disk_access = cleaner_err.needs_full_disk_access();
+                    match cleaner_err.hint() {
+                        Some(hint) => format!("{}\n{}", cleaner_err, hint),
+                        None => cleaner_err.to_string(),
+                    }
+                }
+                None => err.to_string(),
+            };
+
+            let event = StatusEvent::new()
+                .with_operation(state.operation)
+                .with_phase(Phase::Failed { reason: message });
+            if let Some(log) = &state.status_log {
+                log.handle_event(event.clone());
+            }
+            state
+                .status
+                .update(StatusMessage::Event(event))
+                .map(AppMessage::Status)
+        }
+
+        AppMessage::OpenFullDiskAccess => {
+            Task::perform(open_full_disk_access_async(), |_| AppMessage::NoOperations)
+        }
+
+        AppMessage::PermissionsProbed(report) => {
+            debug_dev!("Denied capabilities: {:?}", report.denied());
+            state.permissions = report;
+            Task::none()
+        }
+
+        AppMessage::FindOrphans => {
+            state.reset();
+            state.orphan_mode = true;
+            let generation = state.scan_generation;
+
+            let (reporter, rx) = status_emitter(state);
+
+            let scan_task =
+                Task::perform(scan_orphans_async(Some(reporter)), move |res| match res {
+                    Ok(orphans) => AppMessage::OrphansFound(orphans),
+                    Err(err) => AppMessage::Failed(generation, Arc::new(err)),
+                });
+
+            let status_task = Task::run(
+                status_stream(rx).map(|event| AppMessage::Status(StatusMessage::Event(event))),
+                |msg| msg,
+            );
+
+            Task::batch(vec![scan_task, status_task])
+        }
+
+        AppMessage::OrphansFound(orphans) => {
+            state.orphans = orphans.into_iter().map(|group| (group, false)).collect();
+
+            let event = StatusEvent::new()
+                .with_phase(Phase::Done)
+                .with_message(format!("{} leftovers founded", state.orphans.len()));
+            gui_event(state, event)
+        }
+
+        AppMessage::ToggleOrphan(index) => {
+            if let Some((_, selected)) = state.orphans.get_mut(index) {
+                *selected = !*selected;
+            }
+            Task::none()
+        }
+
+        AppMessage::TrashOrphans => {
+            let paths: Vec<PathBuf> = state
+                .orphans
+                .iter()
+                .filter(|(_, selected)| *selected)
+                .flat_map(|(group, _)| group.paths.clone())
+                .collect();
+
+            if paths.is_empty() {
+                state.status.message = Some("Select the leftovers to move to Trash".to_string());
+                return Task::none();
+            }
+
+            let cancel = state.start_operation();
+            let generation = state.scan_generation;
+            let (reporter, rx) = status_emitter(state);
+
+            let trash_task = Task::perform(
+                trash_paths_async(paths, Some(reporter), cancel),
+                move |res| match res {
+                    Ok(report) => AppMessage::DeletedApp(report),
+                    Err(err) => AppMessage::Failed(generation, Arc::new(err)),
+                },
+            );
+            let progress_task = Task::run(
+                status_stream(rx).map(|event| AppMessage::Status(StatusMessage::Event(event))),
+                |msg| msg,
+            );
+
+            Task::batch(vec![trash_task, progress_task])
+        }
+
+        AppMessage::ShowIdentityForm => {
+            state.reset();
+            state.identity_form = Some(IdentityForm::default());
+
+            Task::perform(
+                identity_suggestions_async(),
+                AppMessage::IdentitySuggestions,
+            )
+        }
+
+        AppMessage::IdentitySuggestions(suggestions) => {
+            if let Some(form) = &mut state.identity_form {
+                form.suggestions = suggestions;
+            }
+            Task::none()
+        }
+
+        AppMessage::IdentityInput(field, value) => {
+            if let Some(form) = &mut state.identity_form {
+                match field {
+                    IdentityField::Name => form.name = value,
+                    IdentityField::BundleId => form.bundle_id = value,
+                    IdentityField::Executable => form.executable = value,
+                }
+            }
+            Task::none()
+        }
+
+        AppMessage::PickSuggestion(bundle_id) => {
+            if let Some(form) = &mut state.identity_form {
+                form.bundle_id = bundle_id;
+            }
+            Task::none()
+        }
+
+        AppMessage::ScanIdentity => {
+            // The form stays open until the bundle id was accepted
+            let Some(form) = &state.identity_form else {
+                return Task::none();
+            };
+            let add_identity = add_identity_async(
+                form.name.clone(),
+                form.bundle_id.clone(),
+                form.executable.clone(),
+            );
+            let name = if form.name.trim().is_empty() {
+                form.bundle_id.trim().to_string()
+            } else {
+                form.name.trim().to_string()
+            };
+            let (generation, _) = state.start_scan();
+            let status_task = start_app_operation(state, name);
+
+            let add_task = Task::perform(add_identity, move |res| match res {
+                Ok(cleaner) => AppMessage::ConfirmKill(generation, Ok(cleaner)),
+                Err(err) => AppMessage::Failed(generation, Arc::new(err)),
+            });
+
+            Task::batch(vec![add_task, status_task])
+        }
+
+        AppMessage::ShowCatalog => {
+            state.reset();
+            state.catalog = Some(CatalogView {
+                loading: true,
+                ascending: true,
+                ..Default::default()
+            });
+            state.status.message = Some("Loading installed applications...".to_string());
+            let generation = state.scan_generation;
+
+            Task::perform(load_catalog_async(), move |res| match res {
+                Ok(catalog) => AppMessage::CatalogLoaded(catalog),
+                Err(err) => AppMessage::Failed(generation, Arc::new(err)),
+            })
+        }
+
+        AppMessage::CatalogLoaded(catalog) => {
+            // The user may have moved on while it was loading
+            let Some(view) = &mut state.catalog else {
+                return Task::none();
+            };
+
+            view.catalog = catalog;
+            view.catalog.sort_by(view.sort, view.ascending);
+            view.loading = false;
+
+            state.status.message = Some(format!(
+                "{} applications installed",
+                view.catalog.entries.len()
+            ));
+            load_catalog_details(view, catalog_sort_limit(view.sort))
+        }
+
+        AppMessage::CatalogDetailsLoaded(details) => {
+            let Some(view) = &mut state.catalog else {
+                return Task::none();
+            };
+
+            for (path, entry) in &details {
+                view.pending.remove(path);
+                if let Some(icon) = &entry.icon {
+                    let handle =
+                        image::Handle::from_rgba(icon.width, icon.height, icon.rgba.clone());
+                    view.icons.insert(path.clone(), handle);
+                }
+            }
+            view.catalog.set_details(details);
+
+            // The order depends on what was just loaded
+            if matches!(view.sort, CatalogSort::Size | CatalogSort::LastOpened)
+                && view.pending.is_empty()
+            {
+                view.catalog.sort_by(view.sort, view.ascending);
+            }
+            Task::none()
+        }
+
+        AppMessage::CatalogQuery(query) => {
+            let Some(view) = &mut state.catalog else {
+                return Task::none();
+            };
+            view.query = query;
+            load_catalog_details(view, CATALOG_PAGE)
+        }
+
+        AppMessage::CatalogSortBy(sort) => {
+            let Some(view) = &mut state.catalog else {
+                return Task::none();
+            };
+            // Clicking the same column again flips the order
+            view.ascending = view.sort != sort || !view.ascending;
+            view.sort = sort;
+            view.catalog.sort_by(view.sort, view.ascending);
+            load_catalog_details(view, catalog_sort_limit(sort))
+        }
+
+        AppMessage::CatalogClicked(path) => {
+            let Some(view) = &mut state.catalog else {
+                return Task::none();
+            };
+
+            let double_click = view
+                .last_click
+                .as_ref()
+                .map(|(last, at)| *last == path && at.elapsed() < DOUBLE_CLICK)
+                .unwrap_or(false);
+
+            if double_click {
+                return Task::done(AppMessage::DropFile(path));
+            }
+
+            // The selected row gets its details even when scrolled to
+            let missing = view
+                .catalog
+                .entry(&path)
+                .filter(|entry| entry.details.is_none())
+                .map(|entry| entry.info.clone());
+            let details_task = match missing {
+                Some(info) if view.pending.insert(path.clone()) => Task::perform(
+                    load_catalog_details_async(vec![info]),
+                    AppMessage::CatalogDetailsLoaded,
+                ),
+                _ => Task::none(),
+            };
+
+            view.last_click = Some((path, Instant::now()));
+            details_task
+        }
+
         AppMessage::NoOperations => Task::none(),
     }
 }
@@ -1,17 +1,21 @@
 use iced::widget::Column;
+use iced::widget::Image;
 use iced::widget::Stack;
 use iced::{
-    Background, Border, Color, Padding, alignment,
-    widget::{Container, Row, Text, container, row, text},
+    Background, Border, Color, Padding, Theme, alignment,
+    widget::{Container, Row, Text, container, row, scrollable, text, text_input},
 };
 use iced::{Element, Length};
 
-use crate::app_state::{AppMessage, AppState};
+use crate::app_state::{AppMessage, AppState, CatalogView, IdentityField, IdentityForm};
+use cleaner::{CatalogSort, InstallSource, TrashRemedy, format_age, format_size};
+use status::status_event::{StatusEvent, Step};
 use widget::button_style::{
     CustomButton, blank_border_style, blank_btn_style, custom_btn_rounded_style, danger_style,
 };
 use widget::drop_file::DropFile;
 use widget::list_view::{HeaderContent, HeaderWidget, ListView, RowContent, WidgetContent};
+use widget::progress_bar::ProgressBar;
 
 pub fn view(state: &AppState) -> Element<'_, AppMessage> {
     let drop_zone: Element<AppMessage> = DropFile::widget(|| {
@@ -107,7 +111,13 @@ pub fn view(state: &AppState) -> Element<'_, AppMessage> {
         })
         .view();
 
-    let center_view = if !has_real_items {
+    let center_view = if let Some(catalog) = &state.catalog {
+        catalog_list(catalog)
+    } else if let Some(form) = &state.identity_form {
+        identity_form(form)
+    } else if state.orphan_mode {
+        orphan_list(state)
+    } else if !has_real_items {
         drop_zone
     } else {
         list_view
@@ -216,6 +226,45 @@ pub fn view(state: &AppState) -> Element<'_, AppMessage> {
     .padding([3, 20])
     .align_y(alignment::Vertical::Center);
 
+    let button_find_orphans = Container::new(
+        CustomButton::new("Find leftovers")
+            .text_align_y(alignment::Vertical::Center)
+            .text_align_x(alignment::Horizontal::Center)
+            .width(Length::Fill)
+            .style(blank_border_style)
+            .on_press(AppMessage::FindOrphans)
+            .view(),
+    )
+    .width(Length::Shrink)
+    .padding([3, 0])
+    .align_y(alignment::Vertical::Center);
+
+    let button_identity = Container::new(
+        CustomButton::new("Bundle ID")
+            .text_align_y(alignment::Vertical::Center)
+            .text_align_x(alignment::Horizontal::Center)
+            .width(Length::Fill)
+            .style(blank_border_style)
+            .on_press(AppMessage::ShowIdentityForm)
+            .view(),
+    )
+    .width(Length::Shrink)
+    .padding([3, 0])
+    .align_y(alignment::Vertical::Center);
+
+    let button_catalog = Container::new(
+        CustomButton::new("Applications")
+            .text_align_y(alignment::Vertical::Center)
+            .text_align_x(alignment::Horizontal::Center)
+            .width(Length::Fill)
+            .style(blank_border_style)
+            .on_press(AppMessage::ShowCatalog)
+            .view(),
+    )
+    .width(Length::Shrink)
+    .padding([3, 0])
+    .align_y(alignment::Vertical::Center);
+
     let status_msg = state
         .status
         .view(|message_status| {
@@ -248,7 +297,11 @@ pub fn view(state: &AppState) -> Element<'_, AppMessage> {
             .text_align_x(alignment::Horizontal::Center)
             .text_align_y(alignment::Vertical::Center)
             .width(Length::Fill)
-            .on_press(AppMessage::TrashApp)
+            .on_press(if state.orphan_mode {
+                AppMessage::TrashOrphans
+            } else {
+                AppMessage::TrashApp
+            })
             .style(danger_style)
             .view(),
     )
@@ -266,13 +319,31 @@ pub fn view(state: &AppState) -> Element<'_, AppMessage> {
     let top = Container::new(
         Row::new()
             .push(bom_output)
+            .push(button_find_orphans)
+            .push(button_identity)
+            .push(button_catalog)
             .push(button_clear_list)
             .width(Length::Fill)
             .height(Length::Shrink),
     );
 
+    let mut center_column = Column::new();
+    if has_real_items {
+        // Warn before the cleanup, not after it partly failed
+        if let Some(banner) = permission_banner(state) {
+            center_column = center_column.push(banner);
+        }
+        if let Some(banner) = homebrew_banner(state) {
+            center_column = center_column.push(banner);
+        }
+        if let Some(banner) = scan_warnings_banner(state) {
+            center_column = center_column.push(banner);
+        }
+        center_column = center_column.push(app_header(state));
+    }
+
     let center = Container::new(
-        Column::new()
+        center_column
             .push(center_view)
             .width(Length::Fill)
             .height(Length::Fill)
@@ -293,24 +364,104 @@ pub fn view(state: &AppState) -> Element<'_, AppMessage> {
         shadow: Default::default(),
     });
 
-    let bottom = Container::new(
-        row![status_msg, button_delete,]
-            .align_y(alignment::Vertical::Center)
-            .spacing(5),
-    )
-    .width(Length::Fill)
-    .align_x(alignment::Horizontal::Center)
-    .align_y(alignment::Vertical::Center)
-    .padding(Padding {
-        top: 6.0,
-        bottom: 6.0,
-        left: 12.0,
-        right: 12.0,
-    });
+    let mut bottom_row = row![status_msg]
+        .align_y(alignment::Vertical::Center)
+        .spacing(5);
+
+    // How far the file scan or the move to Trash is
+    if let Some(progress) = progress_bar(state) {
+        bottom_row = bottom_row.push(progress);
+    }
+
+    // Stops the running scan, export or move to Trash
+    if state.cancel.is_some() {
+        bottom_row = bottom_row.push(
+            Container::new(
+                CustomButton::new("Cancel")
+                    .text_align_x(alignment::Horizontal::Center)
+                    .text_align_y(alignment::Vertical::Center)
+                    .width(Length::Fill)
+                    .on_press(AppMessage::Cancel)
+                    .style(custom_btn_rounded_style)
+                    .view(),
+            )
+            .width(Length::Shrink),
+        );
+    }
+
+    // Guide to the settings when macOS privacy protection denied access
+    if state.show_full_disk_access {
+        bottom_row = bottom_row.push(
+            Container::new(
+                CustomButton::new("Open Full Disk Access")
+                    .text_align_x(alignment::Horizontal::Center)
+                    .text_align_y(alignment::Vertical::Center)
+                    .width(Length::Fill)
+                    .on_press(AppMessage::OpenFullDiskAccess)
+                    .style(custom_btn_rounded_style)
+                    .view(),
+            )
+            .width(Length::Shrink),
+        );
+    }
+
+    // Root-owned items, authorization is asked once for all of them
+    if state
+        .trash_remedies
+        .iter()
+        .any(|(remedy, _)| *remedy == TrashRemedy::RetryWithAdmin)
+    {
+        bottom_row = bottom_row.push(
+            Container::new(
+                CustomButton::new("Retry with admin rights")
+                    .text_align_x(alignment::Horizontal::Center)
+                    .text_align_y(alignment::Vertical::Center)
+                    .width(Length::Fill)
+                    .on_press(AppMessage::RetryWithAdmin)
+                    .style(custom_btn_rounded_style)
+                    .view(),
+            )
+            .width(Length::Shrink),
+        );
+    }
+
+    // Items that have to be removed by hand
+    if let Some((_, path)) = state
+        .trash_remedies
+        .iter()
+        .find(|(remedy, _)| *remedy == TrashRemedy::RevealInFinder)
+    {
+        bottom_row = bottom_row.push(
+            Container::new(
+                CustomButton::new("Reveal in Finder")
+                    .text_align_x(alignment::Horizontal::Center)
+                    .text_align_y(alignment::Vertical::Center)
+                    .width(Length::Fill)
+                    .on_press(AppMessage::RevealInFinder(path.clone()))
+                    .style(custom_btn_rounded_style)
+                    .view(),
+            )
+            .width(Length::Shrink),
+        );
+    }
+
+    let bottom = Container::new(bottom_row.push(button_delete))
+        .width(Length::Fill)
+        .align_x(alignment::Horizontal::Center)
+        .align_y(alignment::Vertical::Center)
+        .padding(Padding {
+            top: 6.0,
+            bottom: 6.0,
+            left: 12.0,
+            right: 12.0,
+        });
 
-    let content: Element<_> = Column::new()
-        .push(top)
-        .push(center)
+    let mut content = Column::new().push(top).push(center);
+    if let Some(steps) = step_indicator(state) {
+        content = content.push(steps);
+    }
+
+    let content: Element<_> = content
         .push(bottom)
         .width(Length::Fill)
         .height(Length::Fill)
@@ -325,3 +476,561 @@ pub fn view(state: &AppState) -> Element<'_, AppMessage> {
         content
     }
 }
+
+/// Bar of the whole app operation, or of the file scan or the move to Trash
+/// while it runs with a known total
+fn progress_bar(state: &AppState) -> Option<Element<'_, AppMessage>> {
+    state.cancel.as_ref()?;
+    let event = state.status.event.as_ref()?;
+    let fraction = match event.overall {
+        Some(overall) => overall,
+        None if matches!(event.step()?, Step::ScanningFiles | Step::Trashing) => {
+            event.fraction()?
+        }
+        None => return None,
+    };
+
+    Some(
+        ProgressBar::new(Some(fraction))
+            .label(progress_label(event, fraction))
+            .width(Length::Fixed(220.0))
+            .view(),
+    )
+}
+
+/// Like "40% · 1200 items/s · 12 s left"
+fn progress_label(event: &StatusEvent, fraction: f32) -> String {
+    let mut parts = vec![format!("{:.0}%", fraction * 100.0)];
+    if let Some(rate) = event.rate {
+        parts.push(format!("{:.0} items/s", rate));
+    }
+    if let Some(eta) = event.eta {
+        let secs = eta.as_secs();
+        parts.push(if secs >= 60 {
+            format!("{} min {} s left", secs / 60, secs % 60)
+        } else {
+            format!("{} s left", secs)
+        });
+    }
+    parts.join(" · ")
+}
+
+/// Steps of the app scan, the finished ones lit and the running one highlighted.
+/// Only shown while a scan reports its phases
+fn step_indicator(state: &AppState) -> Option<Element<'_, AppMessage>> {
+    let step = state.status.event.as_ref()?.step()?;
+    if state.orphan_mode {
+        return None;
+    }
+
+    // A failure stops at the step that was running
+    let current = match step {
+        Step::Failed => state.status.last_step.unwrap_or(Step::ResolvingApp),
+        Step::KillingProcesses => Step::ScanningProcesses,
+        step => step,
+    };
+    let position = Step::SCAN.iter().position(|s| *s == current)?;
+
+    let mut steps = Row::new().spacing(12).align_y(alignment::Vertical::Center);
+    for (i, s) in Step::SCAN.iter().enumerate() {
+        let color = if i == position && step == Step::Failed {
+            Color::from_rgb8(220, 90, 80)
+        } else if i == position && step != Step::Done {
+            Color::from_rgb8(90, 160, 255)
+        } else if i <= position {
+            Color::from_rgb8(200, 200, 200)
+        } else {
+            Color::from_rgb8(110, 110, 110)
+        };
+
+        let marker = if i < position || step == Step::Done {
+            "●"
+        } else {
+            "○"
+        };
+        steps = steps.push(
+            text(format!("{} {}", marker, s.label()))
+                .size(12)
+                .style(move |_| text::Style { color: Some(color) }),
+        );
+    }
+
+    Some(
+        container(steps)
+            .width(Length::Fill)
+            .align_x(alignment::Horizontal::Center)
+            .into(),
+    )
+}
+
+/// Icon and metadata of the dropped app, shown above the list of files
+fn app_header(state: &AppState) -> Element<'_, AppMessage> {
+    let app = &state.cleaner.app_data.app;
+
+    let icon: Element<AppMessage> = if let Some(handle) = &state.app_icon {
+        Image::new(handle.clone())
+            .width(Length::Fixed(48.0))
+            .height(Length::Fixed(48.0))
+            .into()
+    } else {
+        Container::new(text(""))
+            .width(Length::Fixed(48.0))
+            .height(Length::Fixed(48.0))
+            .into()
+    };
+
+    let title = match &app.version {
+        Some(version) => format!("{} {}", app.name, version),
+        None => app.name.clone(),
+    };
+
+    let details = Column::new()
+        .push(Text::new(title).size(16).color(Color::WHITE))
+        .push(
+            Text::new(&app.bundle_id)
+                .size(11)
+                .color(Color::from_rgb8(150, 150, 150)),
+        )
+        .push(
+            Text::new(if app.path.as_os_str().is_empty() {
+                "App bundle already deleted".to_string()
+            } else {
+                app.path.display().to_string()
+            })
+            .size(11)
+            .color(Color::from_rgb8(3, 161, 252)),
+        )
+        .width(Length::Fill)
+        .spacing(2);
+
+    // Nothing tells how a deleted app was installed unless a receipt or cask is left
+    let source_known =
+        !app.path.as_os_str().is_empty() || app.install_source != InstallSource::DragInstall;
+    let details = if source_known {
+        details.push(
+            Text::new(app.install_source.describe())
+                .size(11)
+                .color(Color::from_rgb8(150, 150, 150)),
+        )
+    } else {
+        details
+    };
+
+    let total_size = Text::new(format_size(state.cleaner.app_data.total_size))
+        .size(14)
+        .color(Color::from_rgb8(200, 200, 200));
+
+    Container::new(
+        row![icon, details, total_size]
+            .spacing(10)
+            .align_y(alignment::Vertical::Center),
+    )
+    .width(Length::Fill)
+    .padding([5, 10])
+    .style(|_| container::Style {
+        background: Some(Background::Color(Color {
+            r: 1.0,
+            g: 1.0,
+            b: 1.0,
+            a: 0.06,
+        })),
+        border: Border {
+            color: Color::TRANSPARENT,
+            width: 0.0,
+            radius: 5.0.into(),
+        },
+        ..Default::default()
+    })
+    .into()
+}
+
+/// Banner about listed items in locations that macOS privacy protection denies
+fn permission_banner(state: &AppState) -> Option<Element<'_, AppMessage>> {
+    let blocked = state
+        .cleaner
+        .app_data
+        .associate_files
+        .iter()
+        .filter(|(path, _)| state.permissions.is_blocked(path))
+        .count();
+
+    if blocked == 0 {
+        return None;
+    }
+
+    let denied = state
+        .permissions
+        .denied()
+        .iter()
+        .map(|c| c.label())
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let message = Text::new(format!(
+        "{} items can not be moved to the Trash without Full Disk Access ({})",
+        blocked, denied
+    ))
+    .size(12)
+    .color(Color::from_rgb8(255, 204, 0))
+    .width(Length::Fill);
+
+    let banner = Container::new(
+        row![
+            message,
+            CustomButton::new("Open Full Disk Access")
+                .text_align_x(alignment::Horizontal::Center)
+                .text_align_y(alignment::Vertical::Center)
+                .on_press(AppMessage::OpenFullDiskAccess)
+                .style(custom_btn_rounded_style)
+                .view(),
+        ]
+        .spacing(10)
+        .align_y(alignment::Vertical::Center),
+    )
+    .width(Length::Fill)
+    .padding([5, 10])
+    .style(warning_banner_style);
+
+    Some(banner.into())
+}
+
+/// Trashing a cask app leaves brew believing it is still installed
+fn homebrew_banner(state: &AppState) -> Option<Element<'_, AppMessage>> {
+    let cask = state.cleaner.app_data.cask.as_ref()?;
+
+    let banner = Container::new(
+        Text::new(cask.warning())
+            .size(12)
+            .color(Color::from_rgb8(255, 204, 0))
+            .width(Length::Fill),
+    )
+    .width(Length::Fill)
+    .padding([5, 10])
+    .style(warning_banner_style);
+
+    Some(banner.into())
+}
+
+/// What the scan skipped, like a profile of the user that could not be read
+fn scan_warnings_banner(state: &AppState) -> Option<Element<'_, AppMessage>> {
+    let warnings = &state.cleaner.app_data.warnings;
+    if warnings.is_empty() {
+        return None;
+    }
+
+    let banner = Container::new(
+        Text::new(warnings.join("\n"))
+            .size(12)
+            .color(Color::from_rgb8(255, 204, 0))
+            .width(Length::Fill),
+    )
+    .width(Length::Fill)
+    .padding([5, 10])
+    .style(warning_banner_style);
+
+    Some(banner.into())
+}
+
+fn warning_banner_style(_theme: &Theme) -> container::Style {
+    container::Style {
+        background: Some(Background::Color(Color {
+            r: 1.0,
+            g: 0.8,
+            b: 0.0,
+            a: 0.1,
+        })),
+        border: Border {
+            color: Color::from_rgb8(255, 204, 0),
+            width: 0.5,
+            radius: 5.0.into(),
+        },
+        ..Default::default()
+    }
+}
+
+/// Leftovers of deleted apps, grouped by bundle id and selectable for trashing
+fn orphan_list(state: &AppState) -> Element<'_, AppMessage> {
+    let items = state
+        .orphans
+        .iter()
+        .enumerate()
+        .map(|(i, (group, selected))| {
+            let mark = if *selected { "x" } else { "" };
+            let name = if group.uncertain {
+                format!(
+                    "{} ({} items, an app of this vendor is installed)",
+                    group.bundle_id,
+                    group.paths.len()
+                )
+            } else {
+                format!("{} ({} items)", group.bundle_id, group.paths.len())
+            };
+            let size = format_size(group.size);
+            let modified = group.modified.map(format_age).unwrap_or_default();
+
+            RowContent::Widget(WidgetContent::new(move |_selected| {
+                row![
+                    CustomButton::new(mark)
+                        .text_size(12)
+                        .text_align_x(alignment::Horizontal::Center)
+                        .width(Length::Fixed(24.0))
+                        .on_press(AppMessage::ToggleOrphan(i))
+                        .style(blank_border_style)
+                        .view(),
+                    CustomButton::new(name.clone())
+                        .text_size(12)
+                        .text_align_x(alignment::Horizontal::Left)
+                        .width(Length::FillPortion(3))
+                        .on_press(AppMessage::ToggleOrphan(i))
+                        .style(blank_btn_style)
+                        .view(),
+                    Text::new(size.clone())
+                        .size(12)
+                        .color(Color::from_rgb8(200, 200, 200))
+                        .width(Length::FillPortion(1)),
+                    Text::new(modified.clone())
+                        .size(12)
+                        .color(Color::from_rgb8(3, 161, 252))
+                        .width(Length::FillPortion(1)),
+                ]
+                .spacing(10)
+                .align_y(alignment::Vertical::Center)
+                .into()
+            }))
+        })
+        .collect::<Vec<_>>();
+
+    let headers = vec![HeaderContent::Widget(HeaderWidget::new(|_selected| {
+        row![
+            Text::new("").width(Length::Fixed(24.0)),
+            Text::new("Bundle ID")
+                .size(12)
+                .color(Color::WHITE)
+                .width(Length::FillPortion(3)),
+            Text::new("Size")
+                .size(12)
+                .color(Color::WHITE)
+                .width(Length::FillPortion(1)),
+            Text::new("Modified")
+                .size(12)
+                .color(Color::WHITE)
+                .width(Length::FillPortion(1)),
+        ]
+        .spacing(10)
+        .into()
+    }))];
+
+    ListView::new(items)
+        .headers(headers)
+        .row_style(|i, _theme| {
+            let color = if i % 2 == 0 {
+                Color::from_rgb8(32, 36, 42)
+            } else {
+                Color::from_rgb8(28, 32, 38)
+            };
+
+            container::Style {
+                background: Some(color.into()),
+                border: Border {
+                    color: Color::TRANSPARENT,
+                    width: 1.0,
+                    radius: 5.0.into(),
+                },
+                ..Default::default()
+            }
+        })
+        .view()
+}
+
+/// Form to scan for the leftovers of an app that is already deleted
+fn identity_form(form: &IdentityForm) -> Element<'_, AppMessage> {
+    let input = |placeholder: &str, value: &str, field: IdentityField| {
+        text_input(placeholder, value)
+            .size(12)
+            .on_input(move |v| AppMessage::IdentityInput(field, v))
+            .on_submit(AppMessage::ScanIdentity)
+    };
+
+    // Suggestions follow what was typed in the bundle id field
+    let suggestions = form
+        .suggestions
+        .iter()
+        .filter(|s| s.matches(&form.bundle_id))
+        .take(50)
+        .fold(Column::new().spacing(2), |column, suggestion| {
+            column.push(
+                CustomButton::new(suggestion.bundle_id.clone())
+                    .text_size(12)
+                    .text_align_x(alignment::Horizontal::Left)
+                    .width(Length::Fill)
+                    .on_press(AppMessage::PickSuggestion(suggestion.bundle_id.clone()))
+                    .style(blank_btn_style)
+                    .view(),
+            )
+        });
+
+    Column::new()
+        .push(
+            Text::new("Scan for the leftovers of an app that is already deleted")
+                .size(14)
+                .color(Color::WHITE),
+        )
+        .push(input(
+            "Bundle ID, like com.vendor.App",
+            &form.bundle_id,
+            IdentityField::BundleId,
+        ))
+        .push(
+            row![
+                input("Name (optional)", &form.name, IdentityField::Name),
+                input(
+                    "Executable (optional)",
+                    &form.executable,
+                    IdentityField::Executable
+                ),
+                CustomButton::new("Scan")
+                    .text_align_x(alignment::Horizontal::Center)
+                    .text_align_y(alignment::Vertical::Center)
+                    .on_press(AppMessage::ScanIdentity)
+                    .style(custom_btn_rounded_style)
+                    .view(),
+            ]
+            .spacing(5),
+        )
+        .push(scrollable(suggestions).height(Length::Fill))
+        .spacing(8)
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .into()
+}
+
+/// Installed applications, searchable and sortable, a double click opens one
+fn catalog_list(view: &CatalogView) -> Element<'_, AppMessage> {
+    let search = text_input("Search applications", &view.query)
+        .size(12)
+        .on_input(AppMessage::CatalogQuery);
+
+    if view.loading {
+        return Column::new()
+            .push(search)
+            .push(
+                Text::new("Loading installed applications...")
+                    .size(12)
+                    .color(Color::from_rgb8(200, 200, 200)),
+            )
+            .spacing(8)
+            .into();
+    }
+
+    let entries = view.catalog.search(&view.query);
+
+    let selected = view
+        .last_click
+        .as_ref()
+        .and_then(|(path, _)| entries.iter().position(|entry| entry.info.path == *path));
+
+    let items = entries
+        .into_iter()
+        .map(|entry| {
+            let path = entry.info.path.clone();
+            let icon = view.icons.get(&path).cloned();
+            let name = entry.info.name.clone();
+            let version = entry.info.version.clone().unwrap_or_default();
+            let size = entry.size().map(format_size).unwrap_or_default();
+            let last_opened = entry.last_opened().map(format_age).unwrap_or_default();
+
+            RowContent::Widget(WidgetContent::new(move |_selected| {
+                let icon: Element<AppMessage> = match &icon {
+                    Some(handle) => Image::new(handle.clone())
+                        .width(Length::Fixed(20.0))
+                        .height(Length::Fixed(20.0))
+                        .into(),
+                    None => Container::new(text(""))
+                        .width(Length::Fixed(20.0))
+                        .height(Length::Fixed(20.0))
+                        .into(),
+                };
+
+                row![
+                    icon,
+                    CustomButton::new(name.clone())
+                        .text_size(12)
+                        .text_align_x(alignment::Horizontal::Left)
+                        .width(Length::FillPortion(3))
+                        .on_press(AppMessage::CatalogClicked(path.clone()))
+                        .style(blank_btn_style)
+                        .view(),
+                    Text::new(version.clone())
+                        .size(12)
+                        .color(Color::from_rgb8(200, 200, 200))
+                        .width(Length::FillPortion(1)),
+                    Text::new(size.clone())
+                        .size(12)
+                        .color(Color::from_rgb8(200, 200, 200))
+                        .width(Length::FillPortion(1)),
+                    Text::new(last_opened.clone())
+                        .size(12)
+                        .color(Color::from_rgb8(3, 161, 252))
+                        .width(Length::FillPortion(1)),
+                ]
+                .spacing(10)
+                .align_y(alignment::Vertical::Center)
+                .into()
+            }))
+        })
+        .collect::<Vec<_>>();
+
+    let sort = view.sort;
+    let ascending = view.ascending;
+    let headers = vec![HeaderContent::Widget(HeaderWidget::new(move |_selected| {
+        // Columns are sort buttons, the sorted one shows the order
+        let column = |label: &str, by: CatalogSort, portion: u16| {
+            let label = match (sort == by, ascending) {
+                (true, true) => format!("{} ▲", label),
+                (true, false) => format!("{} ▼", label),
+                (false, _) => label.to_string(),
+            };
+            CustomButton::new(label)
+                .text_size(12)
+                .text_align_x(alignment::Horizontal::Left)
+                .width(Length::FillPortion(portion))
+                .on_press(AppMessage::CatalogSortBy(by))
+                .style(blank_btn_style)
+                .view()
+        };
+
+        row![
+            Text::new("").width(Length::Fixed(20.0)),
+            column("Name", CatalogSort::Name, 3),
+            column("Version", CatalogSort::Version, 1),
+            column("Size", CatalogSort::Size, 1),
+            column("Last opened", CatalogSort::LastOpened, 1),
+        ]
+        .spacing(10)
+        .into()
+    }))];
+
+    let list = ListView::new(items)
+        .headers(headers)
+        .row_selected(selected)
+        .row_style(|i, _theme| {
+            let color = if i % 2 == 0 {
+                Color::from_rgb8(32, 36, 42)
+            } else {
+                Color::from_rgb8(28, 32, 38)
+            };
+
+            container::Style {
+                background: Some(color.into()),
+                border: Border {
+                    color: Color::TRANSPARENT,
+                    width: 1.0,
+                    radius: 5.0.into(),
+                },
+                ..Default::default()
+            }
+        })
+        .view();
+
+    Column::new().push(search).push(list).spacing(8).into()
+}
@@ -6,6 +6,7 @@ mod app_update;
 mod app_window;
 
 use crate::app_state::{AppMessage, AppState};
+use crate::app_task::probe_permissions_async;
 use crate::app_update::{subscription, update};
 use crate::app_window::view;
 use common_debug::debug_dev;
@@ -13,7 +14,8 @@ use iced::{Size, Task, application, window};
 
 fn init() -> (AppState, Task<AppMessage>) {
     let app_state = AppState::new();
-    (app_state, Task::none())
+    let probe = Task::perform(probe_permissions_async(), AppMessage::PermissionsProbed);
+    (app_state, probe)
 }
 
 fn main() {
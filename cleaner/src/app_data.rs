@@ -1,20 +1,38 @@
+mod app_icon;
 mod app_info;
 mod app_process;
+mod bundle_error;
+mod embedded_bundle;
+mod install_source;
 mod locations_scan;
 mod log_receipt;
+mod match_rules;
+mod vendor;
 
-pub use app_info::AppInfo;
-pub use app_process::AppProcess;
+pub use app_icon::AppIcon;
+pub use app_info::{AppInfo, BundleKind};
+pub use app_process::{AppProcess, ProcessPatterns};
+pub use bundle_error::BundleError;
+pub use embedded_bundle::{EmbeddedBundle, EmbeddedKind};
+pub use install_source::InstallSource;
 pub use locations_scan::LocationsScan;
 pub use log_receipt::LogReceipt;
+pub use match_rules::{MatchOptions, MatchRule, MatchRuleSet, MatchRules, rules_match};
+pub use vendor::Vendor;
 
-use anyhow::Result;
 use rayon::prelude::*;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::sync::atomic::{AtomicUsize, Ordering};
-use walkdir::WalkDir;
+
+use crate::cancel::CancelToken;
+use crate::error::{CleanerError, Result};
+use crate::helpers::{total_size_of, walk_root};
+use crate::homebrew::{Homebrew, HomebrewCask};
+use crate::profile::{AppProfile, ProfileDb};
+use crate::safety_guard::TrashReport;
+use crate::trash_failure::TrashFailure;
 
 #[cfg(debug_assertions)]
 use common_debug::debug_dev;
@@ -25,12 +43,28 @@ pub struct AppData {
     pub app_process: Vec<AppProcess>,
     pub log: LogReceipt,
     pub associate_files: Vec<(PathBuf, String)>,
+    pub icon: Option<AppIcon>,
+    pub total_size: u64,
+    /// Set when the app was installed with a Homebrew cask
+    pub cask: Option<HomebrewCask>,
+    /// Rules of the user and of the app profile that add or exclude associate files
+    pub extra_rules: MatchRuleSet,
+    /// Profile of the app from the profile database
+    pub profile: Option<AppProfile>,
+    /// Entries walked under each scan root by the last complete scan
+    pub scan_counts: HashMap<PathBuf, usize>,
+    /// Problems that did not stop the scan, like a profile that was skipped
+    pub warnings: Vec<String>,
 }
 
+/// Icons are only shown small, no need to decode the big entries
+const ICON_MAX_SIZE: u32 = 128;
+
 impl AppData {
     pub fn new(app_path: &Path) -> Result<Self> {
         // Create AppInfo from path
         let app_info = AppInfo::from_path(app_path)?;
+        let icon = AppIcon::load(&app_info, ICON_MAX_SIZE).ok();
 
         Ok(Self {
             app: app_info,
@@ -39,6 +73,32 @@ impl AppData {
                 bom_file: Vec::new(),
             },
             associate_files: Vec::new(),
+            icon,
+            total_size: 0,
+            cask: None,
+            extra_rules: MatchRuleSet::default(),
+            profile: None,
+            scan_counts: HashMap::new(),
+            warnings: Vec::new(),
+        })
+    }
+
+    /// AppData of an app whose bundle was already deleted, there is no icon to load
+    pub fn from_identity(name: &str, bundle_id: &str, executable: &str) -> Result<Self> {
+        let bundle_id = bundle_id.trim();
+        let valid = bundle_id.contains('.')
+            && !bundle_id.starts_with('.')
+            && !bundle_id.ends_with('.')
+            && !bundle_id.contains(|c: char| c.is_whitespace() || c == '/');
+        if !valid {
+            return Err(CleanerError::InvalidBundleId {
+                bundle_id: bundle_id.to_string(),
+            });
+        }
+
+        Ok(Self {
+            app: AppInfo::from_identity(name, bundle_id, executable),
+            ..Default::default()
         })
     }
 
@@ -65,28 +125,50 @@ impl AppData {
     // for huge directory and try using walkdir + rayon
     // use in_progress as emitter status to caller
     pub fn find_associate_files<F>(&mut self, locations: &LocationsScan, in_progress: F)
+    where
+        F: Fn(usize, &Path) + Send + Sync,
+    {
+        // A token nobody holds is never cancelled
+        let _ = self.find_associate_files_with(locations, &CancelToken::new(), in_progress);
+    }
+
+    /// Like find_associate_files, stopping when the token is cancelled.
+    /// The associate files are left as they were then
+    pub fn find_associate_files_with<F>(
+        &mut self,
+        locations: &LocationsScan,
+        cancel: &CancelToken,
+        in_progress: F,
+    ) -> Result<()>
     where
         F: Fn(usize, &Path) + Send + Sync,
     {
         let counter = Arc::new(AtomicUsize::new(0));
         let progress = Arc::new(in_progress);
+        let rules = self.app.associate_rules();
 
         // Parallel
-        let results: Vec<(PathBuf, String)> = locations
+        let walked: Vec<_> = locations
             .paths
             .par_iter()
             .filter(|base| base.exists())
             .map(|base| {
-                WalkDir::new(base)
-                    .max_depth(3)
-                    .into_iter()
-                    .filter_map(Result::ok)
-                    .filter(|entry| entry.file_type().is_file() || entry.file_type().is_dir())
+                let mut count = 0;
+
+                // The base is a scan root, never a file of the app
+                let matches = walk_root(base)
+                    .take_while(|_| !cancel.is_cancelled())
                     .flat_map(|entry| {
+                        count += 1;
+
                         let path_buf = entry.path().to_path_buf();
                         let mut matches = Vec::new();
 
-                        if self.app.associate_path_matches(&path_buf) {
+                        let matched = (self.app.associate_path_matches_with(&path_buf, &rules)
+                            || self.extra_rules.includes(&path_buf))
+                            && !self.extra_rules.excludes(&path_buf);
+
+                        if matched {
                             matches.push((
                                 path_buf.clone(),
                                 path_buf.file_name().unwrap().to_string_lossy().to_string(),
@@ -101,12 +183,21 @@ impl AppData {
 
                         matches.into_iter()
                     })
-                    .collect::<Vec<_>>()
+                    .collect::<Vec<_>>();
+
+                (base.clone(), count, matches)
             })
-            .reduce(Vec::new, |mut acc, v| {
-                acc.extend(v);
-                acc
-            }); // Collect directly without per-base Vec
+            .collect();
+
+        // A cancelled walk is incomplete, keep nothing of it
+        cancel.check()?;
+
+        let mut results = Vec::new();
+        self.scan_counts.clear();
+        for (base, count, matches) in walked {
+            self.scan_counts.insert(base, count);
+            results.extend(matches);
+        }
 
         // Deduplicate once at the end
         let mut seen = HashSet::new();
@@ -118,6 +209,80 @@ impl AppData {
 
         // Build the indexed list including the app itself
         self.set_all_associate_file(unique_results);
+        Ok(())
+    }
+
+    /// Look for the Homebrew cask of the app and list its artifacts,
+    /// symlinks and Caskroom entry with the associate files
+    pub fn find_homebrew_cask(&mut self, brew: &Homebrew) {
+        self.cask = brew.find_cask(&self.app);
+        self.add_cask_files();
+        self.update_total_size();
+    }
+
+    /// Look for the profile of the app, its rules join the extra rules
+    /// and its paths are listed with the associate files
+    pub fn find_profile(&mut self, db: &ProfileDb) {
+        self.profile = db.find(&self.app.bundle_id).cloned();
+        if let Some(profile) = &self.profile {
+            self.extra_rules.rules.extend(profile.match_rules());
+        }
+        self.add_profile_files();
+        self.update_total_size();
+    }
+
+    /// Tell how the app was installed from its bundle, cask and receipts.
+    /// Call after find_log_bom and find_homebrew_cask
+    pub fn detect_install_source(&mut self) {
+        self.app.install_source = InstallSource::detect(
+            &self.app.path,
+            self.app.kind,
+            self.cask.as_ref(),
+            &self.log.bom_file,
+        );
+    }
+
+    fn add_cask_files(&mut self) {
+        let Some(cask) = &self.cask else {
+            return;
+        };
+
+        for path in cask.paths() {
+            if self.associate_files.iter().any(|(p, _)| *p == path) {
+                continue;
+            }
+            let label = path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default();
+            self.associate_files.push((path, label));
+        }
+    }
+
+    fn add_profile_files(&mut self) {
+        let Some(profile) = &self.profile else {
+            return;
+        };
+
+        let home = std::env::var("HOME")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| PathBuf::from("/Users/Unknown"));
+
+        for path in profile.resolve_paths(&home) {
+            if self.associate_files.iter().any(|(p, _)| *p == path) {
+                continue;
+            }
+            let label = path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default();
+            self.associate_files.push((path, label));
+        }
+
+        // Shared folders and the folders holding them stay even when a rule or a name matched them
+        let never_delete = profile.never_delete_paths(&home);
+        self.associate_files
+            .retain(|(p, _)| !never_delete.iter().any(|keep| keep.starts_with(p)));
     }
 
     /// Update associate_files with given list and include app itself
@@ -125,10 +290,53 @@ impl AppData {
         // Start with enumerated files
         let mut path_asc: Vec<(PathBuf, String)> = files.into_iter().collect();
 
-        // Append the app itself
-        path_asc.push((self.app.path.clone(), self.app.name.clone()));
+        // Append the app itself, unless it was only known by its bundle id
+        if !self.app.path.as_os_str().is_empty() {
+            path_asc.push((self.app.path.clone(), self.app.name.clone()));
+        }
 
         self.associate_files = path_asc;
+
+        // The cask may have been found before the scan
+        self.add_cask_files();
+        self.add_profile_files();
+        self.update_total_size();
+    }
+
+    /// Keep only the entries that could not be removed.
+    /// The total size is not walked again, see associate_paths
+    pub fn retain_failed(&mut self, failed: &[(PathBuf, TrashFailure)]) {
+        self.associate_files
+            .retain(|(path, _)| failed.iter().any(|(p, _)| p == path));
+    }
+
+    /// Keep only the entries that failed, were refused by the safety guard
+    /// or were skipped by a cancel.
+    /// The total size is not walked again, see associate_paths
+    pub fn retain_unremoved(&mut self, report: &TrashReport) {
+        self.associate_files.retain(|(path, _)| {
+            report.failed.iter().any(|(p, _)| p == path)
+                || report.refused.iter().any(|(p, _)| p == path)
+                || report.skipped.contains(path)
+        });
+    }
+
+    /// Paths of the associate files, to size them away from the caller's thread
+    pub fn associate_paths(&self) -> Vec<PathBuf> {
+        self.associate_files
+            .iter()
+            .map(|(p, _)| p.clone())
+            .collect()
+    }
+
+    /// Recompute the size on disk of all associate files, this walks them all
+    pub fn update_total_size(&mut self) {
+        let paths: Vec<&Path> = self
+            .associate_files
+            .iter()
+            .map(|(p, _)| p.as_path())
+            .collect();
+        self.total_size = total_size_of(&paths);
     }
 
     // ===============All Associate file with enumerate==================
@@ -144,12 +352,14 @@ impl AppData {
     }
 
     // =======Save All Bom Log that was founded==============
-    pub fn save_bom_log_app(&self, log_dir: &Path) -> Result<()> {
+    pub fn save_bom_log_app(&self, log_dir: &Path, cancel: &CancelToken) -> Result<()> {
         if self.log.bom_file.is_empty() {
-            anyhow::bail!("No BOM files found for app: {}", self.app.name);
+            return Err(CleanerError::NoBomFiles {
+                path: self.app.path.clone(),
+            });
         }
 
-        self.log.save_bom_log(log_dir)
+        self.log.save_bom_log(log_dir, cancel)
     }
 
     pub fn reset(&mut self) {
@@ -157,5 +367,12 @@ impl AppData {
         self.app_process.clear();
         self.log = LogReceipt::default();
         self.associate_files.clear();
+        self.icon = None;
+        self.total_size = 0;
+        self.cask = None;
+        self.extra_rules = MatchRuleSet::default();
+        self.profile = None;
+        self.scan_counts.clear();
+        self.warnings.clear();
     }
 }
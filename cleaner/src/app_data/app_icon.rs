@@ -0,0 +1,260 @@
+use std::path::{Path, PathBuf};
+
+use crate::app_data::AppInfo;
+use crate::error::{CleanerError, Result};
+
+/// Decoding errors before the icon path is known
+type DecodeResult<T> = std::result::Result<T, String>;
+
+/// Icon entries that hold a PNG image, with their pixel size
+const PNG_TYPES: [(&[u8; 4], u32); 11] = [
+    (b"icp4", 16),
+    (b"icp5", 32),
+    (b"icp6", 64),
+    (b"ic07", 128),
+    (b"ic08", 256),
+    (b"ic09", 512),
+    (b"ic10", 1024),
+    (b"ic11", 32),
+    (b"ic12", 64),
+    (b"ic13", 256),
+    (b"ic14", 512),
+];
+
+/// Legacy RLE compressed RGB entries with their alpha mask entry and pixel size
+const RGB_TYPES: [(&[u8; 4], &[u8; 4], u32); 4] = [
+    (b"is32", b"s8mk", 16),
+    (b"il32", b"l8mk", 32),
+    (b"ih32", b"h8mk", 48),
+    (b"it32", b"t8mk", 128),
+];
+
+/// Icon entry that can be decoded
+#[derive(Clone, Copy)]
+struct Candidate<'a> {
+    size: u32,
+    data: &'a [u8],
+    mask: Option<&'a [u8]>,
+    is_png: bool,
+}
+
+/// Decoded app icon as RGBA8 pixels
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AppIcon {
+    pub width: u32,
+    pub height: u32,
+    pub rgba: Vec<u8>,
+}
+
+impl AppIcon {
+    /// Load the icon of the app from CFBundleIconFile in Contents/Resources
+    pub fn load(app: &AppInfo, max_size: u32) -> Result<Self> {
+        let icns_path = Self::icns_path(app).ok_or_else(|| CleanerError::Icon {
+            path: Some(app.path.clone()),
+            reason: "No icon file found".to_string(),
+        })?;
+
+        let bytes = std::fs::read(&icns_path).map_err(|e| CleanerError::io(&icns_path, e))?;
+
+        Self::decode_icns(&bytes, max_size).map_err(|reason| CleanerError::Icon {
+            path: Some(icns_path),
+            reason,
+        })
+    }
+
+    fn icns_path(app: &AppInfo) -> Option<PathBuf> {
+        let resources = Path::new(&app.path).join("Contents").join("Resources");
+
+        // CFBundleIconFile may be given with or without the extension
+        let path = match &app.icon_file {
+            Some(name) if Path::new(name).extension().is_some() => resources.join(name),
+            Some(name) => resources.join(format!("{}.icns", name)),
+            None => resources.join("AppIcon.icns"),
+        };
+
+        path.exists().then_some(path)
+    }
+
+    /// Decode the largest entry of an .icns file that is not bigger than max_size
+    pub fn from_icns(bytes: &[u8], max_size: u32) -> Result<Self> {
+        Self::decode_icns(bytes, max_size)
+            .map_err(|reason| CleanerError::Icon { path: None, reason })
+    }
+
+    fn decode_icns(bytes: &[u8], max_size: u32) -> DecodeResult<Self> {
+        if bytes.len() < 8 || &bytes[0..4] != b"icns" {
+            return Err("Not an icns file".to_string());
+        }
+
+        let entries = Self::read_entries(bytes)?;
+        let entry = |kind: &[u8; 4]| {
+            entries
+                .iter()
+                .find(|(t, _)| t == kind)
+                .map(|(_, data)| *data)
+        };
+
+        // Collect the candidates, biggest first
+        let mut candidates = Vec::new();
+        for (kind, size) in PNG_TYPES {
+            if let Some(data) = entry(kind)
+                && data.starts_with(b"\x89PNG")
+            {
+                candidates.push(Candidate {
+                    size,
+                    data,
+                    mask: None,
+                    is_png: true,
+                });
+            }
+        }
+        for (kind, mask, size) in RGB_TYPES {
+            if let Some(data) = entry(kind) {
+                candidates.push(Candidate {
+                    size,
+                    data,
+                    mask: entry(mask),
+                    is_png: false,
+                });
+            }
+        }
+        candidates.sort_by_key(|c| std::cmp::Reverse(c.size));
+
+        // Fall back to the smallest one when all are bigger than max_size
+        let candidate = candidates
+            .iter()
+            .find(|c| c.size <= max_size)
+            .or_else(|| candidates.last())
+            .copied()
+            .ok_or_else(|| "No supported icon entry found".to_string())?;
+
+        if candidate.is_png {
+            Self::decode_png(candidate.data)
+        } else {
+            Self::decode_rgb(candidate.data, candidate.mask, candidate.size)
+        }
+    }
+
+    fn read_entries(bytes: &[u8]) -> DecodeResult<Vec<([u8; 4], &[u8])>> {
+        let mut entries = Vec::new();
+        let mut offset = 8;
+
+        while offset + 8 <= bytes.len() {
+            let kind = [
+                bytes[offset],
+                bytes[offset + 1],
+                bytes[offset + 2],
+                bytes[offset + 3],
+            ];
+            let len = u32::from_be_bytes([
+                bytes[offset + 4],
+                bytes[offset + 5],
+                bytes[offset + 6],
+                bytes[offset + 7],
+            ]) as usize;
+
+            if len < 8 || offset + len > bytes.len() {
+                return Err("Corrupted icns entry".to_string());
+            }
+
+            entries.push((kind, &bytes[offset + 8..offset + len]));
+            offset += len;
+        }
+
+        Ok(entries)
+    }
+
+    fn decode_png(data: &[u8]) -> DecodeResult<Self> {
+        let mut decoder = png::Decoder::new(data);
+        decoder.set_transformations(png::Transformations::normalize_to_color8());
+
+        let mut reader = decoder.read_info().map_err(|e| e.to_string())?;
+        let mut buf = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut buf).map_err(|e| e.to_string())?;
+        buf.truncate(info.buffer_size());
+
+        let rgba = match info.color_type {
+            png::ColorType::Rgba => buf,
+            png::ColorType::Rgb => buf
+                .chunks_exact(3)
+                .flat_map(|p| [p[0], p[1], p[2], 255])
+                .collect(),
+            png::ColorType::GrayscaleAlpha => buf
+                .chunks_exact(2)
+                .flat_map(|p| [p[0], p[0], p[0], p[1]])
+                .collect(),
+            png::ColorType::Grayscale => buf.iter().flat_map(|&g| [g, g, g, 255]).collect(),
+            png::ColorType::Indexed => return Err("Unexpected indexed PNG".to_string()),
+        };
+
+        Ok(Self {
+            width: info.width,
+            height: info.height,
+            rgba,
+        })
+    }
+
+    fn decode_rgb(data: &[u8], mask: Option<&[u8]>, size: u32) -> DecodeResult<Self> {
+        let pixels = (size * size) as usize;
+
+        // it32 data starts with four zero bytes
+        let data = if size == 128 && data.starts_with(&[0, 0, 0, 0]) {
+            &data[4..]
+        } else {
+            data
+        };
+
+        // Each channel is compressed separately, one plane after the other
+        let planes = if data.len() == pixels * 3 {
+            data.to_vec()
+        } else {
+            Self::unpack_rle(data, pixels * 3)?
+        };
+
+        let mut rgba = vec![255; pixels * 4];
+        for i in 0..pixels {
+            rgba[i * 4] = planes[i];
+            rgba[i * 4 + 1] = planes[pixels + i];
+            rgba[i * 4 + 2] = planes[pixels * 2 + i];
+            if let Some(mask) = mask
+                && mask.len() == pixels
+            {
+                rgba[i * 4 + 3] = mask[i];
+            }
+        }
+
+        Ok(Self {
+            width: size,
+            height: size,
+            rgba,
+        })
+    }
+
+    fn unpack_rle(data: &[u8], expected: usize) -> DecodeResult<Vec<u8>> {
+        let mut out = Vec::with_capacity(expected);
+        let mut i = 0;
+
+        while i < data.len() && out.len() < expected {
+            let n = data[i] as usize;
+            i += 1;
+
+            if n < 0x80 {
+                // copy the next n + 1 bytes
+                let end = (i + n + 1).min(data.len());
+                out.extend_from_slice(&data[i..end]);
+                i = end;
+            } else if let Some(&value) = data.get(i) {
+                // repeat the next byte n - 125 times
+                out.extend(std::iter::repeat_n(value, n - 125));
+                i += 1;
+            }
+        }
+
+        if out.len() < expected {
+            return Err("Truncated icon data".to_string());
+        }
+        out.truncate(expected);
+
+        Ok(out)
+    }
+}
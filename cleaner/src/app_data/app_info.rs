@@ -1,20 +1,77 @@
-use anyhow::{Context, Result, anyhow};
 use plist::Value;
 use std::path::{Path, PathBuf};
 
-use crate::helpers::path_contains_ignore_case;
-use crate::helpers::path_equals_ignore_case;
+use crate::app_data::match_rules::rules_match;
+use crate::app_data::{BundleError, EmbeddedBundle, InstallSource, MatchRule, MatchRules, Vendor};
+use crate::helpers::Normalization;
+use crate::syscom::{run_codesign_entitlements, run_codesign_team_id};
 
-pub enum MatchRules {
-    Equal,
-    Contain,
+const SANDBOX_ENTITLEMENT: &str = "com.apple.security.app-sandbox";
+const APP_GROUPS_ENTITLEMENT: &str = "com.apple.security.application-groups";
+
+/// Kind of bundle that can be uninstalled
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum BundleKind {
+    #[default]
+    App,
+    /// iOS or iPadOS app running on Apple silicon, wrapped in a macOS .app
+    WrappedIos,
+    PrefPane,
+    Plugin,
+    QuickLookGenerator,
 }
 
-impl MatchRules {
-    fn match_path(&self, path: &Path, value: &str) -> bool {
+impl BundleKind {
+    pub const EXTENSIONS: [&str; 4] = ["app", "prefPane", "plugin", "qlgenerator"];
+
+    pub fn from_path(path: &Path) -> Self {
+        let ext = path
+            .extension()
+            .map(|e| e.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+
+        match ext.as_str() {
+            "prefpane" => BundleKind::PrefPane,
+            "plugin" => BundleKind::Plugin,
+            "qlgenerator" => BundleKind::QuickLookGenerator,
+            _ if path.join("Wrapper").is_dir() && !path.join("Contents").is_dir() => {
+                BundleKind::WrappedIos
+            }
+            _ => BundleKind::App,
+        }
+    }
+
+    /// iOS bundles are flat, macOS bundles keep everything in Contents
+    fn info_plist_path(&self, bundle_root: &Path) -> PathBuf {
         match self {
-            MatchRules::Equal => path_equals_ignore_case(path, value),
-            MatchRules::Contain => path_contains_ignore_case(path, value),
+            BundleKind::WrappedIos => bundle_root.join("Info.plist"),
+            _ => bundle_root.join("Contents").join("Info.plist"),
+        }
+    }
+
+    /// Guess the executable from the binaries shipped in the bundle
+    fn infer_executable(&self, bundle_root: &Path) -> Option<String> {
+        let stem = bundle_root.file_stem()?.to_string_lossy().into_owned();
+
+        let binaries_dir = match self {
+            BundleKind::WrappedIos => bundle_root.to_path_buf(),
+            _ => bundle_root.join("Contents").join("MacOS"),
+        };
+
+        let binaries: Vec<String> = std::fs::read_dir(binaries_dir)
+            .ok()?
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().map(|t| t.is_file()).unwrap_or(false))
+            .map(|e| e.file_name().to_string_lossy().into_owned())
+            .collect();
+
+        // Prefer the one named like the bundle, else the only one there is
+        if binaries.contains(&stem) {
+            Some(stem)
+        } else if binaries.len() == 1 && *self != BundleKind::WrappedIos {
+            binaries.into_iter().next()
+        } else {
+            None
         }
     }
 }
@@ -22,80 +79,277 @@ impl MatchRules {
 #[derive(Debug, Clone)]
 pub struct AppInfo {
     pub path: PathBuf,
+    pub kind: BundleKind,
     pub name: String,
     pub bundle_id: String,
     pub bundle_name: String,
+    /// Vendor name, only for display, matching uses the vendor prefix
     pub organization: String,
+    /// Reverse-DNS vendor prefix of the bundle id, like "com.company"
+    pub vendor_prefix: String,
+    pub embedded: Vec<EmbeddedBundle>,
+    /// CFBundleName
+    pub short_name: Option<String>,
+    /// CFBundleShortVersionString
+    pub version: Option<String>,
+    /// CFBundleVersion
+    pub build_version: Option<String>,
+    /// LSMinimumSystemVersion
+    pub min_system_version: Option<String>,
+    /// CFBundleIconFile
+    pub icon_file: Option<String>,
+    pub team_id: Option<String>,
+    pub sandboxed: bool,
+    pub app_groups: Vec<String>,
+    /// Only the App Store is told by the bundle, the scan refines the rest
+    pub install_source: InstallSource,
 }
 
 impl AppInfo {
-    /// Construct AppInfo from .app path
-    pub fn from_path(app_path: &Path) -> Result<Self> {
-        let plist_path = Path::new(app_path).join("Contents").join("Info.plist");
+    /// Construct AppInfo from a bundle path, with its code signature and embedded bundles
+    pub fn from_path(app_path: &Path) -> Result<Self, BundleError> {
+        let mut info = Self::from_info_plist(app_path)?;
+        info.inspect_bundle();
+        Ok(info)
+    }
 
+    /// Construct AppInfo from what the Info.plist tells, quick enough for every
+    /// installed app. Signature and embedded bundles are left out, see inspect_bundle
+    pub fn from_info_plist(app_path: &Path) -> Result<Self, BundleError> {
+        if !app_path.exists() {
+            return Err(BundleError::NotFound(app_path.to_path_buf()));
+        }
+        if !app_path.is_dir() {
+            return Err(BundleError::NotABundle(app_path.to_path_buf()));
+        }
+
+        let kind = BundleKind::from_path(app_path);
+
+        // iOS apps keep the real bundle inside the wrapper
+        let bundle_root = match kind {
+            BundleKind::WrappedIos => Self::wrapped_bundle(app_path)
+                .ok_or_else(|| BundleError::InfoPlistMissing(app_path.to_path_buf()))?,
+            _ => app_path.to_path_buf(),
+        };
+
+        let plist_path = kind.info_plist_path(&bundle_root);
         if !plist_path.exists() {
-            anyhow::bail!("Info.plist not found in {}", app_path.display());
+            return Err(BundleError::InfoPlistMissing(app_path.to_path_buf()));
         }
 
-        let plist = Value::from_file(&plist_path)
-            .with_context(|| format!("Failed to read plist: {}", plist_path.display()))?;
+        // Reads both XML and binary plists
+        let plist = Value::from_file(&plist_path).map_err(|e| BundleError::InvalidPlist {
+            path: plist_path.clone(),
+            reason: e.to_string(),
+        })?;
 
         let bundle_id = plist
             .as_dictionary()
             .and_then(|d| d.get("CFBundleIdentifier"))
             .and_then(|v| v.as_string())
-            .ok_or_else(|| {
-                anyhow::anyhow!("CFBundleIdentifier not found in {}", plist_path.display())
-            })?;
+            .ok_or_else(|| BundleError::MissingBundleId(plist_path.clone()))?;
+
+        // fallback to file stem if CFBundleDisplayName is missing
+        let file_stem = app_path
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_default();
 
         let app_name = plist
             .as_dictionary()
             .and_then(|d| d.get("CFBundleDisplayName"))
             .and_then(|v| v.as_string())
             .map(|s| s.to_string())
-            .or_else(|| {
-                // fallback to file stem if CFBundleDisplayName is missing
-                Some(app_path.file_stem()?.to_string_lossy().into_owned())
-            })
-            .ok_or_else(|| anyhow!("Failed to determine app name for {}", app_path.display()))?;
+            .unwrap_or_else(|| file_stem.clone());
 
+        // Not every bundle declares its executable, look at what is shipped instead
         let executable_name = plist
             .as_dictionary()
             .and_then(|d| d.get("CFBundleExecutable"))
             .and_then(|v| v.as_string())
-            .ok_or_else(|| anyhow!("CFBundleExecutable not found in {}", plist_path.display()))?;
+            .map(|s| s.to_string())
+            .or_else(|| kind.infer_executable(&bundle_root))
+            .unwrap_or_else(|| file_stem.clone());
+
+        // Optional metadata, missing keys are fine
+        let plist_string = |key: &str| {
+            plist
+                .as_dictionary()
+                .and_then(|d| d.get(key))
+                .and_then(|v| v.as_string())
+                .map(|s| s.to_string())
+        };
 
-        let organization = bundle_id.split('.').nth(1).unwrap_or("").to_string();
+        let vendor = Vendor::from_bundle_id(bundle_id);
 
         Ok(Self {
             path: app_path.to_path_buf(),
-            name: app_name.to_string(),
+            kind,
+            name: app_name,
             bundle_id: bundle_id.to_string(),
-            bundle_name: executable_name.to_string(),
-            organization,
+            bundle_name: executable_name,
+            organization: vendor.name,
+            vendor_prefix: vendor.prefix,
+            embedded: Vec::new(),
+            short_name: plist_string("CFBundleName"),
+            version: plist_string("CFBundleShortVersionString"),
+            build_version: plist_string("CFBundleVersion"),
+            min_system_version: plist_string("LSMinimumSystemVersion"),
+            icon_file: plist_string("CFBundleIconFile"),
+            team_id: None,
+            sandboxed: false,
+            app_groups: Vec::new(),
+            install_source: InstallSource::from_bundle(app_path, kind),
         })
     }
 
+    /// Read the code signature and the embedded bundles, this runs codesign
+    /// and walks the bundle. The vendor takes the signing team into account
+    pub fn inspect_bundle(&mut self) {
+        // Extensions, login items and helpers carry their own bundle id
+        self.embedded = EmbeddedBundle::inspect(&self.path);
+
+        let bundle_root = match self.kind {
+            BundleKind::WrappedIos => {
+                Self::wrapped_bundle(&self.path).unwrap_or_else(|| self.path.clone())
+            }
+            _ => self.path.clone(),
+        };
+
+        // Code signature, only available for signed apps on macOS
+        self.team_id = run_codesign_team_id(&bundle_root);
+        let entitlements = run_codesign_entitlements(&bundle_root);
+        let entitlements = entitlements.as_ref().and_then(|v| v.as_dictionary());
+
+        self.sandboxed = entitlements
+            .and_then(|d| d.get(SANDBOX_ENTITLEMENT))
+            .and_then(|v| v.as_boolean())
+            .unwrap_or(false);
+
+        self.app_groups = entitlements
+            .and_then(|d| d.get(APP_GROUPS_ENTITLEMENT))
+            .and_then(|v| v.as_array())
+            .map(|groups| {
+                groups
+                    .iter()
+                    .filter_map(|g| g.as_string())
+                    .map(|g| g.to_string())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let vendor = Vendor::from_signature(&self.bundle_id, self.team_id.as_deref());
+        self.organization = vendor.name;
+        self.vendor_prefix = vendor.prefix;
+    }
+
+    /// Construct AppInfo when the bundle is already gone, from what the user knows.
+    /// The name falls back to the last segment of the bundle id, the executable
+    /// stays empty when not given, a guess would match unrelated files and processes
+    pub fn from_identity(name: &str, bundle_id: &str, executable: &str) -> Self {
+        let bundle_id = bundle_id.trim();
+        let last_segment = bundle_id.rsplit('.').next().unwrap_or_default();
+
+        let name = match name.trim() {
+            "" => last_segment.to_string(),
+            name => name.to_string(),
+        };
+        let executable = executable.trim().to_string();
+
+        let vendor = Vendor::from_bundle_id(bundle_id);
+
+        Self {
+            name,
+            bundle_id: bundle_id.to_string(),
+            bundle_name: executable,
+            organization: vendor.name,
+            vendor_prefix: vendor.prefix,
+            ..Default::default()
+        }
+    }
+
+    /// First .app inside the Wrapper folder of an iOS app
+    fn wrapped_bundle(app_path: &Path) -> Option<PathBuf> {
+        std::fs::read_dir(app_path.join("Wrapper"))
+            .ok()?
+            .filter_map(|e| e.ok())
+            .map(|e| e.path())
+            .find(|p| p.extension().map(|ext| ext == "app").unwrap_or(false))
+    }
+
     pub fn associate_path_matches(&self, path: &Path) -> bool {
-        self.rules_matches(
-            path,
-            &[
-                (MatchRules::Equal, &self.name),
-                (MatchRules::Equal, &self.bundle_name),
-                (MatchRules::Equal, &self.organization),
-                (MatchRules::Contain, &self.bundle_id),
-            ],
-        )
-        // path_equals_ignore_case(path, &self.name)
-        //     || path_equals_ignore_case(path, &self.bundle_name)
-        //     || path_equals_ignore_case(path, &self.organization)
-        //     || path_contains_ignore_case(path, &self.bundle_id)
+        self.associate_path_matches_with(path, &self.associate_rules())
     }
 
-    pub fn rules_matches(&self, path: &Path, rules: &[(MatchRules, &str)]) -> bool {
+    /// Same as associate_path_matches with rules from associate_rules
+    pub fn associate_path_matches_with(&self, path: &Path, rules: &[MatchRule]) -> bool {
+        self.rules_matches(path, rules) || self.vendor_matches(path)
+    }
+
+    /// Rules for the files of the app, built once for a whole scan
+    pub fn associate_rules(&self) -> Vec<MatchRule<'_>> {
+        let mut rules = vec![
+            MatchRule::new(MatchRules::Equal, self.name.as_str()),
+            MatchRule::new(MatchRules::Equal, self.bundle_name.as_str()),
+            // Bundle ids are never spelled with other separators
+            MatchRule::new(MatchRules::Contain, self.bundle_id.as_str()).keep_separators(),
+        ];
+
+        // Sandboxed extensions get their own container named after their bundle id
+        for bundle in &self.embedded {
+            rules.push(
+                MatchRule::new(MatchRules::Contain, bundle.bundle_id.as_str()).keep_separators(),
+            );
+        }
+
+        // Group containers are named after the app group entitlement, or
+        // "<team id>.<bundle id>" which the bundle id rule matches. Other
+        // containers of the team are shared with its other apps
+        for group in &self.app_groups {
+            rules.push(MatchRule::new(MatchRules::Equal, group.as_str()).keep_separators());
+        }
+
         rules
-            .iter()
-            .any(|(rule, value)| rule.match_path(path, value))
+    }
+
+    /// Vendor of the app, with the team id of its signature
+    pub fn vendor(&self) -> Vendor {
+        Vendor {
+            prefix: self.vendor_prefix.clone(),
+            name: self.organization.clone(),
+            team_id: self.team_id.clone(),
+        }
+    }
+
+    /// Names under the vendor prefix or the team id that still mention the app,
+    /// like "com.vendor.app-updater" for "com.vendor.App"
+    /// or "EQHXZ8M8AV.app.settings" for an app signed by that team
+    pub fn vendor_matches(&self, path: &Path) -> bool {
+        let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+            return false;
+        };
+
+        let normalization = Normalization::default();
+        let mentions_app = |rest: &str| {
+            let rest = normalization.apply(rest);
+            [&self.bundle_name, &self.name]
+                .iter()
+                .filter(|n| !n.is_empty())
+                .any(|n| rest.contains(&normalization.apply(n)))
+        };
+
+        let by_prefix = !self.vendor_prefix.is_empty()
+            && normalization
+                .apply(name)
+                .strip_prefix(&normalization.apply(&format!("{}.", self.vendor_prefix)))
+                .is_some_and(mentions_app);
+
+        by_prefix || self.vendor().strip_team(name).is_some_and(mentions_app)
+    }
+
+    /// Any rule matches and no Not rule rejects the path
+    pub fn rules_matches(&self, path: &Path, rules: &[MatchRule]) -> bool {
+        rules_match(rules, path)
     }
 }
 
@@ -103,10 +357,22 @@ impl Default for AppInfo {
     fn default() -> Self {
         Self {
             path: PathBuf::new(),
+            kind: BundleKind::App,
             name: String::new(),
             bundle_id: String::new(),
             bundle_name: String::new(),
             organization: String::new(),
+            vendor_prefix: String::new(),
+            embedded: Vec::new(),
+            short_name: None,
+            version: None,
+            build_version: None,
+            min_system_version: None,
+            icon_file: None,
+            team_id: None,
+            sandboxed: false,
+            app_groups: Vec::new(),
+            install_source: InstallSource::default(),
         }
     }
 }
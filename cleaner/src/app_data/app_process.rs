@@ -1,10 +1,11 @@
-use anyhow::Result;
 use rayon::prelude::*;
 use std::ffi::OsString;
+use std::path::{Path, PathBuf};
 use sysinfo::{ProcessesToUpdate, System};
 
 use crate::AppInfo;
-use crate::syscom::kill_pids;
+use crate::error::Result;
+use crate::syscom::kill_pid;
 use common_debug::debug_dev;
 
 #[derive(Debug, Clone)]
@@ -27,12 +28,7 @@ impl AppProcess {
         let mut sys = System::new();
         sys.refresh_processes(ProcessesToUpdate::All, true);
 
-        let patterns = [
-            app.bundle_name.clone(),
-            app.bundle_id.clone(),
-            app.organization.clone(),
-            format!("{} Helper", app.bundle_name),
-        ];
+        let patterns = ProcessPatterns::of(app);
 
         sys.processes()
             .par_iter()
@@ -56,12 +52,7 @@ impl AppProcess {
                     patterns
                 );
 
-                // Match if command line contains pattern OR process name contains pattern
-                let is_match = patterns
-                    .iter()
-                    .any(|pat| cmd_line.contains(pat) || process_name.contains(pat));
-
-                if is_match {
+                if patterns.matches(&cmd_line, &process_name, process.exe()) {
                     Some(Self::new(
                         pid.as_u32() as i32,
                         cmd_line,
@@ -83,13 +74,77 @@ impl AppProcess {
         let mut killed_count = 0;
 
         for p in processes {
-            if kill_pids(&p.pid.to_string()).is_ok() {
-                killed_count += 1;
-            } else {
-                eprintln!("Failed to kill PID {} for {}", p.pid, app_name);
+            match kill_pid(p.pid) {
+                Ok(()) => killed_count += 1,
+                Err(err) => eprintln!("{} for {}", err, app_name),
             }
         }
 
         Ok(killed_count)
     }
 }
+
+/// What tells that a running process belongs to the app
+#[derive(Debug, Clone, Default)]
+pub struct ProcessPatterns {
+    /// Matched anywhere in the command line or the process name
+    names: Vec<String>,
+    /// Binaries inside the app bundle, matched exactly
+    executables: Vec<PathBuf>,
+    /// Any binary started from inside the bundle
+    bundle: Option<PathBuf>,
+}
+
+impl ProcessPatterns {
+    /// Embedded helpers are only matched by bundle id or by their binary
+    /// inside the bundle, their executable names are often as generic
+    /// as "Installer" or "Downloader".
+    /// Without a bundle on disk only the bundle id is known for sure
+    pub fn of(app: &AppInfo) -> Self {
+        let has_bundle = !app.path.as_os_str().is_empty();
+
+        let mut names = vec![app.bundle_id.clone()];
+        if has_bundle && !app.bundle_name.is_empty() {
+            names.push(app.bundle_name.clone());
+            names.push(format!("{} Helper", app.bundle_name));
+        }
+
+        let mut executables = Vec::new();
+        for bundle in &app.embedded {
+            names.push(bundle.bundle_id.clone());
+            executables.extend(bundle.executable_path());
+        }
+
+        // An empty pattern would match every process
+        names.retain(|name| !name.is_empty());
+
+        Self {
+            names,
+            executables,
+            bundle: has_bundle.then(|| app.path.clone()),
+        }
+    }
+
+    pub fn matches(&self, cmd_line: &str, process_name: &str, exe: Option<&Path>) -> bool {
+        let name_match = self
+            .names
+            .iter()
+            .any(|pat| cmd_line.contains(pat.as_str()) || process_name.contains(pat.as_str()));
+
+        // The command line starts with the binary when the exe is not readable
+        let exe_match = self.executables.iter().any(|path| {
+            let path_str = path.to_string_lossy();
+            exe == Some(path.as_path())
+                || cmd_line
+                    .strip_prefix(path_str.as_ref())
+                    .is_some_and(|rest| rest.is_empty() || rest.starts_with(' '))
+        });
+
+        let bundle_match = match (&self.bundle, exe) {
+            (Some(bundle), Some(exe)) => exe.starts_with(bundle),
+            _ => false,
+        };
+
+        name_match || exe_match || bundle_match
+    }
+}
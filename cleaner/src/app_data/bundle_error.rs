@@ -0,0 +1,59 @@
+use std::fmt;
+use std::path::PathBuf;
+
+/// Reasons why a dropped item can not be used as uninstall target
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BundleError {
+    NotFound(PathBuf),
+    NotABundle(PathBuf),
+    InfoPlistMissing(PathBuf),
+    InvalidPlist { path: PathBuf, reason: String },
+    MissingBundleId(PathBuf),
+}
+
+impl BundleError {
+    pub fn path(&self) -> &PathBuf {
+        match self {
+            BundleError::NotFound(path)
+            | BundleError::NotABundle(path)
+            | BundleError::InfoPlistMissing(path)
+            | BundleError::InvalidPlist { path, .. }
+            | BundleError::MissingBundleId(path) => path,
+        }
+    }
+
+    /// Short advice for the user on what to do about it
+    pub fn hint(&self) -> &'static str {
+        match self {
+            BundleError::NotFound(_) => "The item was moved or deleted, try to drop it again",
+            BundleError::NotABundle(_) => "Drop an .app, .prefPane, .plugin or .qlgenerator bundle",
+            BundleError::InfoPlistMissing(_) => {
+                "The bundle is incomplete or damaged, it has no Info.plist"
+            }
+            BundleError::InvalidPlist { .. } => "The Info.plist of the bundle can not be read",
+            BundleError::MissingBundleId(_) => {
+                "The bundle has no identifier, its files can not be matched"
+            }
+        }
+    }
+}
+
+impl fmt::Display for BundleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BundleError::NotFound(path) => write!(f, "{} does not exist", path.display()),
+            BundleError::NotABundle(path) => write!(f, "{} is not a bundle", path.display()),
+            BundleError::InfoPlistMissing(path) => {
+                write!(f, "Info.plist not found in {}", path.display())
+            }
+            BundleError::InvalidPlist { path, reason } => {
+                write!(f, "Failed to read plist {}: {}", path.display(), reason)
+            }
+            BundleError::MissingBundleId(path) => {
+                write!(f, "CFBundleIdentifier not found in {}", path.display())
+            }
+        }
+    }
+}
+
+impl std::error::Error for BundleError {}
@@ -0,0 +1,119 @@
+use plist::Value;
+use std::path::{Path, PathBuf};
+
+use common_debug::debug_dev;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmbeddedKind {
+    PlugIn,
+    LoginItem,
+    XpcService,
+    Helper,
+    SystemExtension,
+}
+
+impl EmbeddedKind {
+    /// Folder inside the app bundle and the extension of the bundles it holds
+    fn location(&self) -> (&'static str, &'static str) {
+        match self {
+            EmbeddedKind::PlugIn => ("Contents/PlugIns", "appex"),
+            EmbeddedKind::LoginItem => ("Contents/Library/LoginItems", "app"),
+            EmbeddedKind::XpcService => ("Contents/XPCServices", "xpc"),
+            EmbeddedKind::Helper => ("Contents/Frameworks", "app"),
+            EmbeddedKind::SystemExtension => {
+                ("Contents/Library/SystemExtensions", "systemextension")
+            }
+        }
+    }
+
+    fn accepts(&self, path: &Path) -> bool {
+        let (_, ext) = self.location();
+        if !path.extension().map(|e| e == ext).unwrap_or(false) {
+            return false;
+        }
+
+        // Frameworks holds a lot of things, only the helper apps are interesting
+        match self {
+            EmbeddedKind::Helper => path
+                .file_name()
+                .map(|n| n.to_string_lossy().contains("Helper"))
+                .unwrap_or(false),
+            _ => true,
+        }
+    }
+}
+
+/// Bundle shipped inside the app, like extensions, login items or helpers
+#[derive(Debug, Clone)]
+pub struct EmbeddedBundle {
+    pub kind: EmbeddedKind,
+    pub path: PathBuf,
+    pub bundle_id: String,
+    pub executable: Option<String>,
+}
+
+impl EmbeddedBundle {
+    const KINDS: [EmbeddedKind; 5] = [
+        EmbeddedKind::PlugIn,
+        EmbeddedKind::LoginItem,
+        EmbeddedKind::XpcService,
+        EmbeddedKind::Helper,
+        EmbeddedKind::SystemExtension,
+    ];
+
+    /// List all embedded bundles of the .app at the given path
+    /// bundles without a readable bundle id are skipped
+    pub fn inspect(app_path: &Path) -> Vec<Self> {
+        let mut bundles = Vec::new();
+
+        for kind in Self::KINDS {
+            let (folder, _) = kind.location();
+            let Ok(entries) = std::fs::read_dir(app_path.join(folder)) else {
+                continue;
+            };
+
+            for entry in entries.filter_map(|e| e.ok()) {
+                let path = entry.path();
+                if !kind.accepts(&path) {
+                    continue;
+                }
+
+                if let Some(bundle) = Self::from_path(kind, &path) {
+                    debug_dev!(
+                        "embedded {:?}: {} ({})",
+                        bundle.kind,
+                        bundle.bundle_id,
+                        bundle.path.display()
+                    );
+                    bundles.push(bundle);
+                }
+            }
+        }
+
+        bundles
+    }
+
+    /// Binary the bundle runs, processes started from it belong to the app
+    pub fn executable_path(&self) -> Option<PathBuf> {
+        let exe = self.executable.as_ref()?;
+        Some(self.path.join("Contents/MacOS").join(exe))
+    }
+
+    fn from_path(kind: EmbeddedKind, path: &Path) -> Option<Self> {
+        let plist = Value::from_file(path.join("Contents").join("Info.plist")).ok()?;
+        let dict = plist.as_dictionary()?;
+
+        let bundle_id = dict.get("CFBundleIdentifier")?.as_string()?.to_string();
+        let executable = dict
+            .get("CFBundleExecutable")
+            .and_then(|v| v.as_string())
+            .map(|s| s.to_string());
+
+        Some(Self {
+            kind,
+            path: path.to_path_buf(),
+            bundle_id,
+            executable,
+        })
+    }
+}
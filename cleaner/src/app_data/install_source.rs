@@ -0,0 +1,93 @@
+use std::path::{Path, PathBuf};
+
+use crate::app_data::BundleKind;
+use crate::homebrew::HomebrewCask;
+
+/// How the app got on this Mac, it tells where its data is most likely kept
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub enum InstallSource {
+    /// Copied to Applications from a disk image or an archive
+    #[default]
+    DragInstall,
+    AppStore,
+    /// Installer package, with the package id of its receipt
+    Package(String),
+    /// Homebrew cask, with its token and the prefix of the brew that installed it
+    Homebrew {
+        token: String,
+        prefix: PathBuf,
+    },
+}
+
+impl InstallSource {
+    /// What can be told from the bundle alone
+    pub fn from_bundle(app_path: &Path, kind: BundleKind) -> Self {
+        // iOS apps on a Mac only come from the App Store
+        if kind == BundleKind::WrappedIos || Self::has_mas_receipt(app_path) {
+            InstallSource::AppStore
+        } else {
+            InstallSource::DragInstall
+        }
+    }
+
+    fn has_mas_receipt(app_path: &Path) -> bool {
+        app_path
+            .join("Contents")
+            .join("_MASReceipt")
+            .join("receipt")
+            .is_file()
+    }
+
+    /// Refine the source with the cask and the package receipts found for the app.
+    /// The App Store wins, a cask may itself run an installer package
+    pub fn detect(
+        app_path: &Path,
+        kind: BundleKind,
+        cask: Option<&HomebrewCask>,
+        bom_files: &[PathBuf],
+    ) -> Self {
+        if let source @ InstallSource::AppStore = Self::from_bundle(app_path, kind) {
+            return source;
+        }
+
+        if let Some(cask) = cask {
+            return InstallSource::Homebrew {
+                token: cask.token.clone(),
+                prefix: cask.prefix.clone(),
+            };
+        }
+
+        // Receipts are named "<package id>.bom"
+        let package_id = bom_files
+            .iter()
+            .filter_map(|bom| bom.file_stem())
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .min();
+
+        match package_id {
+            Some(id) => InstallSource::Package(id),
+            None => InstallSource::DragInstall,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            InstallSource::DragInstall => "Drag-install",
+            InstallSource::AppStore => "App Store",
+            InstallSource::Package(_) => "Installer package",
+            InstallSource::Homebrew { .. } => "Homebrew",
+        }
+    }
+
+    /// Sentence for the summary, like "Installed with Homebrew cask 'tool'"
+    pub fn describe(&self) -> String {
+        match self {
+            InstallSource::DragInstall => "Installed by dragging it to Applications".to_string(),
+            InstallSource::AppStore => "Installed from the App Store".to_string(),
+            InstallSource::Package(id) => format!("Installed with installer package '{}'", id),
+            InstallSource::Homebrew { token, .. } => {
+                format!("Installed with Homebrew cask '{}'", token)
+            }
+        }
+    }
+}
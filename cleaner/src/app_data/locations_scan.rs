@@ -1,6 +1,7 @@
 use std::env;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 // =======
+use crate::app_data::InstallSource;
 use crate::syscom::sysconf_path;
 use crate::syscom::{DARWIN_USER_CACHE_DIR, DARWIN_USER_TEMP_DIR};
 
@@ -75,6 +76,47 @@ impl LocationsScan {
             .cloned()
             .collect()
     }
+
+    /// Add the folders of a Homebrew prefix, like /opt/homebrew on Apple silicon
+    pub fn add_homebrew_prefix(&mut self, prefix: &Path) {
+        for dir in ["bin", "etc", "opt", "sbin", "share", "var"] {
+            let path = prefix.join(dir);
+            if !self.paths.contains(&path) {
+                self.paths.push(path);
+            }
+        }
+    }
+
+    /// Move the locations where apps of this source keep their data first.
+    /// Call add_homebrew_prefix before for Homebrew apps
+    pub fn prioritize(&mut self, source: &InstallSource) {
+        let is_preferred = |path: &PathBuf| match source {
+            // Sandboxed apps keep everything in their containers
+            InstallSource::AppStore => [
+                "Library/Containers",
+                "Library/Group Containers",
+                "Library/Application Scripts",
+            ]
+            .iter()
+            .any(|dir| path.ends_with(dir)),
+            // Installers put daemons and helpers system-wide
+            InstallSource::Package(_) => [
+                "/Library/LaunchDaemons",
+                "/Library/LaunchAgents",
+                "/Library/PrivilegedHelperTools",
+                "/Library/Application Support",
+                "/Library/Extensions",
+                "/private/var/db/receipts",
+            ]
+            .iter()
+            .any(|dir| path == Path::new(dir)),
+            InstallSource::Homebrew { prefix, .. } => path.starts_with(prefix),
+            InstallSource::DragInstall => false,
+        };
+
+        // Stable, the usual order is kept inside both groups
+        self.paths.sort_by_key(|p| !is_preferred(p));
+    }
 }
 
 impl Default for LocationsScan {
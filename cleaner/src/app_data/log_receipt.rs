@@ -1,10 +1,11 @@
-use anyhow::{Context, Result};
 use rayon::prelude::*;
 use std::path::{Path, PathBuf};
 
 use crate::app_data::AppInfo;
 use crate::app_data::LocationsScan;
-use crate::app_data::app_info::MatchRules;
+use crate::app_data::{MatchRule, MatchRules};
+use crate::cancel::CancelToken;
+use crate::error::{CleanerError, Result};
 use crate::syscom::run_lsbom_command;
 
 #[derive(Debug, Default, Clone)]
@@ -21,15 +22,14 @@ impl LogReceipt {
                 for entry in entries.filter_map(|e| e.ok()) {
                     let path = entry.path();
                     if path.extension().map(|ext| ext == "bom").unwrap_or(false)
-                        && app.rules_matches(
+                        && (app.rules_matches(
                             &path,
                             &[
-                                (MatchRules::Contain, &app.name),
-                                (MatchRules::Contain, &app.bundle_name),
-                                (MatchRules::Contain, &app.organization),
-                                (MatchRules::Contain, &app.bundle_id),
+                                MatchRule::new(MatchRules::Contain, app.name.as_str()),
+                                MatchRule::new(MatchRules::Contain, app.bundle_name.as_str()),
+                                MatchRule::new(MatchRules::Contain, app.bundle_id.as_str()),
                             ],
-                        )
+                        ) || app.vendor_matches(&path))
                     {
                         bom_files.push(path);
                     }
@@ -42,25 +42,33 @@ impl LogReceipt {
         }
     }
 
-    //// Save all BOM files to the given log directory in parallel
-    pub fn save_bom_log(&self, log_dir: &Path) -> Result<()> {
-        std::fs::create_dir_all(log_dir)
-            .with_context(|| format!("Failed to create log folder: {}", log_dir.display()))?;
+    //// Save all BOM files to the given log directory in parallel,
+    /// files not started yet are skipped once the token is cancelled
+    pub fn save_bom_log(&self, log_dir: &Path, cancel: &CancelToken) -> Result<()> {
+        std::fs::create_dir_all(log_dir).map_err(|e| CleanerError::io(log_dir, e))?;
 
         // Use par_iter() for parallel processing
         let results: Vec<Result<()>> = self
             .bom_file
             .par_iter()
             .map(|bom_file| {
+                cancel.check()?;
+
                 let output_file = bom_file
                     .file_name()
                     .map(|n| log_dir.join(n).with_extension("log"))
-                    .context("BOM file has no filename")?;
+                    .ok_or_else(|| CleanerError::BomParse {
+                        path: bom_file.clone(),
+                        reason: "BOM file has no filename".to_string(),
+                    })?;
 
                 run_lsbom_command(bom_file, &output_file)
             })
             .collect();
 
+        // Cancelled first, the other errors do not matter then
+        cancel.check()?;
+
         // Collect all errors, return the first one if any
         results.into_iter().collect::<Result<()>>()
     }
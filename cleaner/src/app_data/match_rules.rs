@@ -0,0 +1,401 @@
+use regex::{Regex, RegexBuilder};
+use std::borrow::Cow;
+use std::env;
+use std::path::{Path, PathBuf};
+use unicode_normalization::UnicodeNormalization;
+
+use crate::error::{CleanerError, Result};
+use crate::helpers::{MatchScope, Normalization};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MatchRules {
+    Equal,
+    Contain,
+    Prefix,
+    Suffix,
+    /// Shell pattern over the whole name, like "com.vendor.*"
+    Glob,
+    /// Regular expression, unanchored unless the pattern says otherwise
+    Regex,
+    /// Excludes what the inner rule matches
+    Not(Box<MatchRules>),
+}
+
+impl MatchRules {
+    pub fn negate(self) -> Self {
+        MatchRules::Not(Box::new(self))
+    }
+
+    pub fn is_not(&self) -> bool {
+        matches!(self, MatchRules::Not(_))
+    }
+
+    /// The rule under any number of Not
+    fn base(&self) -> &MatchRules {
+        match self {
+            MatchRules::Not(inner) => inner.base(),
+            other => other,
+        }
+    }
+
+    pub fn match_path(&self, path: &Path, value: &str) -> bool {
+        self.match_path_with(path, value, &MatchOptions::default())
+    }
+
+    /// Patterns are compiled on every call, keep a MatchRule to match many paths
+    pub fn match_path_with(&self, path: &Path, value: &str, options: &MatchOptions) -> bool {
+        let compiled = compile(self.base(), value, options).ok().flatten();
+        self.match_compiled(path, value, options, compiled.as_ref())
+    }
+
+    fn match_compiled(
+        &self,
+        path: &Path,
+        value: &str,
+        options: &MatchOptions,
+        compiled: Option<&Regex>,
+    ) -> bool {
+        if let MatchRules::Not(inner) = self {
+            return !inner.match_compiled(path, value, options, compiled);
+        }
+
+        if let MatchRules::Glob | MatchRules::Regex = self {
+            // An invalid pattern matches nothing
+            let Some(regex) = compiled else {
+                return false;
+            };
+            return options
+                .scope
+                .names(path)
+                .iter()
+                .any(|name| regex.is_match(&name.nfd().collect::<String>()));
+        }
+
+        let value = options.normalization.apply(value);
+        // Nothing left of the value would match every path
+        if value.is_empty() {
+            return false;
+        }
+
+        options.scope.names(path).iter().any(|name| {
+            let name = options.normalization.apply(name);
+            match self {
+                MatchRules::Equal => name == value,
+                MatchRules::Contain => name.contains(&value),
+                MatchRules::Prefix => name.starts_with(&value),
+                MatchRules::Suffix => name.ends_with(&value),
+                _ => false,
+            }
+        })
+    }
+
+    fn from_keyword(keyword: &str) -> Option<Self> {
+        match keyword {
+            "equal" => Some(MatchRules::Equal),
+            "contain" => Some(MatchRules::Contain),
+            "prefix" => Some(MatchRules::Prefix),
+            "suffix" => Some(MatchRules::Suffix),
+            "glob" => Some(MatchRules::Glob),
+            "regex" => Some(MatchRules::Regex),
+            _ => None,
+        }
+    }
+}
+
+/// Regex of a Glob or Regex rule, None for the other rules.
+/// Patterns only follow the case option, separators are part of the pattern
+fn compile(kind: &MatchRules, value: &str, options: &MatchOptions) -> Result<Option<Regex>> {
+    let pattern = match kind {
+        MatchRules::Glob => glob_to_regex(value),
+        MatchRules::Regex => value.to_string(),
+        _ => return Ok(None),
+    };
+
+    RegexBuilder::new(&pattern.nfd().collect::<String>())
+        .case_insensitive(!options.normalization.case_sensitive)
+        .build()
+        .map(Some)
+        .map_err(|e| CleanerError::InvalidRule {
+            rule: value.to_string(),
+            reason: e.to_string(),
+        })
+}
+
+/// "*" is any run of characters, "?" a single one and "[a-z]" or "[!a-z]" a class
+fn glob_to_regex(glob: &str) -> String {
+    let mut regex = String::from("^");
+    let mut chars = glob.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '*' => regex.push_str(".*"),
+            '?' => regex.push('.'),
+            '[' => {
+                let mut class = String::new();
+                if chars.peek() == Some(&'!') {
+                    chars.next();
+                    class.push('^');
+                }
+                let mut closed = false;
+                for c in chars.by_ref() {
+                    if c == ']' {
+                        closed = true;
+                        break;
+                    }
+                    // Set operators of the regex syntax are plain characters here
+                    if matches!(c, '\\' | '[' | '&' | '~') {
+                        class.push('\\');
+                    }
+                    class.push(c);
+                }
+                if closed {
+                    regex.push('[');
+                    regex.push_str(&class);
+                    regex.push(']');
+                } else {
+                    // A lone "[" is taken literally
+                    regex.push_str(&regex::escape(&format!("[{}", class)));
+                }
+            }
+            c => regex.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+
+    regex.push('$');
+    regex
+}
+
+/// How a rule compares, ignoring case and separators in the file name by default
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct MatchOptions {
+    pub normalization: Normalization,
+    pub scope: MatchScope,
+}
+
+/// A match rule with the value it compares to and its own options
+#[derive(Debug, Clone)]
+pub struct MatchRule<'a> {
+    pub kind: MatchRules,
+    pub value: Cow<'a, str>,
+    pub options: MatchOptions,
+    compiled: Option<Regex>,
+}
+
+impl<'a> MatchRule<'a> {
+    /// An invalid Glob or Regex pattern matches nothing, use try_new to catch it
+    pub fn new(kind: MatchRules, value: impl Into<Cow<'a, str>>) -> Self {
+        let mut rule = Self {
+            kind,
+            value: value.into(),
+            options: MatchOptions::default(),
+            compiled: None,
+        };
+        rule.compiled = rule.compile().ok().flatten();
+        rule
+    }
+
+    pub fn try_new(kind: MatchRules, value: impl Into<Cow<'a, str>>) -> Result<Self> {
+        let rule = Self::new(kind, value);
+        if rule.compiled.is_none() {
+            // Surfaces the error of an invalid pattern
+            rule.compile()?;
+        }
+        Ok(rule)
+    }
+
+    fn compile(&self) -> Result<Option<Regex>> {
+        compile(self.kind.base(), &self.value, &self.options)
+    }
+
+    pub fn case_sensitive(mut self) -> Self {
+        self.options.normalization.case_sensitive = true;
+        self.compiled = self.compile().ok().flatten();
+        self
+    }
+
+    pub fn keep_separators(mut self) -> Self {
+        self.options.normalization.strip_separators = false;
+        self
+    }
+
+    /// Match any component of the path, not only the file name
+    pub fn in_components(mut self) -> Self {
+        self.options.scope = MatchScope::Components;
+        self
+    }
+
+    pub fn matches(&self, path: &Path) -> bool {
+        self.kind
+            .match_compiled(path, &self.value, &self.options, self.compiled.as_ref())
+    }
+
+    /// Parse a line like "not case-sensitive equal com.vendor.Shared".
+    /// Flags and "not" come first, then the rule, the rest of the line is the value
+    pub fn parse(line: &str) -> Result<MatchRule<'static>> {
+        let invalid = |reason: &str| CleanerError::InvalidRule {
+            rule: line.trim().to_string(),
+            reason: reason.to_string(),
+        };
+
+        let mut rest = line.trim();
+        let mut negate = false;
+        let mut options = MatchOptions::default();
+
+        let kind = loop {
+            let (word, tail) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+            rest = tail.trim_start();
+
+            match word {
+                "not" => negate = !negate,
+                "case-sensitive" => options.normalization.case_sensitive = true,
+                "keep-separators" => options.normalization.strip_separators = false,
+                "components" => options.scope = MatchScope::Components,
+                "" => return Err(invalid("missing rule")),
+                word => {
+                    break MatchRules::from_keyword(word)
+                        .ok_or_else(|| invalid(&format!("unknown rule '{}'", word)))?;
+                }
+            }
+        };
+
+        let value = rest.trim_end();
+        if value.is_empty() {
+            return Err(invalid("missing value"));
+        }
+
+        let mut rule = MatchRule {
+            kind: if negate { kind.negate() } else { kind },
+            value: Cow::Owned(value.to_string()),
+            options,
+            compiled: None,
+        };
+        rule.compiled = rule.compile()?;
+
+        Ok(rule)
+    }
+}
+
+/// Whether any rule includes the path and no Not rule excludes it
+pub fn rules_match(rules: &[MatchRule], path: &Path) -> bool {
+    let (excludes, includes): (Vec<&MatchRule>, Vec<&MatchRule>) =
+        rules.iter().partition(|r| r.kind.is_not());
+
+    includes.iter().any(|r| r.matches(path)) && excludes.iter().all(|r| r.matches(path))
+}
+
+/// Rules written by the user, one per line, "#" starts a comment.
+/// A rules file groups them in one `[bundle id]` section per app
+#[derive(Debug, Clone, Default)]
+pub struct MatchRuleSet {
+    pub rules: Vec<MatchRule<'static>>,
+    /// Lines written for other apps that can not be used
+    pub problems: Vec<CleanerError>,
+}
+
+impl MatchRuleSet {
+    pub fn parse(text: &str) -> Result<Self> {
+        let rules = text
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| {
+                let line = line.trim();
+                !line.is_empty() && !line.starts_with('#')
+            })
+            .map(|(i, line)| Self::parse_line(i, line))
+            .collect::<Result<_>>()?;
+
+        Ok(Self {
+            rules,
+            problems: Vec::new(),
+        })
+    }
+
+    // Errors tell the line, counted from 1
+    fn parse_line(i: usize, line: &str) -> Result<MatchRule<'static>> {
+        MatchRule::parse(line).map_err(|err| match err {
+            CleanerError::InvalidRule { rule, reason } => CleanerError::InvalidRule {
+                rule,
+                reason: format!("line {}: {}", i + 1, reason),
+            },
+            other => other,
+        })
+    }
+
+    /// Rules of a rules file that apply to one app. Each rule goes under
+    /// the `[bundle id]` section of the app it is written for.
+    /// A bad rule of the app fails, the bad lines of other apps are only problems
+    pub fn parse_for(text: &str, bundle_id: &str) -> Result<Self> {
+        let mut section: Option<&str> = None;
+        let mut set = Self::default();
+
+        for (i, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Some(name) = line.strip_prefix('[').and_then(|s| s.strip_suffix(']')) {
+                section = Some(name.trim());
+                continue;
+            }
+            let Some(name) = section else {
+                set.problems.push(CleanerError::InvalidRule {
+                    rule: line.to_string(),
+                    reason: format!("line {}: rules go under a [bundle id] section", i + 1),
+                });
+                continue;
+            };
+            // Rules of other apps are checked too, a typo shows up on any scan
+            match Self::parse_line(i, line) {
+                Ok(rule) if name.eq_ignore_ascii_case(bundle_id) => set.rules.push(rule),
+                Err(err) if name.eq_ignore_ascii_case(bundle_id) => return Err(err),
+                Ok(_) => {}
+                Err(err) => set.problems.push(err),
+            }
+        }
+
+        Ok(set)
+    }
+
+    pub fn from_file(path: &Path, bundle_id: &str) -> Result<Self> {
+        let text = std::fs::read_to_string(path).map_err(|e| CleanerError::io(path, e))?;
+        Self::parse_for(&text, bundle_id)
+    }
+
+    /// ~/Library/Application Support/Bristo/rules
+    pub fn user_rules_path() -> PathBuf {
+        env::var("HOME")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| PathBuf::from("/Users/Unknown"))
+            .join("Library/Application Support/Bristo/rules")
+    }
+
+    /// Rules of the file for the app, empty when there is no such file
+    pub fn load(path: &Path, bundle_id: &str) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        Self::from_file(path, bundle_id)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// A rule that is not a Not matches the path
+    pub fn includes(&self, path: &Path) -> bool {
+        self.rules
+            .iter()
+            .any(|r| !r.kind.is_not() && r.matches(path))
+    }
+
+    /// A Not rule rejects the path
+    pub fn excludes(&self, path: &Path) -> bool {
+        self.rules
+            .iter()
+            .any(|r| r.kind.is_not() && !r.matches(path))
+    }
+
+    pub fn matches(&self, path: &Path) -> bool {
+        rules_match(&self.rules, path)
+    }
+}
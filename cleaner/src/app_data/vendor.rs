@@ -0,0 +1,84 @@
+/// Segments of hosting services that say nothing about who made the app
+const GENERIC_SEGMENTS: [&str; 8] = [
+    "github",
+    "gitlab",
+    "bitbucket",
+    "sourceforge",
+    "codeberg",
+    "googlecode",
+    "sr",
+    "example",
+];
+
+/// Vendor part of a reverse-DNS bundle id, and who signed the app when known
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Vendor {
+    /// Bundle id without the last segment, like "io.github.user" for "io.github.user.app"
+    pub prefix: String,
+    /// First segment after the top level domain that is not a hosting service,
+    /// the team id when the bundle id has none
+    pub name: String,
+    /// Team id of the code signature, like "EQHXZ8M8AV"
+    pub team_id: Option<String>,
+}
+
+impl Vendor {
+    /// Split the vendor from a bundle id, empty when the id has no usable vendor
+    pub fn from_bundle_id(bundle_id: &str) -> Self {
+        let segments: Vec<&str> = bundle_id.split('.').filter(|s| !s.is_empty()).collect();
+
+        // "app" or "com.app" have nothing besides the top level domain
+        if segments.len() < 3 {
+            return Self::default();
+        }
+
+        let prefix_segments = &segments[..segments.len() - 1];
+
+        // "com.github.app" would claim everything hosted on github
+        if Self::is_generic(prefix_segments[prefix_segments.len() - 1]) {
+            return Self::default();
+        }
+
+        let name = prefix_segments[1..]
+            .iter()
+            .find(|s| !Self::is_generic(s))
+            .map(|s| s.to_string())
+            .unwrap_or_default();
+
+        Self {
+            prefix: prefix_segments.join("."),
+            name,
+            team_id: None,
+        }
+    }
+
+    /// Vendor of a signed app. The team id tells who made it
+    /// even when the bundle id is generic, like "com.github.Electron"
+    pub fn from_signature(bundle_id: &str, team_id: Option<&str>) -> Self {
+        let mut vendor = Self::from_bundle_id(bundle_id);
+        vendor.team_id = team_id
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+            .map(str::to_string);
+
+        if vendor.name.is_empty()
+            && let Some(team_id) = &vendor.team_id
+        {
+            vendor.name = team_id.clone();
+        }
+        vendor
+    }
+
+    /// The name without the "TEAMID." prefix of entries shared by the team,
+    /// like group containers and keychain groups
+    pub fn strip_team<'a>(&self, name: &'a str) -> Option<&'a str> {
+        let team_id = self.team_id.as_deref()?;
+        name.strip_prefix(team_id)?.strip_prefix('.')
+    }
+
+    fn is_generic(segment: &str) -> bool {
+        GENERIC_SEGMENTS
+            .iter()
+            .any(|g| g.eq_ignore_ascii_case(segment))
+    }
+}
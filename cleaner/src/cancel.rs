@@ -0,0 +1,34 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::error::{CleanerError, Result};
+
+/// Shared flag to stop a scan or an operation from another thread.
+/// Clones share the flag, cancelling one cancels them all
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// Checkpoint for long work, fails with Cancelled once cancelled
+    pub fn check(&self) -> Result<()> {
+        if self.is_cancelled() {
+            Err(CleanerError::Cancelled)
+        } else {
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,177 @@
+use rayon::prelude::*;
+use std::cmp::Ordering;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use crate::app_data::{AppIcon, AppInfo};
+use crate::helpers::{normalize_lowercase, total_size_of};
+use crate::orphans::{InstalledApps, find_app_bundles};
+use crate::syscom::run_mdls_last_used;
+
+/// Icons in the list are small, no need to decode the big entries
+const CATALOG_ICON_SIZE: u32 = 32;
+
+/// Installed app with what is needed to list it
+#[derive(Debug, Clone)]
+pub struct CatalogEntry {
+    /// Read from the Info.plist only, the signature is read once the app is picked
+    pub info: AppInfo,
+    /// None until loaded for the rows that are shown
+    pub details: Option<CatalogDetails>,
+}
+
+impl CatalogEntry {
+    pub fn icon(&self) -> Option<&AppIcon> {
+        self.details.as_ref()?.icon.as_ref()
+    }
+
+    pub fn size(&self) -> Option<u64> {
+        self.details.as_ref().map(|d| d.size)
+    }
+
+    pub fn last_opened(&self) -> Option<SystemTime> {
+        self.details.as_ref()?.last_opened
+    }
+}
+
+/// What takes a walk of the bundle or a Spotlight query to find out
+#[derive(Debug, Clone, Default)]
+pub struct CatalogDetails {
+    pub icon: Option<AppIcon>,
+    pub size: u64,
+    pub last_opened: Option<SystemTime>,
+}
+
+impl CatalogDetails {
+    pub fn load(info: &AppInfo) -> Self {
+        Self {
+            icon: AppIcon::load(info, CATALOG_ICON_SIZE).ok(),
+            size: total_size_of(&[info.path.as_path()]),
+            last_opened: run_mdls_last_used(&info.path),
+        }
+    }
+
+    /// Details of many entries at once, by their path
+    pub fn load_all(infos: &[AppInfo]) -> Vec<(PathBuf, Self)> {
+        infos
+            .par_iter()
+            .map(|info| (info.path.clone(), Self::load(info)))
+            .collect()
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum CatalogSort {
+    #[default]
+    Name,
+    Version,
+    Size,
+    LastOpened,
+}
+
+/// Browsable list of the installed applications
+#[derive(Debug, Default, Clone)]
+pub struct AppCatalog {
+    pub entries: Vec<CatalogEntry>,
+}
+
+impl AppCatalog {
+    /// Scan the standard Applications folders
+    pub fn new() -> Self {
+        Self::scan(&InstalledApps::default_dirs())
+    }
+
+    /// Scan the given folders, bundles that can not be read are skipped.
+    /// Only the Info.plist of each app is read, details are loaded later
+    pub fn scan(dirs: &[PathBuf]) -> Self {
+        let mut entries: Vec<CatalogEntry> = find_app_bundles(dirs)
+            .par_iter()
+            .filter_map(|path| AppInfo::from_info_plist(path).ok())
+            .map(|info| CatalogEntry {
+                info,
+                details: None,
+            })
+            .collect();
+
+        entries.sort_by(|a, b| Self::compare(a, b, CatalogSort::Name));
+
+        Self { entries }
+    }
+
+    /// Apps of the first `limit` entries matching the query, in the current
+    /// order, whose details are not loaded yet
+    pub fn missing_details(&self, query: &str, limit: usize) -> Vec<AppInfo> {
+        self.search(query)
+            .into_iter()
+            .take(limit)
+            .filter(|e| e.details.is_none())
+            .map(|e| e.info.clone())
+            .collect()
+    }
+
+    pub fn entry(&self, path: &Path) -> Option<&CatalogEntry> {
+        self.entries.iter().find(|e| e.info.path == path)
+    }
+
+    pub fn set_details(&mut self, details: Vec<(PathBuf, CatalogDetails)>) {
+        for (path, details) in details {
+            if let Some(entry) = self.entries.iter_mut().find(|e| e.info.path == path) {
+                entry.details = Some(details);
+            }
+        }
+    }
+
+    /// Entries whose name or bundle id contains the query
+    pub fn search(&self, query: &str) -> Vec<&CatalogEntry> {
+        let query = normalize_lowercase(query.trim());
+
+        self.entries
+            .iter()
+            .filter(|e| {
+                query.is_empty()
+                    || normalize_lowercase(&e.info.name).contains(&query)
+                    || normalize_lowercase(&e.info.bundle_id).contains(&query)
+            })
+            .collect()
+    }
+
+    pub fn sort_by(&mut self, sort: CatalogSort, ascending: bool) {
+        self.entries.sort_by(|a, b| {
+            let order = Self::compare(a, b, sort);
+            if ascending { order } else { order.reverse() }
+        });
+    }
+
+    fn compare(a: &CatalogEntry, b: &CatalogEntry, sort: CatalogSort) -> Ordering {
+        let by_name = || normalize_lowercase(&a.info.name).cmp(&normalize_lowercase(&b.info.name));
+
+        match sort {
+            CatalogSort::Name => by_name(),
+            CatalogSort::Version => compare_versions(
+                a.info.version.as_deref().unwrap_or_default(),
+                b.info.version.as_deref().unwrap_or_default(),
+            ),
+            // Entries without details yet come first
+            CatalogSort::Size => a.size().cmp(&b.size()),
+            CatalogSort::LastOpened => a.last_opened().cmp(&b.last_opened()),
+        }
+        .then_with(by_name)
+    }
+}
+
+/// Compare versions like "1.10.2" and "1.9" number by number
+fn compare_versions(a: &str, b: &str) -> Ordering {
+    let numbers = |v: &str| -> Vec<u64> {
+        v.split(['.', '-', ' '])
+            .map(|p| {
+                p.chars()
+                    .take_while(|c| c.is_ascii_digit())
+                    .collect::<String>()
+                    .parse()
+                    .unwrap_or(0)
+            })
+            .collect()
+    };
+
+    numbers(a).cmp(&numbers(b))
+}
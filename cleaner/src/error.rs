@@ -0,0 +1,169 @@
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::app_data::BundleError;
+
+pub type Result<T> = std::result::Result<T, CleanerError>;
+
+/// Settings page of Full Disk Access in System Settings
+pub const FULL_DISK_ACCESS_URL: &str =
+    "x-apple.systempreferences:com.apple.preference.security?Privacy_AllFiles";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CleanerError {
+    /// Stopped by the user, nothing is wrong
+    Cancelled,
+    /// The dropped item can not be read as a bundle
+    Bundle(BundleError),
+    /// The bundle id entered by hand is not a reverse-DNS id
+    InvalidBundleId { bundle_id: String },
+    /// A match rule, like a line of the rules file, can not be used
+    InvalidRule { rule: String, reason: String },
+    /// An app profile can not be read or is not valid
+    Profile { path: PathBuf, reason: String },
+    /// Denied by file permissions or macOS privacy protection (TCC)
+    PermissionDenied { path: PathBuf },
+    /// Sending a signal to a running process failed
+    ProcessSignal { pid: i32, reason: String },
+    /// Moving to the Trash failed
+    Trash { path: PathBuf, reason: String },
+    /// Authorization for administrator rights was cancelled or failed
+    Elevation { reason: String },
+    /// `lsbom` could not read the BOM file
+    BomParse { path: PathBuf, reason: String },
+    /// Nothing to export, the app has no BOM files
+    NoBomFiles { path: PathBuf },
+    /// The app icon could not be decoded
+    Icon {
+        path: Option<PathBuf>,
+        reason: String,
+    },
+    Io {
+        path: PathBuf,
+        kind: io::ErrorKind,
+        reason: String,
+    },
+}
+
+impl CleanerError {
+    /// Wrap an io error, permission errors get their own variant
+    pub fn io(path: &Path, err: io::Error) -> Self {
+        match err.kind() {
+            io::ErrorKind::PermissionDenied => CleanerError::PermissionDenied {
+                path: path.to_path_buf(),
+            },
+            kind => CleanerError::Io {
+                path: path.to_path_buf(),
+                kind,
+                reason: err.to_string(),
+            },
+        }
+    }
+
+    pub fn path(&self) -> Option<&Path> {
+        match self {
+            CleanerError::Bundle(err) => Some(err.path()),
+            CleanerError::PermissionDenied { path }
+            | CleanerError::Trash { path, .. }
+            | CleanerError::BomParse { path, .. }
+            | CleanerError::Profile { path, .. }
+            | CleanerError::NoBomFiles { path }
+            | CleanerError::Io { path, .. } => Some(path),
+            CleanerError::Icon { path, .. } => path.as_deref(),
+            CleanerError::Cancelled
+            | CleanerError::InvalidBundleId { .. }
+            | CleanerError::InvalidRule { .. }
+            | CleanerError::ProcessSignal { .. }
+            | CleanerError::Elevation { .. } => None,
+        }
+    }
+
+    /// Whether granting Full Disk Access would fix it
+    pub fn needs_full_disk_access(&self) -> bool {
+        matches!(self, CleanerError::PermissionDenied { .. })
+    }
+
+    /// Short advice for the user on what to do about it
+    pub fn hint(&self) -> Option<&'static str> {
+        match self {
+            CleanerError::Cancelled => None,
+            CleanerError::Bundle(err) => Some(err.hint()),
+            CleanerError::InvalidBundleId { .. } => Some("Enter a bundle id like com.vendor.App"),
+            CleanerError::InvalidRule { .. } => {
+                Some("Rules look like \"not equal com.vendor.shared\" or \"glob com.vendor.*\"")
+            }
+            CleanerError::Profile { .. } => {
+                Some("Fix or remove the profile in ~/Library/Application Support/Bristo/profiles")
+            }
+            CleanerError::PermissionDenied { .. } => {
+                Some("Grant Full Disk Access to Bristo in System Settings and restart it")
+            }
+            CleanerError::ProcessSignal { .. } => {
+                Some("Quit the app yourself, or use Activity Monitor, then try again")
+            }
+            CleanerError::Trash { .. } => Some("Reveal the item in Finder and remove it manually"),
+            CleanerError::Elevation { .. } => {
+                Some("The items were left in place, remove them manually or try again")
+            }
+            CleanerError::BomParse { .. } => None,
+            CleanerError::NoBomFiles { .. } => {
+                Some("The app was not installed from a package, there is nothing to export")
+            }
+            CleanerError::Icon { .. } => None,
+            CleanerError::Io { .. } => None,
+        }
+    }
+}
+
+impl fmt::Display for CleanerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CleanerError::Cancelled => write!(f, "Cancelled"),
+            CleanerError::Bundle(err) => write!(f, "{}", err),
+            CleanerError::InvalidBundleId { bundle_id } => {
+                write!(f, "Not a valid bundle id: '{}'", bundle_id)
+            }
+            CleanerError::InvalidRule { rule, reason } => {
+                write!(f, "Invalid rule '{}': {}", rule, reason)
+            }
+            CleanerError::Profile { path, reason } => {
+                write!(f, "Invalid profile {}: {}", path.display(), reason)
+            }
+            CleanerError::PermissionDenied { path } => {
+                write!(f, "Permission denied: {}", path.display())
+            }
+            CleanerError::ProcessSignal { pid, reason } => {
+                write!(f, "Failed to kill PID {}: {}", pid, reason)
+            }
+            CleanerError::Trash { path, reason } => {
+                write!(f, "Failed to move {} to Trash: {}", path.display(), reason)
+            }
+            CleanerError::Elevation { reason } => {
+                write!(f, "Removal with administrator rights failed: {}", reason)
+            }
+            CleanerError::BomParse { path, reason } => {
+                write!(f, "lsbom failed for {}: {}", path.display(), reason)
+            }
+            CleanerError::NoBomFiles { path } => {
+                write!(f, "No BOM files found for app: {}", path.display())
+            }
+            CleanerError::Icon {
+                path: Some(path),
+                reason,
+            } => write!(f, "Failed to decode icon {}: {}", path.display(), reason),
+            CleanerError::Icon { path: None, reason } => {
+                write!(f, "Failed to decode icon: {}", reason)
+            }
+            CleanerError::Io { path, reason, .. } => write!(f, "{}: {}", path.display(), reason),
+        }
+    }
+}
+
+impl std::error::Error for CleanerError {}
+
+impl From<BundleError> for CleanerError {
+    fn from(err: BundleError) -> Self {
+        CleanerError::Bundle(err)
+    }
+}
@@ -1,11 +1,88 @@
-use std::path::Path;
+use std::path::{Component, Path, PathBuf};
+use std::time::{Duration, SystemTime};
 use unicode_normalization::UnicodeNormalization;
+use walkdir::WalkDir;
+
+/// Characters that differ between spellings of the same name,
+/// like "Visual Studio Code", "visual-studio-code" and "visual_studio_code"
+const NAME_SEPARATORS: [char; 3] = [' ', '-', '_'];
+
+/// How names are made comparable before matching
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Normalization {
+    pub case_sensitive: bool,
+    pub strip_separators: bool,
+}
+
+impl Default for Normalization {
+    fn default() -> Self {
+        Self {
+            case_sensitive: false,
+            strip_separators: true,
+        }
+    }
+}
+
+impl Normalization {
+    /// Keep the case and the separators, only the Unicode form is unified
+    pub const EXACT: Self = Self {
+        case_sensitive: true,
+        strip_separators: false,
+    };
+
+    pub fn apply(&self, s: &str) -> String {
+        // APFS keeps names as typed, "é" may be one or two code points
+        let mut s: String = s.nfd().collect();
+
+        if !self.case_sensitive {
+            // Char by char, a prefix must lowercase like the whole name.
+            // Lowercasing may produce combining marks, like "İ" to "i̇"
+            s = s.chars().flat_map(char::to_lowercase).nfd().collect();
+        }
+        if self.strip_separators {
+            s.retain(|c| !NAME_SEPARATORS.contains(&c));
+        }
+
+        s
+    }
+}
+
+/// Which names of a path a rule looks at
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum MatchScope {
+    /// Only the last component, like "App.plist"
+    #[default]
+    FileName,
+    /// Every component, like "Application Support" in ".../Application Support/App/log.txt"
+    Components,
+}
+
+impl MatchScope {
+    pub fn names<'a>(&self, path: &'a Path) -> Vec<&'a str> {
+        match self {
+            MatchScope::FileName => path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .into_iter()
+                .collect(),
+            MatchScope::Components => path
+                .components()
+                .filter_map(|c| match c {
+                    Component::Normal(name) => name.to_str(),
+                    _ => None,
+                })
+                .collect(),
+        }
+    }
+}
 
 /// Normalize & lowercase string case-insensitively for macOS APFS-safe comparison
-fn normalize_lowercase(s: &str) -> String {
-    s.nfd() // normalize to NFD (decomposed)
-        .collect::<String>()
-        .to_lowercase() // lowercase for case-insensitive comparison
+pub(crate) fn normalize_lowercase(s: &str) -> String {
+    Normalization {
+        case_sensitive: false,
+        strip_separators: false,
+    }
+    .apply(s)
 }
 
 /// Compare PathBuf or filenames using contains
@@ -25,3 +102,143 @@ pub fn path_equals_ignore_case(path: &Path, value: &str) -> bool {
         false
     }
 }
+
+/// Compare PathBuf or filenames using starts with value
+pub fn path_starts_with_ignore_case(path: &Path, prefix: &str) -> bool {
+    if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
+        normalize_lowercase(name).starts_with(&normalize_lowercase(prefix))
+    } else {
+        false
+    }
+}
+
+/// Drop "." and ".." without touching the disk
+pub(crate) fn resolve_lexically(path: &Path) -> PathBuf {
+    let mut resolved = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                resolved.pop();
+            }
+            other => resolved.push(other),
+        }
+    }
+    resolved
+}
+
+/// Entries the scan looks at under a root, the root itself is never one
+pub(crate) fn walk_root(base: &Path) -> impl Iterator<Item = walkdir::DirEntry> {
+    WalkDir::new(base)
+        .min_depth(1)
+        .max_depth(3)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|entry| entry.file_type().is_file() || entry.file_type().is_dir())
+}
+
+/// Size on disk of all given paths, nested paths are only counted once
+pub fn total_size_of(paths: &[&Path]) -> u64 {
+    // Sorted, the paths nested in another one follow it and are skipped
+    let mut sorted = paths.to_vec();
+    sorted.sort();
+    sorted.dedup();
+
+    let mut roots: Vec<&Path> = Vec::with_capacity(sorted.len());
+    for path in sorted {
+        if roots.last().is_some_and(|root| path.starts_with(root)) {
+            continue;
+        }
+        roots.push(path);
+    }
+
+    roots
+        .iter()
+        .map(|p| {
+            WalkDir::new(p)
+                .into_iter()
+                .filter_map(Result::ok)
+                .filter_map(|e| e.metadata().ok())
+                .filter(|m| m.is_file())
+                .map(|m| m.len())
+                .sum::<u64>()
+        })
+        .sum()
+}
+
+/// Format a size in bytes to a human readable string
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1000.0 && unit < UNITS.len() - 1 {
+        size /= 1000.0;
+        unit += 1;
+    }
+
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[0])
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
+
+/// Format how long ago a time was, like "3 days ago"
+pub fn format_age(time: SystemTime) -> String {
+    const DAY: u64 = 24 * 60 * 60;
+
+    let days = SystemTime::now()
+        .duration_since(time)
+        .map(|d| d.as_secs() / DAY)
+        .unwrap_or(0);
+
+    match days {
+        0 => "today".to_string(),
+        1 => "yesterday".to_string(),
+        2..=59 => format!("{} days ago", days),
+        60..=729 => format!("{} months ago", days / 30),
+        _ => format!("{} years ago", days / 365),
+    }
+}
+
+/// Parse a timestamp like "2024-03-05 14:22:10 +0000" as printed by `mdls`
+pub fn parse_utc_timestamp(s: &str) -> Option<SystemTime> {
+    let mut parts = s.split_whitespace();
+    let date: Vec<i64> = parts
+        .next()?
+        .split('-')
+        .map(|p| p.parse().ok())
+        .collect::<Option<_>>()?;
+    let time: Vec<i64> = parts
+        .next()?
+        .split(':')
+        .map(|p| p.parse().ok())
+        .collect::<Option<_>>()?;
+    let offset = parts.next().unwrap_or("+0000");
+
+    let ([year, month, day], [hour, minute, second]) = (date.as_slice(), time.as_slice()) else {
+        return None;
+    };
+    if !(1..=12).contains(month) || !(1..=31).contains(day) {
+        return None;
+    }
+
+    // Days since 1970-01-01 of the proleptic Gregorian calendar
+    let y = if *month <= 2 { year - 1 } else { *year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let mp = (month + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = era * 146_097 + doe - 719_468;
+
+    let sign = if offset.starts_with('-') { -1 } else { 1 };
+    let offset: i64 = offset.trim_start_matches(['+', '-']).parse().ok()?;
+    let offset = sign * ((offset / 100) * 3600 + (offset % 100) * 60);
+
+    let secs = days * 86_400 + hour * 3600 + minute * 60 + second - offset;
+    let secs = u64::try_from(secs).ok()?;
+
+    Some(SystemTime::UNIX_EPOCH + Duration::from_secs(secs))
+}
@@ -0,0 +1,335 @@
+use serde_json::Value;
+use std::cmp::Ordering;
+use std::collections::HashSet;
+use std::env;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+use crate::app_data::AppInfo;
+use crate::helpers::{normalize_lowercase, resolve_lexically};
+
+/// Apple silicon prefix first, Intel Macs install under /usr/local
+const DEFAULT_PREFIXES: [&str; 2] = ["/opt/homebrew", "/usr/local"];
+
+/// Homebrew install whose Caskroom tells which apps came from a cask
+#[derive(Debug, Clone)]
+pub struct Homebrew {
+    pub prefix: PathBuf,
+    /// Where "app" artifacts are moved to, brew's --appdir
+    pub appdir: PathBuf,
+}
+
+impl Homebrew {
+    /// Homebrew of this Mac, HOMEBREW_PREFIX wins over the default prefixes
+    pub fn detect() -> Option<Self> {
+        let from_env = env::var("HOMEBREW_PREFIX").ok().map(PathBuf::from);
+
+        from_env
+            .into_iter()
+            .chain(DEFAULT_PREFIXES.iter().map(PathBuf::from))
+            .map(Self::with_prefix)
+            .find(|brew| brew.caskroom().is_dir())
+    }
+
+    pub fn with_prefix(prefix: impl Into<PathBuf>) -> Self {
+        Self {
+            prefix: prefix.into(),
+            appdir: PathBuf::from("/Applications"),
+        }
+    }
+
+    pub fn with_appdir(mut self, appdir: impl Into<PathBuf>) -> Self {
+        self.appdir = appdir.into();
+        self
+    }
+
+    pub fn caskroom(&self) -> PathBuf {
+        self.prefix.join("Caskroom")
+    }
+
+    /// Cask that installed the app, matched by the .app artifact it ships.
+    /// The token is only guessed from the name when the bundle was already
+    /// deleted or the receipt lists no app
+    pub fn find_cask(&self, app: &AppInfo) -> Option<HomebrewCask> {
+        let bundle_name = app
+            .path
+            .file_name()
+            .map(|n| normalize_lowercase(&n.to_string_lossy()));
+        let token_guess = normalize_lowercase(&app.name).replace(' ', "-");
+
+        let mut tokens: Vec<String> = std::fs::read_dir(self.caskroom())
+            .ok()?
+            .filter_map(|e| e.ok())
+            .filter(|e| e.path().is_dir())
+            .map(|e| e.file_name().to_string_lossy().into_owned())
+            .filter(|t| !t.starts_with('.'))
+            .collect();
+        tokens.sort();
+
+        tokens.into_iter().find_map(|token| {
+            let metadata = self.read_metadata(&token);
+
+            let ships_app = metadata.as_ref().is_some_and(|json| {
+                artifact_entries(json, "app").any(|(source, target)| {
+                    let name = target.as_deref().unwrap_or(&source);
+                    let name = Path::new(name).file_name().map(|n| n.to_string_lossy());
+                    name.map(|n| normalize_lowercase(&n)) == bundle_name
+                })
+            });
+
+            // A receipt naming another app is a different cask of a similar name
+            let lists_apps = metadata
+                .as_ref()
+                .is_some_and(|json| artifact_entries(json, "app").next().is_some());
+            let guessed = token == token_guess && (!lists_apps || app.path.as_os_str().is_empty());
+
+            (ships_app || guessed).then(|| self.cask(&token, metadata.as_ref()))
+        })
+    }
+
+    /// Latest install receipt of a cask, at
+    /// Caskroom/<token>/.metadata/<version>/<timestamp>/Casks/<token>.json
+    fn read_metadata(&self, token: &str) -> Option<Value> {
+        let file_name = format!("{}.json", token);
+
+        let metadata = self.caskroom().join(token).join(".metadata");
+
+        // Newest version first, then the newest timestamp of it
+        let latest = WalkDir::new(&metadata)
+            .min_depth(4)
+            .max_depth(4)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_name().to_string_lossy() == file_name)
+            .map(|e| e.into_path())
+            .max_by(|a, b| {
+                let folders = |p: &Path| {
+                    let mut parts = p.strip_prefix(&metadata).ok()?.components();
+                    let version = parts.next()?.as_os_str().to_string_lossy().into_owned();
+                    let timestamp = parts.next()?.as_os_str().to_string_lossy().into_owned();
+                    Some((version, timestamp))
+                };
+                match (folders(a), folders(b)) {
+                    (Some((va, ta)), Some((vb, tb))) => {
+                        compare_versions(&va, &vb).then_with(|| ta.cmp(&tb))
+                    }
+                    _ => a.cmp(b),
+                }
+            })?;
+
+        let content = std::fs::read_to_string(latest).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    fn cask(&self, token: &str, metadata: Option<&Value>) -> HomebrewCask {
+        let caskroom = self.caskroom().join(token);
+        let mut artifacts = Vec::new();
+        let mut symlinks = Vec::new();
+        let mut zap = Vec::new();
+
+        if let Some(json) = metadata {
+            for (source, target) in artifact_entries(json, "app") {
+                let name = target.unwrap_or(source);
+                artifacts.push(self.appdir.join(file_name_of(&name)));
+            }
+
+            for (source, target) in artifact_entries(json, "binary") {
+                let name = target.unwrap_or(source);
+                symlinks.push(self.prefix.join("bin").join(file_name_of(&name)));
+            }
+
+            for (source, target) in artifact_entries(json, "manpage") {
+                let name = file_name_of(&target.unwrap_or(source));
+                // Section is the extension, like "1" for "tool.1"
+                if let Some(section) = Path::new(&name).extension() {
+                    let dir = format!("man{}", section.to_string_lossy());
+                    symlinks.push(self.prefix.join("share/man").join(dir).join(name));
+                }
+            }
+
+            zap.extend(zap_trash(json).iter().map(|p| expand_home(p)));
+        }
+
+        // Links brew made that the receipt does not tell about
+        let mut roots = artifacts.clone();
+        roots.push(caskroom.clone());
+        for dir in ["bin", "sbin"] {
+            symlinks.extend(links_into(&self.prefix.join(dir), &roots));
+        }
+
+        let exists = |p: &PathBuf| p.symlink_metadata().is_ok();
+        let mut seen = HashSet::new();
+        artifacts.retain(|p| exists(p) && seen.insert(p.clone()));
+        symlinks.retain(|p| exists(p) && seen.insert(p.clone()));
+        zap.retain(|p| exists(p) && seen.insert(p.clone()));
+
+        HomebrewCask {
+            version: installed_version(&caskroom),
+            token: token.to_string(),
+            prefix: self.prefix.clone(),
+            caskroom,
+            artifacts,
+            symlinks,
+            zap,
+        }
+    }
+}
+
+/// App installed with `brew install --cask`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HomebrewCask {
+    pub token: String,
+    /// Prefix of the brew that installed it, like /opt/homebrew
+    pub prefix: PathBuf,
+    pub version: Option<String>,
+    /// Caskroom/<token>, brew keeps its receipts there
+    pub caskroom: PathBuf,
+    /// Installed app
+    pub artifacts: Vec<PathBuf>,
+    /// Binaries and manpages linked into the prefix
+    pub symlinks: Vec<PathBuf>,
+    /// What `brew uninstall --zap` would trash too. Zap lists often name
+    /// folders of the whole vendor, they are never part of paths
+    pub zap: Vec<PathBuf>,
+}
+
+impl HomebrewCask {
+    /// What `brew uninstall` removes that exists on disk, the Caskroom entry last
+    pub fn paths(&self) -> Vec<PathBuf> {
+        self.artifacts
+            .iter()
+            .chain(&self.symlinks)
+            .cloned()
+            .chain(std::iter::once(self.caskroom.clone()))
+            .collect()
+    }
+
+    pub fn uninstall_command(&self) -> String {
+        format!("brew uninstall --cask {}", self.token)
+    }
+
+    /// Trashing leaves brew believing the cask is still installed
+    pub fn warning(&self) -> String {
+        let mut warning = format!(
+            "Installed with Homebrew cask '{}', `{}` is preferred",
+            self.token,
+            self.uninstall_command()
+        );
+        if !self.zap.is_empty() {
+            warning.push_str(&format!(
+                ". The {} paths its zap lists are not included, they may be shared",
+                self.zap.len()
+            ));
+        }
+        warning
+    }
+}
+
+/// Source and optional target of every artifact of the given kind,
+/// entries look like {"binary": ["path", {"target": "name"}]}
+fn artifact_entries<'a>(
+    json: &'a Value,
+    kind: &'a str,
+) -> impl Iterator<Item = (String, Option<String>)> + 'a {
+    json.get("artifacts")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(move |artifact| artifact.get(kind)?.as_array())
+        .filter_map(|args| {
+            let source = args.first()?.as_str()?.to_string();
+            let target = args
+                .iter()
+                .find_map(|a| a.get("target")?.as_str())
+                .map(|t| t.to_string());
+            Some((source, target))
+        })
+}
+
+/// Paths listed under zap trash, a single path or a list of them
+fn zap_trash(json: &Value) -> Vec<String> {
+    let mut paths = Vec::new();
+
+    let zaps = json
+        .get("artifacts")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|artifact| artifact.get("zap")?.as_array())
+        .flatten();
+
+    for zap in zaps {
+        match zap.get("trash") {
+            Some(Value::String(path)) => paths.push(path.clone()),
+            Some(Value::Array(list)) => {
+                paths.extend(list.iter().filter_map(|p| p.as_str()).map(String::from))
+            }
+            _ => {}
+        }
+    }
+
+    // Globs need brew itself to expand
+    paths.retain(|p| !p.contains('*'));
+    paths
+}
+
+fn file_name_of(path: &str) -> String {
+    Path::new(path)
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.to_string())
+}
+
+fn expand_home(path: &str) -> PathBuf {
+    match path.strip_prefix("~/") {
+        Some(rest) => env::var("HOME")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| PathBuf::from("/Users/Unknown"))
+            .join(rest),
+        None => PathBuf::from(path),
+    }
+}
+
+/// Symlinks of the folder pointing inside one of the roots
+fn links_into(dir: &Path, roots: &[PathBuf]) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    let mut links: Vec<PathBuf> = entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| {
+            std::fs::read_link(p)
+                .map(|target| {
+                    let target = resolve_lexically(&dir.join(target));
+                    roots.iter().any(|root| target.starts_with(root))
+                })
+                .unwrap_or(false)
+        })
+        .collect();
+    links.sort();
+    links
+}
+
+/// Version folder next to .metadata, the newest when there are several
+fn installed_version(caskroom: &Path) -> Option<String> {
+    std::fs::read_dir(caskroom)
+        .ok()?
+        .filter_map(|e| e.ok())
+        .map(|e| e.file_name().to_string_lossy().into_owned())
+        .filter(|name| !name.starts_with('.'))
+        .max_by(|a, b| compare_versions(a, b))
+}
+
+/// Numbers compare as numbers, so "10.0" is newer than "9.0".
+/// Parts that are not numbers, like "beta", compare as text
+fn compare_versions(a: &str, b: &str) -> Ordering {
+    let parts = |version: &str| -> Vec<(Option<u64>, String)> {
+        version
+            .split(|c: char| !c.is_ascii_alphanumeric())
+            .map(|part| (part.parse().ok(), part.to_string()))
+            .collect()
+    };
+    parts(a).cmp(&parts(b))
+}
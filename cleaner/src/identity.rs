@@ -0,0 +1,66 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use crate::helpers::normalize_lowercase;
+use crate::orphans::{InstalledApps, parse_bundle_id};
+
+/// Where a suggested bundle id was found
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SuggestionSource {
+    /// Package receipt, named after the package id
+    Receipt,
+    /// Preferences plist, named after the bundle id
+    Preferences,
+}
+
+/// Bundle id of an app that may be gone, to fill in the identity form
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IdentitySuggestion {
+    pub bundle_id: String,
+    pub source: SuggestionSource,
+}
+
+impl IdentitySuggestion {
+    /// Collect ids from receipts and preferences file names,
+    /// ids of installed apps and of macOS itself are left out
+    pub fn collect(
+        receipts_dirs: &[PathBuf],
+        preferences_dir: &Path,
+        installed: &InstalledApps,
+    ) -> Vec<Self> {
+        let sources = receipts_dirs
+            .iter()
+            .map(|dir| (dir.as_path(), SuggestionSource::Receipt))
+            .chain([(preferences_dir, SuggestionSource::Preferences)]);
+
+        let mut seen = HashSet::new();
+        let mut suggestions = Vec::new();
+
+        for (dir, source) in sources {
+            let Ok(entries) = std::fs::read_dir(dir) else {
+                continue;
+            };
+
+            for entry in entries.filter_map(|e| e.ok()) {
+                let Some(bundle_id) = entry.file_name().to_str().and_then(parse_bundle_id) else {
+                    continue;
+                };
+
+                let key = bundle_id.to_lowercase();
+                if key.starts_with("com.apple.") || installed.owns(&key) || !seen.insert(key) {
+                    continue;
+                }
+
+                suggestions.push(Self { bundle_id, source });
+            }
+        }
+
+        suggestions.sort_by_key(|s| s.bundle_id.to_lowercase());
+        suggestions
+    }
+
+    /// Whether the suggestion fits what was typed so far
+    pub fn matches(&self, query: &str) -> bool {
+        normalize_lowercase(&self.bundle_id).contains(&normalize_lowercase(query.trim()))
+    }
+}
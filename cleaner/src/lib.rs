@@ -1,11 +1,38 @@
 mod app_data;
+mod cancel;
+mod catalog;
+mod error;
 mod helpers;
+mod homebrew;
+mod identity;
+mod orphans;
+mod permission_probe;
+mod privileged;
+mod profile;
+mod safety_guard;
+mod scan_estimate;
+mod scan_setup;
 mod syscom;
+mod trash_failure;
 pub use app_data::*;
+pub use cancel::*;
+pub use catalog::*;
+pub use error::*;
 pub use helpers::*;
+pub use homebrew::*;
+pub use identity::*;
+pub use orphans::*;
+pub use permission_probe::*;
+pub use privileged::*;
+pub use profile::*;
+pub use safety_guard::*;
+pub use scan_estimate::*;
+pub use scan_setup::*;
+pub use trash_failure::*;
 
-use anyhow::Result;
 use status::StatusEmitter;
+use status::status_event::Phase;
+use status::throughput::Throughput;
 use std::path::Path;
 use std::path::PathBuf;
 
@@ -14,6 +41,8 @@ use common_debug::debug_dev;
 #[derive(Debug, Default, Clone)]
 pub struct Cleaner {
     pub app_data: AppData,
+    /// Roots the last scan walked, the safety guard refuses every one of them
+    pub locations: Option<LocationsScan>,
 }
 
 impl Cleaner {
@@ -22,14 +51,23 @@ impl Cleaner {
     }
 
     pub fn new_app(path: &Path, status: Option<&StatusEmitter>) -> Result<Self> {
+        if let Some(s) = status {
+            s.with_phase(Phase::ResolvingApp { path: None }).emit();
+        }
+
         let mut app_data = AppData::new(path)?;
 
         if let Some(s) = status {
-            s.with_message(format!(
-                "Scanning running processes for '{}'",
-                app_data.app.name
-            ))
+            s.with_phase(Phase::ResolvingApp {
+                path: Some(path.to_path_buf()),
+            })
             .emit();
+            s.with_phase(Phase::ScanningProcesses { found: None })
+                .with_message(format!(
+                    "Scanning running processes for '{}'",
+                    app_data.app.name
+                ))
+                .emit();
         }
 
         // Find running processes
@@ -37,11 +75,44 @@ impl Cleaner {
 
         if let Some(s) = status {
             let total_process = app_data.app_process.len();
-            s.with_message(format!("Found process {}", total_process))
-                .emit();
+            s.with_phase(Phase::ScanningProcesses {
+                found: Some(total_process),
+            })
+            .with_message(format!("Found process {}", total_process))
+            .emit();
         }
 
-        Ok(Self { app_data })
+        Ok(Self {
+            app_data,
+            locations: None,
+        })
+    }
+
+    /// Cleaner for an app that was already deleted, known only by its bundle id.
+    /// Empty name and executable are derived from the bundle id
+    pub fn from_identity(name: &str, bundle_id: &str, executable: &str) -> Result<Self> {
+        let mut app_data = AppData::from_identity(name, bundle_id, executable)?;
+
+        // Helpers or login items may still be running
+        app_data.find_pid_and_command();
+
+        Ok(Self {
+            app_data,
+            locations: None,
+        })
+    }
+
+    /// Bundle ids of deleted apps found in receipts and preferences
+    pub fn identity_suggestions() -> Vec<IdentitySuggestion> {
+        let home = std::env::var("HOME")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| PathBuf::from("/Users/Unknown"));
+
+        IdentitySuggestion::collect(
+            &LocationsScan::new().receipts_dirs(),
+            &home.join("Library/Preferences"),
+            &InstalledApps::new(),
+        )
     }
 
     pub fn kill_app_process(&self, status: Option<&StatusEmitter>) -> Result<()> {
@@ -49,83 +120,166 @@ impl Cleaner {
             return Ok(());
         }
 
+        if let Some(s) = status {
+            s.with_phase(Phase::KillingProcesses { killed: None })
+                .with_total(self.app_data.app_process.len())
+                .emit();
+        }
+
         let killed_count =
             AppProcess::kill_app_processes(&self.app_data.app.name, &self.app_data.app_process)?;
 
         if let Some(s) = status {
-            s.with_stage("Completed")
-                .with_message("All processes killed")
-                .with_total(killed_count)
-                .emit();
+            s.with_phase(Phase::KillingProcesses {
+                killed: Some(killed_count),
+            })
+            .with_message("All processes killed")
+            .emit();
         }
 
         Ok(())
     }
 
-    /// Scan an app at the given path and return AppData
-    pub fn scan_app_data(&mut self, status: Option<&StatusEmitter>) -> Result<&Self> {
-        if let Some(s) = status {
-            s.with_message(format!(
-                "Scanning logs and associated files for '{}'",
-                self.app_data.app.name
-            ))
-            .emit();
-        }
+    /// Scan an app at the given path and return AppData.
+    /// The caller ends the operation with Done or Failed
+    pub fn scan_app_data(
+        &mut self,
+        status: Option<&StatusEmitter>,
+        cancel: &CancelToken,
+    ) -> Result<&Self> {
+        self.scan_app_data_with(&ScanSetup::for_user()?, status, cancel)
+    }
 
+    /// Like scan_app_data, reading and writing what the setup says
+    pub fn scan_app_data_with(
+        &mut self,
+        setup: &ScanSetup,
+        status: Option<&StatusEmitter>,
+        cancel: &CancelToken,
+    ) -> Result<&Self> {
         if let Some(s) = status {
-            s.with_stage("started")
-                .with_message("Finding BOM logs...")
+            s.with_phase(Phase::ScanningBom { found: None })
+                .with_message(format!(
+                    "Finding BOM logs of '{}'...",
+                    self.app_data.app.name
+                ))
                 .emit();
         }
 
-        let locations = LocationsScan::new();
+        let mut locations = setup.locations.clone();
 
         self.app_data.find_log_bom(&locations);
+        cancel.check()?;
 
         let total_bom_file = self.app_data.log.bom_file.len();
 
         if let Some(s) = status {
-            s.with_stage("completed")
-                .with_total(total_bom_file)
-                .with_message("BOM logs scan completed")
-                .emit();
+            s.with_phase(Phase::ScanningBom {
+                found: Some(total_bom_file),
+            })
+            .emit();
+        }
+
+        if let Some(brew) = &setup.homebrew {
+            self.app_data.find_homebrew_cask(brew);
         }
 
+        cancel.check()?;
+
+        self.app_data.extra_rules = match &setup.rules_file {
+            Some(path) => MatchRuleSet::load(path, &self.app_data.app.bundle_id)?,
+            None => MatchRuleSet::default(),
+        };
+        self.app_data.warnings.extend(
+            self.app_data
+                .extra_rules
+                .problems
+                .iter()
+                .map(|err| format!("{}, the rule was skipped", err)),
+        );
+        self.app_data.find_profile(&setup.profiles);
+        self.app_data.warnings.extend(
+            setup
+                .profiles
+                .problems
+                .iter()
+                .map(|(_, err)| format!("{}, the profile was skipped", err)),
+        );
+
+        // Where to look first depends on how the app was installed
+        self.app_data.detect_install_source();
+        let source = &self.app_data.app.install_source;
+        if let InstallSource::Homebrew { prefix, .. } = source {
+            locations.add_homebrew_prefix(prefix);
+        }
+        locations.prioritize(source);
+        self.locations = Some(locations.clone());
+
         if let Some(s) = status {
-            s.with_stage("started")
-                .with_message("Finding associated files...")
+            s.with_phase(Phase::ScanningFiles { found: None })
+                .with_message(format!(
+                    "{}, finding associated files...",
+                    source.describe()
+                ))
                 .emit();
         }
 
+        // Only worth counting when someone shows the progress
+        let mut estimate = setup
+            .estimate_cache
+            .as_deref()
+            .map(ScanEstimate::load_from)
+            .unwrap_or_default();
+        let total = status.and_then(|_| estimate.total(&locations, cancel));
+        cancel.check()?;
+
+        let throughput = Throughput::start();
         self.app_data
-            .find_associate_files(&locations, |cur, _path| {
+            .find_associate_files_with(&locations, cancel, |cur, _path| {
                 if let Some(s) = status {
-                    s.with_stage("Searching").with_current(cur).emit();
+                    s.with_phase(Phase::ScanningFiles { found: None })
+                        .with_progress(cur, total, &throughput)
+                        .emit();
                 }
-            });
+            })?;
+
+        // The estimate is only a help, the scan went fine without it
+        if let Some(cache) = &setup.estimate_cache {
+            estimate.record(&self.app_data.scan_counts);
+            if let Err(_e) = estimate.save_to(cache) {
+                debug_dev!("Saving the scan estimate failed: {}", _e);
+            }
+        }
 
         if let Some(s) = status {
-            s.with_stage("completed")
-                .with_message("Associated files scan completed")
-                .emit();
+            let found = self.app_data.associate_files.len();
+            let scanned = s.with_phase(Phase::ScanningFiles { found: Some(found) });
+            match &self.app_data.cask {
+                Some(cask) => scanned.with_message(cask.warning()).emit(),
+                None => scanned.emit(),
+            }
         }
 
         Ok(self)
     }
 
     /// Save BOM logs of the current app to the given folder
-    pub fn save_bom_logs(&self, log_dir: &Path) -> Result<()> {
+    pub fn save_bom_logs(&self, log_dir: &Path, cancel: &CancelToken) -> Result<()> {
         // Determine the folder
         let app_log_folder =
             Path::new(log_dir).join(format!("{}_bom_log", &self.app_data.app.name));
         debug_dev!("Creating folder: {}", app_log_folder.display());
 
         // Call the LogReceipt function
-        self.app_data.save_bom_log_app(&app_log_folder)
+        self.app_data.save_bom_log_app(&app_log_folder, cancel)
     }
 
     /// Move all associated files including the app itself to trash
-    pub fn trash_all(&self) -> Result<Vec<(PathBuf, String)>> {
+    pub fn trash_all(
+        &self,
+        status: Option<&StatusEmitter>,
+        cancel: &CancelToken,
+    ) -> Result<TrashReport> {
         // get all path in the associate_files field with enumerate
         let paths: Vec<PathBuf> = self
             .app_data
@@ -135,9 +289,120 @@ impl Cleaner {
             .collect();
 
         // delete all associate_files
-        let failed_paths = syscom::trash_files_nsfilemanager(&paths)?;
+        Self::trash_guarded(&paths, &self.safety_guard()?, status, cancel)
+    }
 
-        Ok(failed_paths)
+    /// Move the given paths to trash, returns the ones that failed or were refused
+    pub fn trash_paths(
+        paths: &[PathBuf],
+        status: Option<&StatusEmitter>,
+        cancel: &CancelToken,
+    ) -> Result<TrashReport> {
+        Self::trash_guarded(paths, &SafetyGuard::for_user()?, status, cancel)
+    }
+
+    /// Only the paths the guard allows are tried, the others are reported as refused.
+    /// Once cancelled the paths left are reported as skipped
+    pub fn trash_guarded(
+        paths: &[PathBuf],
+        guard: &SafetyGuard,
+        status: Option<&StatusEmitter>,
+        cancel: &CancelToken,
+    ) -> Result<TrashReport> {
+        let (allowed, refused) = guard.partition(paths);
+        for (path, refusal) in &refused {
+            debug_dev!("Refused to trash {}: {}", path.display(), refusal);
+        }
+
+        let total = allowed.len();
+        let throughput = Throughput::start();
+        let mut failed = Vec::new();
+        let mut skipped = Vec::new();
+        for (i, path) in allowed.iter().enumerate() {
+            if cancel.is_cancelled() {
+                skipped.push(path.clone());
+                continue;
+            }
+            if let Some(s) = status {
+                s.with_phase(Phase::Trashing { trashed: None })
+                    .with_progress(i, Some(total), &throughput)
+                    .with_path(path.clone())
+                    .emit();
+            }
+            failed.extend(syscom::trash_files_nsfilemanager(std::slice::from_ref(
+                path,
+            ))?);
+        }
+
+        if let Some(s) = status {
+            let tried = total - skipped.len();
+            let trashed = tried.saturating_sub(failed.len());
+            s.with_phase(Phase::Trashing {
+                trashed: Some(trashed),
+            })
+            .with_progress(tried, Some(total), &throughput)
+            .emit();
+        }
+
+        Ok(TrashReport {
+            failed,
+            refused,
+            skipped,
+        })
+    }
+
+    /// Guard of the roots the scan walked and the never delete file of the user,
+    /// the never delete paths of the app profile are kept too
+    pub fn safety_guard(&self) -> Result<SafetyGuard> {
+        let home = std::env::var("HOME")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| PathBuf::from("/Users/Unknown"));
+        let locations = self.locations.clone().unwrap_or_default();
+        let guard =
+            SafetyGuard::new(&locations, &home).with_allowlist(SafetyGuard::load_allowlist(&home)?);
+
+        let Some(profile) = &self.app_data.profile else {
+            return Ok(guard);
+        };
+        Ok(guard.with_shared(profile.never_delete_paths(&home)))
+    }
+
+    /// Find leftovers of apps that are no longer installed
+    pub fn scan_orphans(status: Option<&StatusEmitter>) -> Vec<OrphanGroup> {
+        if let Some(s) = status {
+            s.with_phase(Phase::ScanningFiles { found: None })
+                .with_message("Indexing installed apps...")
+                .emit();
+        }
+
+        let installed = InstalledApps::new();
+
+        if let Some(s) = status {
+            s.with_phase(Phase::ScanningFiles { found: None })
+                .with_total(installed.bundle_ids.len())
+                .with_message("Finding leftovers...")
+                .emit();
+        }
+
+        let orphans = find_orphans(&LocationsScan::new(), &installed);
+
+        if let Some(s) = status {
+            s.with_phase(Phase::ScanningFiles {
+                found: Some(orphans.len()),
+            })
+            .emit();
+        }
+
+        orphans
+    }
+
+    /// Remove the failed items owned by root with administrator rights,
+    /// returns the items that are still left
+    pub fn remove_with_admin(
+        failed: Vec<(PathBuf, TrashFailure)>,
+        elevator: &dyn Elevator,
+    ) -> Result<Vec<(PathBuf, TrashFailure)>> {
+        privileged::remove_elevated(failed, elevator)
     }
 
     /// Print a summary of the app data
@@ -146,6 +411,21 @@ impl Cleaner {
         println!("App Name: {}", self.app_data.app.name);
         println!("Bundle ID: {}", self.app_data.app.bundle_id);
         println!("Bundle Name: {}", self.app_data.app.bundle_name);
+        if let Some(version) = &self.app_data.app.version {
+            println!("Version: {}", version);
+        }
+        if let Some(team_id) = &self.app_data.app.team_id {
+            println!("Team ID: {}", team_id);
+        }
+        println!("Sandboxed: {}", self.app_data.app.sandboxed);
+        println!("{}", self.app_data.app.install_source.describe());
+        if let Some(cask) = &self.app_data.cask {
+            println!("Homebrew cask: {}", cask.token);
+            println!("Warning: {}", cask.warning());
+        }
+        if let Some(profile) = &self.app_data.profile {
+            println!("Profile: {}", profile.bundle_id);
+        }
 
         println!("\nRunning processes:");
         for p in &self.app_data.app_process {
@@ -167,7 +447,18 @@ impl Cleaner {
         syscom::show_in_finder(path)
     }
 
+    /// Check which protected locations can be read, before scanning
+    pub fn probe_permissions() -> PermissionReport {
+        PermissionProbe::new().run()
+    }
+
+    /// Open the Full Disk Access page of System Settings
+    pub fn open_full_disk_access_settings() -> Result<()> {
+        syscom::open_url(FULL_DISK_ACCESS_URL)
+    }
+
     pub fn reset(&mut self) {
         self.app_data.reset();
+        self.locations = None;
     }
 }
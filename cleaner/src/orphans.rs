@@ -0,0 +1,261 @@
+use plist::Value;
+use std::collections::{BTreeMap, HashSet};
+use std::env;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use walkdir::WalkDir;
+
+use crate::app_data::{EmbeddedBundle, LocationsScan, Vendor};
+
+/// Suffixes that follow the bundle id in leftover names
+const KNOWN_SUFFIXES: [&str; 6] = [
+    ".plist",
+    ".savedState",
+    ".binarycookies",
+    ".db",
+    ".log",
+    ".bom",
+];
+
+/// Bundle ids of every installed app, to tell which leftovers have no owner
+#[derive(Debug, Clone, Default)]
+pub struct InstalledApps {
+    pub bundle_ids: HashSet<String>,
+    /// Vendor prefixes of the installed apps, like "com.docker" for "com.docker.docker"
+    pub vendor_prefixes: HashSet<String>,
+}
+
+impl InstalledApps {
+    /// Index the apps of the usual application folders
+    pub fn new() -> Self {
+        Self::index(&Self::default_dirs())
+    }
+
+    pub fn default_dirs() -> Vec<PathBuf> {
+        let home = env::var("HOME")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| PathBuf::from("/Users/Unknown"));
+
+        vec![
+            PathBuf::from("/Applications"),
+            home.join("Applications"),
+            PathBuf::from("/System/Applications"),
+        ]
+    }
+
+    pub fn index(dirs: &[PathBuf]) -> Self {
+        let mut bundle_ids = HashSet::new();
+        let mut vendor_prefixes = HashSet::new();
+
+        for path in &find_app_bundles(dirs) {
+            // iOS apps keep their Info.plist inside the wrapper
+            let plists = [
+                path.join("Contents").join("Info.plist"),
+                path.join("Wrapper")
+                    .join(path.file_name().unwrap_or_default())
+                    .join("Info.plist"),
+            ];
+            if let Some(id) = plists.iter().find_map(|p| read_bundle_id(p)) {
+                let prefix = Vendor::from_bundle_id(&id).prefix;
+                if !prefix.is_empty() {
+                    vendor_prefixes.insert(prefix.to_lowercase());
+                }
+                bundle_ids.insert(id.to_lowercase());
+            }
+
+            // Extensions and helpers leave their own leftovers
+            for bundle in EmbeddedBundle::inspect(path) {
+                bundle_ids.insert(bundle.bundle_id.to_lowercase());
+            }
+        }
+
+        Self {
+            bundle_ids,
+            vendor_prefixes,
+        }
+    }
+
+    /// Whether an installed app owns this id, its extensions like
+    /// "com.vendor.app.share" and its vendor folder like "com.vendor" included
+    pub fn owns(&self, bundle_id: &str) -> bool {
+        let bundle_id = bundle_id.to_lowercase();
+
+        self.bundle_ids.iter().any(|installed| {
+            *installed == bundle_id
+                || bundle_id.starts_with(&format!("{}.", installed))
+                || installed.starts_with(&format!("{}.", bundle_id))
+        })
+    }
+
+    /// Whether the id is named under the vendor of an installed app.
+    /// Helpers like "com.docker.vmnetd" are not embedded in the app
+    /// that installed them, so they can not be told apart from leftovers
+    pub fn shares_vendor(&self, bundle_id: &str) -> bool {
+        let bundle_id = bundle_id.to_lowercase();
+
+        self.vendor_prefixes
+            .iter()
+            .any(|prefix| bundle_id.starts_with(&format!("{}.", prefix)))
+    }
+}
+
+/// All .app bundles of the given folders, apps may be grouped one level down
+/// in a folder like Utilities or the vendor name
+pub(crate) fn find_app_bundles(dirs: &[PathBuf]) -> Vec<PathBuf> {
+    dirs.iter()
+        .flat_map(|dir| {
+            WalkDir::new(dir)
+                .max_depth(2)
+                .into_iter()
+                .filter_entry(|e| !e.path().parent().map(is_app).unwrap_or(false))
+                .filter_map(|e| e.ok())
+                .filter(|e| is_app(e.path()))
+                .map(|e| e.into_path())
+        })
+        .collect()
+}
+
+fn is_app(path: &Path) -> bool {
+    path.extension().map(|e| e == "app").unwrap_or(false)
+}
+
+fn read_bundle_id(plist_path: &Path) -> Option<String> {
+    let plist = Value::from_file(plist_path).ok()?;
+    plist
+        .as_dictionary()?
+        .get("CFBundleIdentifier")?
+        .as_string()
+        .map(|s| s.to_string())
+}
+
+/// Leftovers named after the same bundle id
+#[derive(Debug, Clone)]
+pub struct OrphanGroup {
+    pub bundle_id: String,
+    pub paths: Vec<PathBuf>,
+    pub size: u64,
+    pub modified: Option<SystemTime>,
+    /// Another app of the same vendor is installed, these may still be in use
+    pub uncertain: bool,
+}
+
+/// Find leftovers in the scan locations that belong to no installed app
+pub fn find_orphans(locations: &LocationsScan, installed: &InstalledApps) -> Vec<OrphanGroup> {
+    let mut groups: BTreeMap<String, Vec<PathBuf>> = BTreeMap::new();
+    let mut seen = HashSet::new();
+
+    // Receipts are named after package ids, not bundle ids
+    let receipts = locations.receipts_dirs();
+
+    for base in locations.paths.iter().filter(|p| !receipts.contains(p)) {
+        let Ok(entries) = std::fs::read_dir(base) else {
+            continue;
+        };
+
+        for entry in entries.filter_map(|e| e.ok()) {
+            let path = entry.path();
+            if !seen.insert(path.clone()) {
+                continue;
+            }
+
+            let Some(bundle_id) = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .and_then(bundle_id_from_name)
+            else {
+                continue;
+            };
+
+            // System components are never leftovers
+            if bundle_id.starts_with("com.apple.") || installed.owns(&bundle_id) {
+                continue;
+            }
+
+            groups.entry(bundle_id).or_default().push(path);
+        }
+    }
+
+    let mut orphans: Vec<OrphanGroup> = groups
+        .into_iter()
+        .map(|(bundle_id, paths)| {
+            let (size, modified) = size_and_modified(&paths);
+            OrphanGroup {
+                uncertain: installed.shares_vendor(&bundle_id),
+                bundle_id,
+                paths,
+                size,
+                modified,
+            }
+        })
+        .collect();
+
+    // Biggest leftovers first, the uncertain ones after the others
+    orphans.sort_by_key(|g| (g.uncertain, std::cmp::Reverse(g.size)));
+    orphans
+}
+
+/// Bundle id of a leftover name, like "com.vendor.app" for
+/// "com.vendor.App.plist" or "ABCDE12345.group.com.vendor.app"
+pub fn bundle_id_from_name(name: &str) -> Option<String> {
+    parse_bundle_id(name).map(|id| id.to_lowercase())
+}
+
+/// Same as bundle_id_from_name, keeping the case for display
+pub(crate) fn parse_bundle_id(name: &str) -> Option<String> {
+    let mut name = name;
+    for suffix in KNOWN_SUFFIXES {
+        if let Some(stripped) = name.strip_suffix(suffix) {
+            name = stripped;
+            break;
+        }
+    }
+
+    // Group containers carry the team id and a "group." prefix
+    let segments: Vec<&str> = name.split('.').collect();
+    let segments = match segments.as_slice() {
+        [team, rest @ ..] if is_team_id(team) => rest,
+        all => all,
+    };
+    let segments = match segments {
+        ["group", rest @ ..] => rest,
+        all => all,
+    };
+
+    let tld = segments.first()?;
+    let is_reverse_dns = segments.len() >= 3
+        && (2..=6).contains(&tld.len())
+        && tld.chars().all(|c| c.is_ascii_lowercase())
+        && segments.iter().all(|s| !s.is_empty() && !s.contains(' '));
+
+    is_reverse_dns.then(|| segments.join("."))
+}
+
+fn is_team_id(segment: &str) -> bool {
+    segment.len() == 10
+        && segment
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit())
+}
+
+/// Size on disk and the latest modification of all files in the paths
+fn size_and_modified(paths: &[PathBuf]) -> (u64, Option<SystemTime>) {
+    let mut size = 0;
+    let mut modified: Option<SystemTime> = None;
+
+    for path in paths {
+        for meta in WalkDir::new(path)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter_map(|e| e.metadata().ok())
+        {
+            if meta.is_file() {
+                size += meta.len();
+            }
+            if let Ok(time) = meta.modified() {
+                modified = Some(modified.map_or(time, |m| m.max(time)));
+            }
+        }
+    }
+
+    (size, modified)
+}
@@ -0,0 +1,194 @@
+use std::env;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// System TCC database, only readable with Full Disk Access
+const SYSTEM_TCC_DB: &str = "/Library/Application Support/com.apple.TCC/TCC.db";
+
+/// Protected data that macOS privacy protection (TCC) guards
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Capability {
+    FullDiskAccess,
+    /// Containers and group containers of other apps
+    AppContainers,
+    Mail,
+    Safari,
+}
+
+impl Capability {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Capability::FullDiskAccess => "Full Disk Access",
+            Capability::AppContainers => "App containers",
+            Capability::Mail => "Mail",
+            Capability::Safari => "Safari",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Access {
+    Granted,
+    Denied,
+    /// Nothing to probe on this Mac, like Mail that was never set up
+    NotPresent,
+}
+
+impl Access {
+    pub fn from_io_error(err: &io::Error) -> Self {
+        match err.kind() {
+            io::ErrorKind::NotFound => Access::NotPresent,
+            // TCC denies with EPERM, plain file permissions with EACCES
+            _ => Access::Denied,
+        }
+    }
+}
+
+/// A protected path to try and the locations it stands for
+#[derive(Debug, Clone)]
+pub struct PermissionCheck {
+    pub capability: Capability,
+    pub probe: Option<PathBuf>,
+    pub scope: Vec<PathBuf>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ProbeResult {
+    pub capability: Capability,
+    pub access: Access,
+    pub scope: Vec<PathBuf>,
+}
+
+#[derive(Debug, Clone)]
+pub struct PermissionProbe {
+    pub checks: Vec<PermissionCheck>,
+}
+
+impl PermissionProbe {
+    /// Probe the protected locations of the current user
+    pub fn new() -> Self {
+        let home = env::var("HOME")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| PathBuf::from("/Users/Unknown"));
+
+        Self::with_home(&home)
+    }
+
+    pub fn with_home(home: &Path) -> Self {
+        let library = home.join("Library");
+        let containers = library.join("Containers");
+
+        let checks = vec![
+            PermissionCheck {
+                capability: Capability::FullDiskAccess,
+                probe: Some(PathBuf::from(SYSTEM_TCC_DB)),
+                scope: Vec::new(),
+            },
+            PermissionCheck {
+                capability: Capability::AppContainers,
+                probe: Self::other_app_container(&containers),
+                scope: vec![containers, library.join("Group Containers")],
+            },
+            PermissionCheck {
+                capability: Capability::Mail,
+                probe: Some(library.join("Mail")),
+                scope: vec![library.join("Mail")],
+            },
+            PermissionCheck {
+                capability: Capability::Safari,
+                probe: Some(library.join("Safari")),
+                scope: vec![library.join("Safari")],
+            },
+        ];
+
+        Self { checks }
+    }
+
+    /// Listing Containers is allowed, reading inside one of them is not
+    fn other_app_container(containers: &Path) -> Option<PathBuf> {
+        let mut entries: Vec<PathBuf> = std::fs::read_dir(containers)
+            .ok()?
+            .filter_map(|e| e.ok())
+            .map(|e| e.path())
+            .filter(|p| p.is_dir())
+            .collect();
+        entries.sort();
+
+        entries.into_iter().next()
+    }
+
+    pub fn run(&self) -> PermissionReport {
+        let results = self
+            .checks
+            .iter()
+            .map(|check| ProbeResult {
+                capability: check.capability,
+                access: check
+                    .probe
+                    .as_deref()
+                    .map(Self::probe_path)
+                    .unwrap_or(Access::NotPresent),
+                scope: check.scope.clone(),
+            })
+            .collect();
+
+        PermissionReport { results }
+    }
+
+    fn probe_path(path: &Path) -> Access {
+        // Only opening tells, metadata is readable even without access
+        let result = if path.is_dir() {
+            std::fs::read_dir(path).and_then(|mut entries| entries.next().transpose().map(|_| ()))
+        } else {
+            std::fs::File::open(path).map(|_| ())
+        };
+
+        match result {
+            Ok(()) => Access::Granted,
+            Err(err) => Access::from_io_error(&err),
+        }
+    }
+}
+
+impl Default for PermissionProbe {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Which capabilities are available before scanning
+#[derive(Debug, Clone, Default)]
+pub struct PermissionReport {
+    pub results: Vec<ProbeResult>,
+}
+
+impl PermissionReport {
+    pub fn available(&self) -> Vec<Capability> {
+        self.with_access(Access::Granted)
+    }
+
+    pub fn denied(&self) -> Vec<Capability> {
+        self.with_access(Access::Denied)
+    }
+
+    fn with_access(&self, access: Access) -> Vec<Capability> {
+        self.results
+            .iter()
+            .filter(|r| r.access == access)
+            .map(|r| r.capability)
+            .collect()
+    }
+
+    /// Some protected data can not be read, a cleanup would partly fail
+    pub fn is_limited(&self) -> bool {
+        !self.denied().is_empty()
+    }
+
+    /// Whether the path lives in a location that was denied
+    pub fn is_blocked(&self, path: &Path) -> bool {
+        self.results
+            .iter()
+            .filter(|r| r.access == Access::Denied)
+            .any(|r| r.scope.iter().any(|root| path.starts_with(root)))
+    }
+}
@@ -0,0 +1,127 @@
+use std::path::{Path, PathBuf};
+
+use crate::error::{CleanerError, Result};
+use crate::syscom;
+use crate::trash_failure::{TrashFailure, TrashRemedy};
+
+/// Result of removing one path with administrator rights
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ElevatedOutcome {
+    Removed,
+    Failed(String),
+}
+
+/// Removes paths that need administrator rights, like root-owned launch daemons
+pub trait Elevator {
+    /// Remove all paths at once, authorization is asked a single time.
+    /// Returns one outcome per path, an error when authorization was not granted
+    fn remove(&self, paths: &[PathBuf]) -> Result<Vec<(PathBuf, ElevatedOutcome)>>;
+}
+
+/// Asks for the administrator password with the macOS authorization dialog
+#[derive(Debug, Default, Clone, Copy)]
+pub struct AdminElevator;
+
+impl Elevator for AdminElevator {
+    fn remove(&self, paths: &[PathBuf]) -> Result<Vec<(PathBuf, ElevatedOutcome)>> {
+        let output = syscom::run_as_admin(&removal_script(paths))?;
+        parse_removal_output(paths, &output)
+    }
+}
+
+/// Failed items that removing with administrator rights would fix
+pub fn elevation_candidates(failed: &[(PathBuf, TrashFailure)]) -> Vec<PathBuf> {
+    failed
+        .iter()
+        .filter(|(path, failure)| {
+            failure.remedy() == TrashRemedy::RetryWithAdmin && path.is_absolute()
+        })
+        .map(|(path, _)| path.clone())
+        .collect()
+}
+
+/// Remove the items that need administrator rights, returns what is still left
+pub fn remove_elevated(
+    failed: Vec<(PathBuf, TrashFailure)>,
+    elevator: &dyn Elevator,
+) -> Result<Vec<(PathBuf, TrashFailure)>> {
+    let candidates = elevation_candidates(&failed);
+    if candidates.is_empty() {
+        return Ok(failed);
+    }
+
+    let outcomes = elevator.remove(&candidates)?;
+
+    let remaining = failed
+        .into_iter()
+        .filter_map(|(path, failure)| {
+            if !candidates.contains(&path) {
+                return Some((path, failure));
+            }
+
+            // A path the helper did not report on is kept, it may still be there
+            match outcomes.iter().find(|(p, _)| *p == path) {
+                Some((_, ElevatedOutcome::Removed)) => None,
+                Some((_, ElevatedOutcome::Failed(reason))) => Some((
+                    path,
+                    TrashFailure::AdminRemovalFailed {
+                        reason: reason.clone(),
+                    },
+                )),
+                None => Some((path, failure)),
+            }
+        })
+        .collect();
+
+    Ok(remaining)
+}
+
+/// Shell script that removes each path and prints its exit code on its own line
+pub fn removal_script(paths: &[PathBuf]) -> String {
+    paths
+        .iter()
+        .map(|p| format!("/bin/rm -rf -- {}; echo $?", shell_quote(p)))
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// Match the exit codes printed by the removal script to their paths.
+/// `do shell script` ends lines with `\r` unless told otherwise, both are accepted.
+/// A missing or extra code means the output can not be trusted
+pub fn parse_removal_output(
+    paths: &[PathBuf],
+    output: &str,
+) -> Result<Vec<(PathBuf, ElevatedOutcome)>> {
+    let codes: Vec<&str> = output
+        .split(['\r', '\n'])
+        .map(str::trim)
+        .filter(|code| !code.is_empty())
+        .collect();
+
+    if codes.len() != paths.len() {
+        return Err(CleanerError::Elevation {
+            reason: format!(
+                "expected {} exit codes, got {}: {:?}",
+                paths.len(),
+                codes.len(),
+                output
+            ),
+        });
+    }
+
+    Ok(paths
+        .iter()
+        .zip(codes)
+        .map(|(path, code)| {
+            let outcome = match code {
+                "0" => ElevatedOutcome::Removed,
+                code => ElevatedOutcome::Failed(format!("rm exited with {}", code)),
+            };
+            (path.clone(), outcome)
+        })
+        .collect())
+}
+
+fn shell_quote(path: &Path) -> String {
+    format!("'{}'", path.to_string_lossy().replace('\'', "'\\''"))
+}
@@ -0,0 +1,295 @@
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::env;
+use std::path::{Component, Path, PathBuf};
+
+use crate::app_data::MatchRule;
+use crate::error::{CleanerError, Result};
+use crate::orphans::parse_bundle_id;
+
+/// Profiles shipped with Bristo, contributions go to cleaner/profiles
+const BUILTIN_PROFILES: [(&str, &str); 3] = [
+    (
+        "com.docker.docker.toml",
+        include_str!("../profiles/com.docker.docker.toml"),
+    ),
+    (
+        "com.google.chrome.toml",
+        include_str!("../profiles/com.google.chrome.toml"),
+    ),
+    (
+        "com.microsoft.vscode.toml",
+        include_str!("../profiles/com.microsoft.vscode.toml"),
+    ),
+];
+
+/// Folders where launchd looks for the plist of a label
+const LAUNCHD_DIRS: [&str; 3] = [
+    "~/Library/LaunchAgents",
+    "/Library/LaunchAgents",
+    "/Library/LaunchDaemons",
+];
+
+/// Files of an app that matching by name can not find, written as data
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AppProfile {
+    pub bundle_id: String,
+    /// Only for people reading the profile
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Absolute or "~/" paths, listed when they exist
+    #[serde(default)]
+    pub paths: Vec<String>,
+    /// Path patterns like "~/Library/Containers/com.vendor.*"
+    #[serde(default)]
+    pub globs: Vec<String>,
+    /// Labels of launch agents and daemons, their plists are listed
+    #[serde(default)]
+    pub launchd_labels: Vec<String>,
+    /// Match rules like "glob com.vendor.*", see MatchRule::parse
+    #[serde(default)]
+    pub rules: Vec<String>,
+    /// Shared with other apps, never listed even when something matches them.
+    /// Folders holding one of them are kept too, their other entries can go
+    #[serde(default)]
+    pub never_delete: Vec<String>,
+}
+
+impl AppProfile {
+    pub fn parse(text: &str, source: &Path) -> Result<Self> {
+        let profile: AppProfile = toml::from_str(text).map_err(|e| CleanerError::Profile {
+            path: source.to_path_buf(),
+            reason: e.message().to_string(),
+        })?;
+
+        profile
+            .validate()
+            .map_err(|problems| CleanerError::Profile {
+                path: source.to_path_buf(),
+                reason: problems.join("; "),
+            })?;
+
+        Ok(profile)
+    }
+
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path).map_err(|e| CleanerError::io(path, e))?;
+        Self::parse(&text, path)
+    }
+
+    /// Every problem of the profile, so a contributor can fix them at once
+    pub fn validate(&self) -> std::result::Result<(), Vec<String>> {
+        let mut problems = Vec::new();
+
+        if parse_bundle_id(&self.bundle_id).as_deref() != Some(self.bundle_id.as_str()) {
+            problems.push(format!("'{}' is not a bundle id", self.bundle_id));
+        }
+
+        if self.paths.is_empty()
+            && self.globs.is_empty()
+            && self.launchd_labels.is_empty()
+            && self.rules.is_empty()
+        {
+            problems.push("nothing to find, add paths, globs, launchd_labels or rules".to_string());
+        }
+
+        for (field, paths) in [
+            ("paths", &self.paths),
+            ("globs", &self.globs),
+            ("never_delete", &self.never_delete),
+        ] {
+            for path in paths {
+                if let Some(problem) = path_problem(path) {
+                    problems.push(format!("{} '{}': {}", field, path, problem));
+                }
+            }
+        }
+
+        for pattern in &self.globs {
+            if let Err(e) = glob::Pattern::new(pattern) {
+                problems.push(format!("globs '{}': {}", pattern, e.msg));
+            }
+        }
+
+        for label in &self.launchd_labels {
+            if label.is_empty() || label.contains(|c: char| c == '/' || c.is_whitespace()) {
+                problems.push(format!("launchd_labels '{}' is not a label", label));
+            }
+        }
+
+        for rule in &self.rules {
+            if let Err(e) = MatchRule::parse(rule) {
+                problems.push(format!("rules: {}", e));
+            }
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(problems)
+        }
+    }
+
+    /// Existing files of the paths, globs and launchd labels
+    pub fn resolve_paths(&self, home: &Path) -> Vec<PathBuf> {
+        let mut found: Vec<PathBuf> = self
+            .paths
+            .iter()
+            .map(|p| expand_home(p, home))
+            .filter(|p| p.symlink_metadata().is_ok())
+            .collect();
+
+        for pattern in &self.globs {
+            let pattern = expand_home(pattern, home);
+            if let Ok(paths) = glob::glob(&pattern.to_string_lossy()) {
+                found.extend(paths.filter_map(|p| p.ok()));
+            }
+        }
+
+        for label in &self.launchd_labels {
+            found.extend(
+                LAUNCHD_DIRS
+                    .iter()
+                    .map(|dir| expand_home(dir, home).join(format!("{}.plist", label)))
+                    .filter(|p| p.exists()),
+            );
+        }
+
+        let never_delete = self.never_delete_paths(home);
+        let mut seen = std::collections::HashSet::new();
+        found.retain(|p| {
+            !never_delete.iter().any(|keep| keep.starts_with(p)) && seen.insert(p.clone())
+        });
+        found
+    }
+
+    pub fn never_delete_paths(&self, home: &Path) -> Vec<PathBuf> {
+        self.never_delete
+            .iter()
+            .map(|p| expand_home(p, home))
+            .collect()
+    }
+
+    pub fn match_rules(&self) -> Vec<MatchRule<'static>> {
+        // Validated when loaded
+        self.rules
+            .iter()
+            .filter_map(|r| MatchRule::parse(r).ok())
+            .collect()
+    }
+}
+
+/// Why a profile path can not be used, profile paths must not climb or reach everything
+fn path_problem(path: &str) -> Option<&'static str> {
+    let rest = match path.strip_prefix("~/") {
+        Some(rest) => rest,
+        None if path.starts_with('/') => path,
+        None => return Some("must be absolute or start with ~/"),
+    };
+
+    let path = Path::new(rest);
+    if path.components().any(|c| c == Component::ParentDir) {
+        return Some("must not contain ..");
+    }
+    if path
+        .components()
+        .all(|c| c == Component::RootDir || c == Component::CurDir)
+    {
+        return Some("is a root folder");
+    }
+    if path.starts_with("/System") {
+        return Some("is part of macOS");
+    }
+
+    None
+}
+
+fn expand_home(path: &str, home: &Path) -> PathBuf {
+    match path.strip_prefix("~/") {
+        Some(rest) => home.join(rest),
+        None => PathBuf::from(path),
+    }
+}
+
+/// Profiles keyed by lowercase bundle id
+#[derive(Debug, Clone, Default)]
+pub struct ProfileDb {
+    pub profiles: HashMap<String, AppProfile>,
+    /// Profile files that were skipped, with why they could not be used
+    pub problems: Vec<(PathBuf, CleanerError)>,
+}
+
+impl ProfileDb {
+    /// Shipped profiles, overridden by the ones of the user
+    pub fn load() -> Result<Self> {
+        let mut db = Self::builtin()?;
+
+        let user_dir = Self::user_dir();
+        if user_dir.is_dir() {
+            db.merge(Self::load_dir(&user_dir)?);
+        }
+
+        Ok(db)
+    }
+
+    pub fn builtin() -> Result<Self> {
+        let mut db = Self::default();
+        for (name, text) in BUILTIN_PROFILES {
+            let source = Path::new("profiles").join(name);
+            db.insert(AppProfile::parse(text, &source)?, &source)?;
+        }
+        Ok(db)
+    }
+
+    /// All .toml files of the folder, one profile each.
+    /// A file that can not be used is skipped and kept in the problems
+    pub fn load_dir(dir: &Path) -> Result<Self> {
+        let mut files: Vec<PathBuf> = std::fs::read_dir(dir)
+            .map_err(|e| CleanerError::io(dir, e))?
+            .filter_map(|e| e.ok())
+            .map(|e| e.path())
+            .filter(|p| p.extension().is_some_and(|e| e == "toml"))
+            .collect();
+        files.sort();
+
+        let mut db = Self::default();
+        for file in files {
+            let loaded = AppProfile::from_file(&file).and_then(|p| db.insert(p, &file));
+            if let Err(err) = loaded {
+                db.problems.push((file, err));
+            }
+        }
+        Ok(db)
+    }
+
+    /// ~/Library/Application Support/Bristo/profiles
+    pub fn user_dir() -> PathBuf {
+        env::var("HOME")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| PathBuf::from("/Users/Unknown"))
+            .join("Library/Application Support/Bristo/profiles")
+    }
+
+    fn insert(&mut self, profile: AppProfile, source: &Path) -> Result<()> {
+        let key = profile.bundle_id.to_lowercase();
+        if self.profiles.contains_key(&key) {
+            return Err(CleanerError::Profile {
+                path: source.to_path_buf(),
+                reason: format!("a profile for '{}' already exists", profile.bundle_id),
+            });
+        }
+        self.profiles.insert(key, profile);
+        Ok(())
+    }
+
+    /// Profiles of the other database replace the ones for the same app
+    pub fn merge(&mut self, other: ProfileDb) {
+        self.profiles.extend(other.profiles);
+        self.problems.extend(other.problems);
+    }
+
+    pub fn find(&self, bundle_id: &str) -> Option<&AppProfile> {
+        self.profiles.get(&bundle_id.to_lowercase())
+    }
+}
@@ -0,0 +1,204 @@
+use std::env;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use crate::app_data::LocationsScan;
+use crate::error::{CleanerError, Result};
+use crate::helpers::resolve_lexically;
+use crate::trash_failure::TrashFailure;
+
+/// Why the guard kept an item out of the Trash
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Refusal {
+    /// A folder the scan looks in, like ~/Library/Caches
+    ScanRoot,
+    /// Holds a folder the scan looks in, like ~/Library or /Library
+    HoldsScanRoot,
+    /// The home folder or a folder holding it
+    Home,
+    /// Part of macOS under /System
+    System,
+    /// Listed in the never delete file of the user
+    Allowlisted,
+    /// Shared with other apps as the profile of the app says, or holds such a path
+    Shared,
+}
+
+impl Refusal {
+    /// Short advice for the user on what to do about it
+    pub fn hint(&self) -> &'static str {
+        match self {
+            Refusal::ScanRoot | Refusal::HoldsScanRoot | Refusal::Home => {
+                "Shared by all apps, remove what is inside it manually if needed"
+            }
+            Refusal::System => "Part of macOS, it is never removed",
+            Refusal::Allowlisted => {
+                "Remove it from ~/Library/Application Support/Bristo/never_delete to allow it"
+            }
+            Refusal::Shared => "Other apps use it, what is inside it can go",
+        }
+    }
+}
+
+impl fmt::Display for Refusal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Refusal::ScanRoot => write!(f, "Searched folder"),
+            Refusal::HoldsScanRoot => write!(f, "Holds searched folders"),
+            Refusal::Home => write!(f, "Home folder"),
+            Refusal::System => write!(f, "System folder"),
+            Refusal::Allowlisted => write!(f, "Marked never delete"),
+            Refusal::Shared => write!(f, "Shared with other apps"),
+        }
+    }
+}
+
+/// Outcome of moving items to the Trash
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TrashReport {
+    /// Tried but could not be moved
+    pub failed: Vec<(PathBuf, TrashFailure)>,
+    /// Never tried, the safety guard kept them
+    pub refused: Vec<(PathBuf, Refusal)>,
+    /// Not tried yet when the operation was cancelled
+    pub skipped: Vec<PathBuf>,
+}
+
+impl TrashReport {
+    /// Everything was moved to the Trash
+    pub fn is_clean(&self) -> bool {
+        self.failed.is_empty() && self.refused.is_empty() && self.skipped.is_empty()
+    }
+}
+
+/// Last check before anything is trashed, whatever the matching found
+#[derive(Debug, Clone)]
+pub struct SafetyGuard {
+    roots: Vec<PathBuf>,
+    home: PathBuf,
+    allowlist: Vec<PathBuf>,
+    shared: Vec<PathBuf>,
+}
+
+impl SafetyGuard {
+    pub fn new(locations: &LocationsScan, home: &Path) -> Self {
+        Self {
+            roots: locations.paths.iter().flat_map(|p| forms(p)).collect(),
+            home: resolve_lexically(home),
+            allowlist: Vec::new(),
+            shared: Vec::new(),
+        }
+    }
+
+    /// Guard of the usual scan locations with the never delete file of the user
+    pub fn for_user() -> Result<Self> {
+        let home = home_dir();
+        let allowlist = Self::load_allowlist(&home)?;
+        Ok(Self::new(&LocationsScan::new(), &home).with_allowlist(allowlist))
+    }
+
+    /// Paths that must never be touched, nor what is in them or holds them
+    pub fn with_allowlist(mut self, paths: impl IntoIterator<Item = PathBuf>) -> Self {
+        self.allowlist
+            .extend(paths.into_iter().flat_map(|p| forms(&p)));
+        self
+    }
+
+    /// Paths shared with other apps, kept with the folders holding them.
+    /// What is inside them can go
+    pub fn with_shared(mut self, paths: impl IntoIterator<Item = PathBuf>) -> Self {
+        self.shared
+            .extend(paths.into_iter().flat_map(|p| forms(&p)));
+        self
+    }
+
+    /// ~/Library/Application Support/Bristo/never_delete
+    pub fn allowlist_path() -> PathBuf {
+        home_dir().join("Library/Application Support/Bristo/never_delete")
+    }
+
+    /// One path per line, "~/" is the home folder and "#" starts a comment.
+    /// Empty when there is no file
+    pub fn load_allowlist(home: &Path) -> Result<Vec<PathBuf>> {
+        let path = Self::allowlist_path();
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let text = std::fs::read_to_string(&path).map_err(|e| CleanerError::io(&path, e))?;
+        Ok(parse_allowlist(&text, home))
+    }
+
+    /// Why the path must not be trashed, None when it can go
+    pub fn check(&self, path: &Path) -> Option<Refusal> {
+        let forms = forms(path);
+        let any = |test: &dyn Fn(&Path) -> bool| forms.iter().any(|p| test(p));
+
+        if any(&|p| p.starts_with("/System")) {
+            return Some(Refusal::System);
+        }
+        if any(&|p| self.home.starts_with(p)) {
+            return Some(Refusal::Home);
+        }
+        if any(&|p| self.roots.iter().any(|root| root == p)) {
+            return Some(Refusal::ScanRoot);
+        }
+        if any(&|p| self.roots.iter().any(|root| root.starts_with(p))) {
+            return Some(Refusal::HoldsScanRoot);
+        }
+        if any(&|p| {
+            self.allowlist
+                .iter()
+                .any(|keep| p.starts_with(keep) || keep.starts_with(p))
+        }) {
+            return Some(Refusal::Allowlisted);
+        }
+        if any(&|p| self.shared.iter().any(|keep| keep.starts_with(p))) {
+            return Some(Refusal::Shared);
+        }
+
+        None
+    }
+
+    /// Split the paths into the ones that can be trashed and the refused ones
+    pub fn partition(&self, paths: &[PathBuf]) -> (Vec<PathBuf>, Vec<(PathBuf, Refusal)>) {
+        let mut allowed = Vec::new();
+        let mut refused = Vec::new();
+
+        for path in paths {
+            match self.check(path) {
+                Some(refusal) => refused.push((path.clone(), refusal)),
+                None => allowed.push(path.clone()),
+            }
+        }
+
+        (allowed, refused)
+    }
+}
+
+/// The path as written and, when it exists, with its links resolved,
+/// so "/tmp/x" and "/private/tmp/x" are the same item
+fn forms(path: &Path) -> Vec<PathBuf> {
+    let lexical = resolve_lexically(path);
+    match std::fs::canonicalize(&lexical) {
+        Ok(canonical) if canonical != lexical => vec![lexical, canonical],
+        _ => vec![lexical],
+    }
+}
+
+fn parse_allowlist(text: &str, home: &Path) -> Vec<PathBuf> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| match line.strip_prefix("~/") {
+            Some(rest) => home.join(rest),
+            None => PathBuf::from(line),
+        })
+        .collect()
+}
+
+fn home_dir() -> PathBuf {
+    env::var("HOME")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| PathBuf::from("/Users/Unknown"))
+}
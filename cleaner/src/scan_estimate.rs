@@ -0,0 +1,84 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::app_data::LocationsScan;
+use crate::cancel::CancelToken;
+use crate::error::{CleanerError, Result};
+
+/// Entries under each scan root, from earlier scans or guessed ahead,
+/// so the file scan can tell how far it is
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScanEstimate {
+    counts: HashMap<PathBuf, usize>,
+}
+
+impl ScanEstimate {
+    /// ~/Library/Caches/Bristo/scan_counts.json
+    pub fn cache_path() -> PathBuf {
+        std::env::var("HOME")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| PathBuf::from("/Users/Unknown"))
+            .join("Library/Caches/Bristo/scan_counts.json")
+    }
+
+    /// Counts of the earlier scans. A missing or broken cache only means guessing again
+    pub fn load_from(path: &Path) -> Self {
+        std::fs::read_to_string(path)
+            .ok()
+            .and_then(|text| serde_json::from_str(&text).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save_to(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| CleanerError::io(dir, e))?;
+        }
+        let text = serde_json::to_string(self).map_err(|e| CleanerError::io(path, e.into()))?;
+        std::fs::write(path, text).map_err(|e| CleanerError::io(path, e))
+    }
+
+    pub fn count(&self, root: &Path) -> Option<usize> {
+        self.counts.get(root).copied()
+    }
+
+    /// Entries the scan of these locations will walk. Roots never scanned
+    /// before are estimated by their top entries only, a bound guess
+    /// that the first scan of them may go past.
+    /// None when cancelled while counting
+    pub fn total(&self, locations: &LocationsScan, cancel: &CancelToken) -> Option<usize> {
+        let total = locations
+            .paths
+            .iter()
+            .filter(|root| root.exists())
+            .map(|root| match self.count(root) {
+                Some(count) => count,
+                None if cancel.is_cancelled() => 0,
+                None => top_entries(root),
+            })
+            .sum();
+
+        if cancel.is_cancelled() {
+            return None;
+        }
+        Some(total)
+    }
+
+    /// Remember what a complete scan walked, the next total is based on it
+    pub fn record(&mut self, counts: &HashMap<PathBuf, usize>) {
+        self.counts
+            .extend(counts.iter().map(|(root, count)| (root.clone(), *count)));
+    }
+}
+
+// Like the first level of walk_root, files and folders but no links
+fn top_entries(root: &Path) -> usize {
+    std::fs::read_dir(root)
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .filter(|e| e.file_type().is_ok_and(|t| t.is_file() || t.is_dir()))
+                .count()
+        })
+        .unwrap_or(0)
+}
@@ -0,0 +1,44 @@
+use std::path::PathBuf;
+
+use crate::app_data::{LocationsScan, MatchRuleSet};
+use crate::error::Result;
+use crate::homebrew::Homebrew;
+use crate::profile::ProfileDb;
+use crate::scan_estimate::ScanEstimate;
+
+/// Where a scan looks and what it reads and writes besides the files it looks at.
+/// The user's home for the app, tests and other callers can point it elsewhere
+#[derive(Debug, Clone)]
+pub struct ScanSetup {
+    pub locations: LocationsScan,
+    /// Homebrew whose casks are looked up, None when there is none
+    pub homebrew: Option<Homebrew>,
+    /// Rules file of the user, None or a missing file for no rules
+    pub rules_file: Option<PathBuf>,
+    pub profiles: ProfileDb,
+    /// Where the entry counts of the scan roots are kept, None to keep nothing
+    pub estimate_cache: Option<PathBuf>,
+}
+
+impl ScanSetup {
+    /// Scan of the given locations only, nothing else is read or written
+    pub fn new(locations: LocationsScan) -> Self {
+        Self {
+            locations,
+            homebrew: None,
+            rules_file: None,
+            profiles: ProfileDb::default(),
+            estimate_cache: None,
+        }
+    }
+
+    pub fn for_user() -> Result<Self> {
+        Ok(Self {
+            locations: LocationsScan::new(),
+            homebrew: Homebrew::detect(),
+            rules_file: Some(MatchRuleSet::user_rules_path()),
+            profiles: ProfileDb::load()?,
+            estimate_cache: Some(ScanEstimate::cache_path()),
+        })
+    }
+}
@@ -1,5 +1,11 @@
 mod sys_bom_log;
+mod sys_codesign;
 mod sys_component;
+mod sys_elevate;
+mod sys_spotlight;
 
 pub use sys_bom_log::*;
+pub use sys_codesign::*;
 pub use sys_component::*;
+pub use sys_elevate::*;
+pub use sys_spotlight::*;
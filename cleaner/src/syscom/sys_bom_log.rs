@@ -3,7 +3,7 @@ use std::io::Write;
 use std::path::Path;
 use std::process::Command;
 
-use anyhow::{Context, Result};
+use crate::error::{CleanerError, Result};
 
 /// OS-dependent: calls `lsbom` for a single BOM file
 pub fn run_lsbom_command(bom_file: &Path, output_file: &Path) -> Result<()> {
@@ -12,21 +12,22 @@ pub fn run_lsbom_command(bom_file: &Path, output_file: &Path) -> Result<()> {
     let output = Command::new("lsbom")
         .args(["-f", "-l", "-s", "-p", "f", &bom_file_str])
         .output()
-        .with_context(|| format!("Failed to run lsbom on {}", bom_file.display()))?;
+        .map_err(|e| CleanerError::BomParse {
+            path: bom_file.to_path_buf(),
+            reason: e.to_string(),
+        })?;
 
     if output.status.success() {
-        let mut f = File::create(output_file)
-            .with_context(|| format!("Failed to create file: {}", output_file.display()))?;
+        let mut f = File::create(output_file).map_err(|e| CleanerError::io(output_file, e))?;
         f.write_all(&output.stdout)
-            .with_context(|| format!("Failed to write BOM log: {}", output_file.display()))?;
+            .map_err(|e| CleanerError::io(output_file, e))?;
         println!("Saved BOM log: {}", output_file.display());
         Ok(())
     } else {
-        anyhow::bail!(
-            "lsbom failed for {}: {}",
-            bom_file.display(),
-            String::from_utf8_lossy(&output.stderr)
-        )
+        Err(CleanerError::BomParse {
+            path: bom_file.to_path_buf(),
+            reason: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        })
     }
 }
 
@@ -0,0 +1,38 @@
+use plist::Value;
+use std::io::Cursor;
+use std::path::Path;
+use std::process::Command;
+
+/// OS-dependent: read the team identifier from the code signature using `codesign`
+pub fn run_codesign_team_id(app_path: &Path) -> Option<String> {
+    let output = Command::new("codesign")
+        .args(["-d", "--verbose=2"])
+        .arg(app_path)
+        .output()
+        .ok()?;
+
+    // codesign writes the signature details to stderr
+    let details = String::from_utf8_lossy(&output.stderr);
+
+    details
+        .lines()
+        .find_map(|line| line.strip_prefix("TeamIdentifier="))
+        .map(|id| id.trim())
+        .filter(|id| !id.is_empty() && *id != "not set")
+        .map(|id| id.to_string())
+}
+
+/// OS-dependent: read the entitlements embedded in the code signature using `codesign`
+pub fn run_codesign_entitlements(app_path: &Path) -> Option<Value> {
+    let output = Command::new("codesign")
+        .args(["-d", "--entitlements", "-", "--xml"])
+        .arg(app_path)
+        .output()
+        .ok()?;
+
+    if !output.status.success() || output.stdout.is_empty() {
+        return None;
+    }
+
+    Value::from_reader(Cursor::new(output.stdout)).ok()
+}
@@ -1,5 +1,3 @@
-use anyhow::Result;
-use anyhow::anyhow;
 use std::ffi::CStr;
 use std::path::Path;
 use std::path::PathBuf;
@@ -10,6 +8,9 @@ use objc2_app_kit::NSWorkspace;
 use objc2_foundation::NSArray;
 use objc2_foundation::{NSError, NSFileManager, NSString, NSURL};
 // ============
+use crate::error::{CleanerError, Result};
+use crate::trash_failure::TrashFailure;
+// ============
 use libc::confstr;
 use libc::{SIGTERM, c_int, kill};
 
@@ -42,26 +43,22 @@ pub fn sysconf_path(name: i32) -> Option<PathBuf> {
     }
 }
 
-pub fn kill_pids(pids: &str) -> Result<()> {
-    for pid_str in pids.split_whitespace() {
-        // parse PID
-        let pid = pid_str
-            .parse::<i32>()
-            .map_err(|_| anyhow!("Invalid PID: {}", pid_str))?;
-
-        // call libc::kill
-        let ret = unsafe { kill(pid as c_int, SIGTERM) };
-
-        if ret != 0 {
-            // errno contains the error code
-            let err = std::io::Error::last_os_error();
-            return Err(anyhow!("Failed to kill PID {}: {}", pid, err));
-        }
+pub fn kill_pid(pid: i32) -> Result<()> {
+    // call libc::kill
+    let ret = unsafe { kill(pid as c_int, SIGTERM) };
+
+    if ret != 0 {
+        // errno contains the error code
+        let err = std::io::Error::last_os_error();
+        return Err(CleanerError::ProcessSignal {
+            pid,
+            reason: err.to_string(),
+        });
     }
     Ok(())
 }
 
-pub fn trash_files_nsfilemanager(paths: &[PathBuf]) -> Result<Vec<(PathBuf, String)>> {
+pub fn trash_files_nsfilemanager(paths: &[PathBuf]) -> Result<Vec<(PathBuf, TrashFailure)>> {
     let mut failed_paths = Vec::new();
 
     if paths.is_empty() {
@@ -72,41 +69,43 @@ pub fn trash_files_nsfilemanager(paths: &[PathBuf]) -> Result<Vec<(PathBuf, Stri
         // NSFileManager *fm = [NSFileManager defaultManager]
         let fm: Retained<NSFileManager> = msg_send![NSFileManager::class(), defaultManager];
 
-        let urls: Vec<Retained<NSURL>> = paths
-            .iter()
-            .filter_map(|path| {
-                let s = path.to_str()?;
-                let ns_string = NSString::from_str(s);
-                let url: Retained<NSURL> = msg_send![NSURL::class(), fileURLWithPath: &*ns_string];
-                Some(url)
-            })
-            .collect();
-
-        for (i, url) in urls.iter().enumerate() {
+        for path in paths {
+            let Some(s) = path.to_str() else {
+                failed_paths.push((
+                    path.clone(),
+                    TrashFailure::Other {
+                        domain: "InvalidPath".to_string(),
+                        code: 0,
+                    },
+                ));
+                continue;
+            };
+            let ns_string = NSString::from_str(s);
+            let url: Retained<NSURL> = msg_send![NSURL::class(), fileURLWithPath: &*ns_string];
+
             let mut resulting_url: *mut NSURL = std::ptr::null_mut();
             let mut error: *mut NSError = std::ptr::null_mut();
 
             let success: bool = msg_send![
                 &*fm,
-                trashItemAtURL: &**url,
+                trashItemAtURL: &*url,
                 resultingItemURL: &mut resulting_url,
                 error: &mut error
             ];
 
             if !success {
-                let reason = if !error.is_null() {
+                let failure = if !error.is_null() {
                     let domain = (*error).domain().to_string();
-                    let code = (*error).code();
-                    if domain == "NSCocoaErrorDomain" && code == 513 {
-                        "Permission not allowed by macOS privacy protection (TCC)".to_string()
-                    } else {
-                        format!("Failed with {} ({})", domain, code)
-                    }
+                    let code = (*error).code() as i64;
+                    TrashFailure::from_ns_error(&domain, code, path)
                 } else {
-                    "unknown reason".to_string()
+                    TrashFailure::Other {
+                        domain: "unknown".to_string(),
+                        code: 0,
+                    }
                 };
 
-                failed_paths.push((paths[i].clone(), reason));
+                failed_paths.push((path.clone(), failure));
             }
         }
     }
@@ -115,9 +114,11 @@ pub fn trash_files_nsfilemanager(paths: &[PathBuf]) -> Result<Vec<(PathBuf, Stri
 }
 
 pub fn show_in_finder(path: &Path) -> Result<()> {
-    let s = path
-        .to_str()
-        .ok_or_else(|| anyhow!("Path is not valid UTF-8"))?;
+    let s = path.to_str().ok_or_else(|| CleanerError::Io {
+        path: path.to_path_buf(),
+        kind: std::io::ErrorKind::InvalidInput,
+        reason: "Path is not valid UTF-8".to_string(),
+    })?;
 
     let ns_path = NSString::from_str(s);
     let url = NSURL::fileURLWithPath(&ns_path);
@@ -130,3 +131,21 @@ pub fn show_in_finder(path: &Path) -> Result<()> {
 
     Ok(())
 }
+
+pub fn open_url(url: &str) -> Result<()> {
+    let ns_string = NSString::from_str(url);
+
+    unsafe {
+        let ns_url: Option<Retained<NSURL>> = msg_send![NSURL::class(), URLWithString: &*ns_string];
+        let ns_url = ns_url.ok_or_else(|| CleanerError::Io {
+            path: PathBuf::from(url),
+            kind: std::io::ErrorKind::InvalidInput,
+            reason: "Invalid URL".to_string(),
+        })?;
+
+        let workspace = NSWorkspace::sharedWorkspace();
+        let _: bool = msg_send![&workspace, openURL: &*ns_url];
+    }
+
+    Ok(())
+}
@@ -0,0 +1,35 @@
+use std::process::Command;
+
+use crate::error::{CleanerError, Result};
+
+/// OS-dependent: run a shell script as root with `osascript`,
+/// macOS asks the user for an administrator password once
+pub fn run_as_admin(script: &str) -> Result<String> {
+    // The script goes inside an AppleScript string literal
+    let escaped = script.replace('\\', "\\\\").replace('"', "\\\"");
+    // Without the last clause the output lines end with \r
+    let apple_script = format!(
+        "do shell script \"{}\" with administrator privileges without altering line endings",
+        escaped
+    );
+
+    let output = Command::new("osascript")
+        .args(["-e", &apple_script])
+        .output()
+        .map_err(|e| CleanerError::Elevation {
+            reason: e.to_string(),
+        })?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        // -128 is "User canceled" in the authorization dialog
+        let reason = if stderr.contains("-128") {
+            "Authorization cancelled".to_string()
+        } else {
+            stderr.trim().to_string()
+        };
+        return Err(CleanerError::Elevation { reason });
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
@@ -0,0 +1,21 @@
+use std::path::Path;
+use std::process::Command;
+use std::time::SystemTime;
+
+use crate::helpers::parse_utc_timestamp;
+
+/// OS-dependent: last time the app was opened, from the Spotlight metadata using `mdls`
+pub fn run_mdls_last_used(app_path: &Path) -> Option<SystemTime> {
+    let output = Command::new("mdls")
+        .args(["-name", "kMDItemLastUsedDate", "-raw"])
+        .arg(app_path)
+        .output()
+        .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    // Prints "(null)" for apps that were never opened
+    parse_utc_timestamp(String::from_utf8_lossy(&output.stdout).trim())
+}
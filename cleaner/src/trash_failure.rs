@@ -0,0 +1,163 @@
+use std::fmt;
+use std::os::unix::fs::MetadataExt;
+use std::path::Path;
+
+/// NSCocoaErrorDomain codes from FoundationErrors.h
+const NS_FILE_NO_SUCH_FILE: i64 = 4;
+const NS_FILE_LOCKING: i64 = 255;
+const NS_FILE_READ_NO_PERMISSION: i64 = 257;
+const NS_FILE_READ_NO_SUCH_FILE: i64 = 260;
+const NS_FILE_WRITE_NO_PERMISSION: i64 = 513;
+const NS_FILE_WRITE_VOLUME_READ_ONLY: i64 = 642;
+
+/// Folders guarded by System Integrity Protection, /usr/local is the exception
+const SIP_ROOTS: [&str; 4] = ["/System", "/bin", "/sbin", "/usr"];
+
+/// What the user can do about a failed item
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TrashRemedy {
+    RetryWithAdmin,
+    OpenFullDiskAccess,
+    RevealInFinder,
+    /// Nothing left to do, like an item that is already gone
+    None,
+}
+
+/// Why an item could not be moved to the Trash
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TrashFailure {
+    /// Owned by another user, or blocked by macOS privacy protection (TCC)
+    PermissionDenied {
+        root_owned: bool,
+    },
+    NotFound,
+    InUse,
+    ReadOnlyVolume,
+    /// Protected by System Integrity Protection, can not be removed at all
+    SipProtected,
+    /// Still failed when removed with administrator rights
+    AdminRemovalFailed {
+        reason: String,
+    },
+    Other {
+        domain: String,
+        code: i64,
+    },
+}
+
+impl TrashFailure {
+    /// Classify the NSError returned by NSFileManager for the given path
+    pub fn from_ns_error(domain: &str, code: i64, path: &Path) -> Self {
+        let failure = match domain {
+            "NSCocoaErrorDomain" => match code {
+                NS_FILE_NO_SUCH_FILE | NS_FILE_READ_NO_SUCH_FILE => TrashFailure::NotFound,
+                NS_FILE_LOCKING => TrashFailure::InUse,
+                NS_FILE_READ_NO_PERMISSION | NS_FILE_WRITE_NO_PERMISSION => {
+                    Self::permission_denied(path)
+                }
+                NS_FILE_WRITE_VOLUME_READ_ONLY => TrashFailure::ReadOnlyVolume,
+                _ => Self::other(domain, code),
+            },
+            "NSPOSIXErrorDomain" => match code as i32 {
+                libc::ENOENT => TrashFailure::NotFound,
+                libc::EBUSY | libc::ETXTBSY => TrashFailure::InUse,
+                libc::EPERM | libc::EACCES => Self::permission_denied(path),
+                libc::EROFS => TrashFailure::ReadOnlyVolume,
+                _ => Self::other(domain, code),
+            },
+            _ => Self::other(domain, code),
+        };
+
+        // Even root can not touch these, asking for admin rights would not help
+        match failure {
+            TrashFailure::PermissionDenied { .. } if is_sip_protected(path) => {
+                TrashFailure::SipProtected
+            }
+            failure => failure,
+        }
+    }
+
+    fn permission_denied(path: &Path) -> Self {
+        let root_owned = std::fs::symlink_metadata(path)
+            .map(|m| m.uid() == 0)
+            .unwrap_or(false);
+
+        TrashFailure::PermissionDenied { root_owned }
+    }
+
+    fn other(domain: &str, code: i64) -> Self {
+        TrashFailure::Other {
+            domain: domain.to_string(),
+            code,
+        }
+    }
+
+    pub fn remedy(&self) -> TrashRemedy {
+        match self {
+            TrashFailure::PermissionDenied { root_owned: true } => TrashRemedy::RetryWithAdmin,
+            TrashFailure::PermissionDenied { root_owned: false } => TrashRemedy::OpenFullDiskAccess,
+            TrashFailure::NotFound => TrashRemedy::None,
+            TrashFailure::InUse
+            | TrashFailure::ReadOnlyVolume
+            | TrashFailure::SipProtected
+            | TrashFailure::AdminRemovalFailed { .. }
+            | TrashFailure::Other { .. } => TrashRemedy::RevealInFinder,
+        }
+    }
+
+    /// Short advice for the user on what to do about it
+    pub fn hint(&self) -> &'static str {
+        match self {
+            TrashFailure::PermissionDenied { root_owned: true } => {
+                "Owned by the system, retry with administrator rights"
+            }
+            TrashFailure::PermissionDenied { root_owned: false } => {
+                "Grant Full Disk Access to Bristo in System Settings and try again"
+            }
+            TrashFailure::NotFound => "Already removed, nothing to do",
+            TrashFailure::InUse => "Quit the app using it, then try again",
+            TrashFailure::ReadOnlyVolume => "The volume is read-only, it can not be changed",
+            TrashFailure::SipProtected => {
+                "Protected by System Integrity Protection, it is part of macOS"
+            }
+            TrashFailure::AdminRemovalFailed { .. } | TrashFailure::Other { .. } => {
+                "Reveal the item in Finder and remove it manually"
+            }
+        }
+    }
+}
+
+impl fmt::Display for TrashFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TrashFailure::PermissionDenied { root_owned: true } => {
+                write!(f, "Permission denied, owned by root")
+            }
+            TrashFailure::PermissionDenied { root_owned: false } => {
+                write!(
+                    f,
+                    "Permission not allowed by macOS privacy protection (TCC)"
+                )
+            }
+            TrashFailure::NotFound => write!(f, "No such file"),
+            TrashFailure::InUse => write!(f, "In use by another process"),
+            TrashFailure::ReadOnlyVolume => write!(f, "Read-only volume"),
+            TrashFailure::SipProtected => write!(f, "Protected by SIP"),
+            TrashFailure::AdminRemovalFailed { reason } => {
+                write!(f, "Removal with administrator rights failed: {}", reason)
+            }
+            TrashFailure::Other { domain, code } => {
+                write!(f, "Failed with {} ({})", domain, code)
+            }
+        }
+    }
+}
+
+/// Whether the path lives in a folder guarded by System Integrity Protection
+pub fn is_sip_protected(path: &Path) -> bool {
+    if path.starts_with("/usr/local") {
+        return false;
+    }
+
+    SIP_ROOTS.iter().any(|root| path.starts_with(root))
+}
@@ -0,0 +1,240 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use cleaner::{AppInfo, BundleError, BundleKind, EmbeddedKind, ProcessPatterns};
+
+fn write_info_plist(bundle: &Path, bundle_id: &str, executable: &str) -> anyhow::Result<()> {
+    fs::create_dir_all(bundle.join("Contents"))?;
+
+    let plist_content = format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>CFBundleIdentifier</key>
+    <string>{bundle_id}</string>
+    <key>CFBundleExecutable</key>
+    <string>{executable}</string>
+</dict>
+</plist>
+"#
+    );
+    fs::write(bundle.join("Contents/Info.plist"), plist_content)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_embedded_bundles_are_found() -> anyhow::Result<()> {
+    let app_path = std::env::temp_dir().join("EmbeddedTest.app");
+    let _ = fs::remove_dir_all(&app_path);
+
+    write_info_plist(&app_path, "com.vendor.App", "App")?;
+    write_info_plist(
+        &app_path.join("Contents/PlugIns/Share.appex"),
+        "com.vendor.App.ShareExtension",
+        "Share",
+    )?;
+    write_info_plist(
+        &app_path.join("Contents/Library/LoginItems/Launcher.app"),
+        "com.vendor.AppLauncher",
+        "Launcher",
+    )?;
+    write_info_plist(
+        &app_path.join("Contents/XPCServices/Fetcher.xpc"),
+        "com.vendor.fetcher",
+        "Fetcher",
+    )?;
+    write_info_plist(
+        &app_path.join("Contents/Frameworks/App Helper.app"),
+        "com.vendor.App.helper",
+        "App Helper",
+    )?;
+    // Not a helper, must be ignored
+    write_info_plist(
+        &app_path.join("Contents/Frameworks/Updater.app"),
+        "com.vendor.updater",
+        "Updater",
+    )?;
+
+    let app_info = AppInfo::from_path(&app_path)?;
+
+    let mut ids: Vec<&str> = app_info
+        .embedded
+        .iter()
+        .map(|b| b.bundle_id.as_str())
+        .collect();
+    ids.sort();
+    assert_eq!(
+        ids,
+        [
+            "com.vendor.App.ShareExtension",
+            "com.vendor.App.helper",
+            "com.vendor.AppLauncher",
+            "com.vendor.fetcher",
+        ]
+    );
+
+    let plugin = app_info
+        .embedded
+        .iter()
+        .find(|b| b.kind == EmbeddedKind::PlugIn)
+        .unwrap();
+    assert_eq!(plugin.executable.as_deref(), Some("Share"));
+
+    // Extension containers are matched by the embedded bundle id
+    assert!(
+        app_info
+            .associate_path_matches(Path::new("/Users/me/Library/Containers/com.vendor.fetcher"))
+    );
+    assert!(
+        !app_info
+            .associate_path_matches(Path::new("/Users/me/Library/Containers/com.vendor.updater"))
+    );
+
+    let _ = fs::remove_dir_all(&app_path);
+
+    Ok(())
+}
+
+#[test]
+fn test_embedded_helpers_match_by_id_or_binary() -> anyhow::Result<()> {
+    let app_path = std::env::temp_dir().join("SparkleTest.app");
+    let _ = fs::remove_dir_all(&app_path);
+
+    write_info_plist(&app_path, "com.vendor.Sparkly", "Sparkly")?;
+    let xpc = app_path.join("Contents/XPCServices/Installer.xpc");
+    write_info_plist(&xpc, "org.sparkle-project.InstallerLauncher", "Installer")?;
+
+    let patterns = ProcessPatterns::of(&AppInfo::from_path(&app_path)?);
+    let helper = xpc.join("Contents/MacOS/Installer");
+    let helper_line = helper.display().to_string();
+
+    // The helper of the app, by its binary or its bundle id
+    assert!(patterns.matches(&helper_line, "Installer", Some(&helper)));
+    assert!(patterns.matches(&format!("{} --launch", helper_line), "Installer", None));
+    assert!(patterns.matches(
+        "/usr/libexec/xpcproxy org.sparkle-project.InstallerLauncher",
+        "xpcproxy",
+        None
+    ));
+
+    // The Installer of macOS only shares the executable name
+    let system_installer =
+        PathBuf::from("/System/Library/CoreServices/Installer.app/Contents/MacOS/Installer");
+    assert!(!patterns.matches(
+        &system_installer.display().to_string(),
+        "Installer",
+        Some(&system_installer)
+    ));
+    assert!(!patterns.matches("/usr/bin/Downloader --help", "Downloader", None));
+
+    // Anything started from inside the bundle belongs to the app
+    let tool = app_path.join("Contents/Resources/tool");
+    assert!(patterns.matches("tool", "tool", Some(&tool)));
+
+    let _ = fs::remove_dir_all(&app_path);
+
+    Ok(())
+}
+
+#[test]
+fn test_executable_inferred_from_macos_folder() -> anyhow::Result<()> {
+    let app_path = std::env::temp_dir().join("NoExecTest.app");
+    let _ = fs::remove_dir_all(&app_path);
+    fs::create_dir_all(app_path.join("Contents/MacOS"))?;
+    fs::write(app_path.join("Contents/MacOS/RealBinary"), b"")?;
+
+    let mut dict = plist::Dictionary::new();
+    dict.insert("CFBundleIdentifier".into(), "com.vendor.noexec".into());
+    // Binary plist instead of XML
+    plist::Value::Dictionary(dict).to_file_binary(app_path.join("Contents/Info.plist"))?;
+
+    let app_info = AppInfo::from_path(&app_path)?;
+    assert_eq!(app_info.bundle_id, "com.vendor.noexec");
+    assert_eq!(app_info.bundle_name, "RealBinary");
+    assert_eq!(app_info.kind, BundleKind::App);
+
+    let _ = fs::remove_dir_all(&app_path);
+
+    Ok(())
+}
+
+#[test]
+fn test_wrapped_ios_and_plugin_bundles() -> anyhow::Result<()> {
+    let base = std::env::temp_dir().join("KindTest");
+    let _ = fs::remove_dir_all(&base);
+
+    // iOS app on Mac, flat bundle inside Wrapper
+    let wrapped = base.join("Game.app");
+    let inner = wrapped.join("Wrapper/Game.app");
+    fs::create_dir_all(&inner)?;
+    fs::write(inner.join("Game"), b"")?;
+    let mut dict = plist::Dictionary::new();
+    dict.insert("CFBundleIdentifier".into(), "com.vendor.game".into());
+    plist::Value::Dictionary(dict).to_file_xml(inner.join("Info.plist"))?;
+
+    let app_info = AppInfo::from_path(&wrapped)?;
+    assert_eq!(app_info.kind, BundleKind::WrappedIos);
+    assert_eq!(app_info.bundle_id, "com.vendor.game");
+    assert_eq!(app_info.bundle_name, "Game");
+    assert_eq!(app_info.path, wrapped);
+
+    // Preference pane
+    let pane = base.join("Settings.prefPane");
+    write_info_plist(&pane, "com.vendor.settings", "Settings")?;
+    let app_info = AppInfo::from_path(&pane)?;
+    assert_eq!(app_info.kind, BundleKind::PrefPane);
+    assert_eq!(app_info.name, "Settings");
+
+    let _ = fs::remove_dir_all(&base);
+
+    Ok(())
+}
+
+#[test]
+fn test_bundle_errors_are_typed() -> anyhow::Result<()> {
+    let base = std::env::temp_dir().join("ErrorTest");
+    let _ = fs::remove_dir_all(&base);
+    fs::create_dir_all(base.join("Empty.app"))?;
+    fs::write(base.join("file.txt"), b"")?;
+
+    let missing = base.join("Missing.app");
+    assert_eq!(
+        AppInfo::from_path(&missing).unwrap_err(),
+        BundleError::NotFound(missing)
+    );
+
+    let file = base.join("file.txt");
+    assert_eq!(
+        AppInfo::from_path(&file).unwrap_err(),
+        BundleError::NotABundle(file)
+    );
+
+    let empty = base.join("Empty.app");
+    assert_eq!(
+        AppInfo::from_path(&empty).unwrap_err(),
+        BundleError::InfoPlistMissing(empty)
+    );
+
+    let no_id = base.join("NoId.app");
+    fs::create_dir_all(no_id.join("Contents"))?;
+    plist::Value::Dictionary(plist::Dictionary::new())
+        .to_file_xml(no_id.join("Contents/Info.plist"))?;
+    assert!(matches!(
+        AppInfo::from_path(&no_id),
+        Err(BundleError::MissingBundleId(_))
+    ));
+
+    let broken = base.join("Broken.app");
+    fs::create_dir_all(broken.join("Contents"))?;
+    fs::write(broken.join("Contents/Info.plist"), b"not a plist")?;
+    assert!(matches!(
+        AppInfo::from_path(&broken),
+        Err(BundleError::InvalidPlist { .. })
+    ));
+
+    let _ = fs::remove_dir_all(&base);
+
+    Ok(())
+}
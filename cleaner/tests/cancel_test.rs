@@ -0,0 +1,92 @@
+use std::fs;
+use std::path::PathBuf;
+
+use cleaner::{
+    AppData, CancelToken, Cleaner, CleanerError, LocationsScan, LogReceipt, SafetyGuard,
+};
+
+#[test]
+fn test_clones_share_the_cancel() {
+    let token = CancelToken::new();
+    let clone = token.clone();
+    assert!(token.check().is_ok());
+
+    clone.cancel();
+    assert!(token.is_cancelled());
+    assert_eq!(token.check(), Err(CleanerError::Cancelled));
+
+    // A new token starts over
+    assert!(!CancelToken::new().is_cancelled());
+}
+
+#[test]
+fn test_cancelled_scan_keeps_the_previous_files() -> anyhow::Result<()> {
+    let base = std::env::temp_dir().join("bristo_cancel_scan");
+    let _ = fs::remove_dir_all(&base);
+    fs::create_dir_all(base.join("Tool"))?;
+
+    let mut app_data = AppData::from_identity("Tool", "com.vendor.tool", "tool")?;
+    let locations = LocationsScan {
+        paths: vec![base.clone()],
+    };
+
+    let cancel = CancelToken::new();
+    cancel.cancel();
+    let result = app_data.find_associate_files_with(&locations, &cancel, |_, _| {});
+    assert_eq!(result, Err(CleanerError::Cancelled));
+    assert!(app_data.associate_files.is_empty());
+
+    app_data.find_associate_files_with(&locations, &CancelToken::new(), |_, _| {})?;
+    assert_eq!(app_data.associate_files.len(), 1);
+
+    let _ = fs::remove_dir_all(&base);
+    Ok(())
+}
+
+#[test]
+fn test_cancelled_export_writes_nothing() {
+    let log_dir = std::env::temp_dir().join("bristo_cancel_export");
+    let _ = fs::remove_dir_all(&log_dir);
+
+    let log = LogReceipt {
+        bom_file: vec![PathBuf::from(
+            "/private/var/db/receipts/com.vendor.tool.pkg.bom",
+        )],
+    };
+    let cancel = CancelToken::new();
+    cancel.cancel();
+
+    assert_eq!(
+        log.save_bom_log(&log_dir, &cancel),
+        Err(CleanerError::Cancelled)
+    );
+    assert_eq!(fs::read_dir(&log_dir).map(|d| d.count()).ok(), Some(0));
+
+    let _ = fs::remove_dir_all(&log_dir);
+}
+
+#[test]
+fn test_cancelled_trash_skips_what_is_left() -> anyhow::Result<()> {
+    let base = std::env::temp_dir().join("bristo_cancel_trash");
+    let _ = fs::remove_dir_all(&base);
+    fs::create_dir_all(base.join("Caches/com.vendor.tool"))?;
+
+    let locations = LocationsScan {
+        paths: vec![base.join("Caches")],
+    };
+    let guard = SafetyGuard::new(&locations, &base.join("home"));
+    let paths = vec![base.join("Caches/com.vendor.tool"), base.join("Caches")];
+
+    let cancel = CancelToken::new();
+    cancel.cancel();
+    let report = Cleaner::trash_guarded(&paths, &guard, None, &cancel)?;
+
+    // Refused items are still told apart, nothing was tried
+    assert!(report.failed.is_empty());
+    assert_eq!(report.refused.len(), 1);
+    assert_eq!(report.skipped, vec![base.join("Caches/com.vendor.tool")]);
+    assert!(base.join("Caches/com.vendor.tool").exists());
+
+    let _ = fs::remove_dir_all(&base);
+    Ok(())
+}
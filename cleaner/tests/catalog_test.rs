@@ -0,0 +1,126 @@
+use std::fs;
+use std::path::Path;
+
+use cleaner::{AppCatalog, CatalogDetails, CatalogSort};
+
+fn write_app(apps_dir: &Path, name: &str, bundle_id: &str, version: &str) -> anyhow::Result<()> {
+    let contents = apps_dir.join(name).join("Contents");
+    fs::create_dir_all(contents.join("MacOS"))?;
+
+    let plist_content = format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>CFBundleIdentifier</key>
+    <string>{bundle_id}</string>
+    <key>CFBundleShortVersionString</key>
+    <string>{version}</string>
+</dict>
+</plist>
+"#
+    );
+    fs::write(contents.join("Info.plist"), plist_content)?;
+
+    Ok(())
+}
+
+fn names(catalog: &AppCatalog) -> Vec<&str> {
+    catalog
+        .entries
+        .iter()
+        .map(|e| e.info.name.as_str())
+        .collect()
+}
+
+#[test]
+fn test_catalog_lists_readable_bundles() -> anyhow::Result<()> {
+    let apps_dir = std::env::temp_dir().join("bristo_catalog_scan");
+    let _ = fs::remove_dir_all(&apps_dir);
+
+    write_app(&apps_dir, "Zebra.app", "com.vendor.Zebra", "1.9")?;
+    write_app(&apps_dir, "alpha.app", "org.alpha.Alpha", "1.10.2")?;
+    write_app(
+        &apps_dir.join("Utilities"),
+        "Tool.app",
+        "net.tools.Tool",
+        "2.0",
+    )?;
+    fs::write(
+        apps_dir.join("Zebra.app/Contents/MacOS/Zebra"),
+        vec![0u8; 5000],
+    )?;
+    // Not a bundle, skipped
+    fs::create_dir_all(apps_dir.join("Broken.app"))?;
+
+    let mut catalog = AppCatalog::scan(std::slice::from_ref(&apps_dir));
+    assert_eq!(names(&catalog), vec!["alpha", "Tool", "Zebra"]);
+
+    let zebra = &catalog.entries[2];
+    assert_eq!(zebra.info.bundle_id, "com.vendor.Zebra");
+    assert_eq!(zebra.info.version.as_deref(), Some("1.9"));
+    // Only the Info.plist was read
+    assert!(zebra.details.is_none());
+    assert!(zebra.info.team_id.is_none());
+
+    // Details of the rows shown, here the first two matching "a"
+    let shown = catalog.missing_details("a", 2);
+    let shown_names: Vec<&str> = shown.iter().map(|i| i.name.as_str()).collect();
+    assert_eq!(shown_names, vec!["alpha", "Zebra"]);
+    catalog.set_details(CatalogDetails::load_all(&shown));
+    assert!(catalog.missing_details("a", 2).is_empty());
+    assert_eq!(catalog.missing_details("", 10).len(), 1);
+
+    let zebra = catalog.entry(&apps_dir.join("Zebra.app")).unwrap();
+    assert!(zebra.size().unwrap() >= 5000);
+    assert!(
+        catalog
+            .entry(&apps_dir.join("Utilities/Tool.app"))
+            .unwrap()
+            .size()
+            .is_none()
+    );
+
+    catalog.sort_by(CatalogSort::Size, false);
+    assert_eq!(catalog.entries[0].info.name, "Zebra");
+
+    // 1.10.2 is newer than 1.9
+    catalog.sort_by(CatalogSort::Version, true);
+    assert_eq!(names(&catalog), vec!["Zebra", "alpha", "Tool"]);
+
+    catalog.sort_by(CatalogSort::Name, false);
+    assert_eq!(names(&catalog), vec!["Zebra", "Tool", "alpha"]);
+
+    let _ = fs::remove_dir_all(&apps_dir);
+    Ok(())
+}
+
+#[test]
+fn test_catalog_search_by_name_and_bundle_id() -> anyhow::Result<()> {
+    let apps_dir = std::env::temp_dir().join("bristo_catalog_search");
+    let _ = fs::remove_dir_all(&apps_dir);
+
+    write_app(&apps_dir, "Editor.app", "com.vendor.Editor", "1.0")?;
+    write_app(&apps_dir, "Player.app", "org.media.Player", "1.0")?;
+
+    let catalog = AppCatalog::scan(std::slice::from_ref(&apps_dir));
+
+    let found: Vec<&str> = catalog
+        .search("EDIT")
+        .iter()
+        .map(|e| e.info.name.as_str())
+        .collect();
+    assert_eq!(found, vec!["Editor"]);
+
+    let found: Vec<&str> = catalog
+        .search("org.media")
+        .iter()
+        .map(|e| e.info.name.as_str())
+        .collect();
+    assert_eq!(found, vec!["Player"]);
+
+    assert_eq!(catalog.search("  ").len(), 2);
+
+    let _ = fs::remove_dir_all(&apps_dir);
+    Ok(())
+}
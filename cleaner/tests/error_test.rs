@@ -0,0 +1,47 @@
+use std::io;
+use std::path::{Path, PathBuf};
+
+use cleaner::{AppData, BundleError, CancelToken, Cleaner, CleanerError};
+
+#[test]
+fn test_io_permission_error_is_typed() {
+    let path = Path::new("/Users/me/Library/Containers/com.other.app");
+
+    let err = CleanerError::io(path, io::Error::from(io::ErrorKind::PermissionDenied));
+    assert_eq!(
+        err,
+        CleanerError::PermissionDenied {
+            path: path.to_path_buf()
+        }
+    );
+    assert!(err.needs_full_disk_access());
+    assert!(err.hint().is_some());
+
+    let err = CleanerError::io(path, io::Error::from(io::ErrorKind::NotFound));
+    assert!(matches!(
+        err,
+        CleanerError::Io {
+            kind: io::ErrorKind::NotFound,
+            ..
+        }
+    ));
+    assert_eq!(err.path(), Some(path));
+}
+
+#[test]
+fn test_missing_bundle_is_typed() {
+    let path = PathBuf::from("/Applications/DoesNotExist.app");
+
+    let err = Cleaner::new_app(&path, None).unwrap_err();
+    assert_eq!(err, CleanerError::Bundle(BundleError::NotFound(path)));
+}
+
+#[test]
+fn test_export_without_bom_files() {
+    let app_data = AppData::default();
+
+    let err = app_data
+        .save_bom_log_app(&std::env::temp_dir(), &CancelToken::new())
+        .unwrap_err();
+    assert!(matches!(err, CleanerError::NoBomFiles { .. }));
+}
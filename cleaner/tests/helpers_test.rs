@@ -0,0 +1,74 @@
+use std::fs;
+use std::path::Path;
+
+use cleaner::{format_age, format_size, parse_utc_timestamp, total_size_of};
+
+#[test]
+fn test_format_size() {
+    assert_eq!(format_size(0), "0 B");
+    assert_eq!(format_size(999), "999 B");
+    assert_eq!(format_size(1_500), "1.5 KB");
+    assert_eq!(format_size(25_300_000), "25.3 MB");
+    assert_eq!(format_size(2_000_000_000), "2.0 GB");
+}
+
+#[test]
+fn test_total_size_counts_nested_paths_once() -> anyhow::Result<()> {
+    let base = std::env::temp_dir().join("SizeTest");
+    let _ = fs::remove_dir_all(&base);
+    fs::create_dir_all(base.join("nested"))?;
+    fs::write(base.join("a.bin"), [0u8; 100])?;
+    fs::write(base.join("nested/b.bin"), [0u8; 50])?;
+
+    let nested = base.join("nested");
+    let paths: Vec<&Path> = vec![&base, &nested];
+    assert_eq!(total_size_of(&paths), 150);
+    assert_eq!(total_size_of(&[nested.as_path()]), 50);
+
+    // Nested first, twice, and a sibling whose name starts like the folder
+    let sibling = std::env::temp_dir().join("SizeTest2");
+    let _ = fs::remove_dir_all(&sibling);
+    fs::create_dir_all(&sibling)?;
+    fs::write(sibling.join("c.bin"), [0u8; 10])?;
+    let paths: Vec<&Path> = vec![&nested, &sibling, &base, &nested];
+    assert_eq!(total_size_of(&paths), 160);
+    let _ = fs::remove_dir_all(&sibling);
+
+    let _ = fs::remove_dir_all(&base);
+
+    Ok(())
+}
+
+#[test]
+fn test_format_age() {
+    use std::time::{Duration, SystemTime};
+
+    let day = Duration::from_secs(24 * 60 * 60);
+    let now = SystemTime::now();
+
+    assert_eq!(format_age(now), "today");
+    assert_eq!(format_age(now - day), "yesterday");
+    assert_eq!(format_age(now - day * 10), "10 days ago");
+    assert_eq!(format_age(now - day * 90), "3 months ago");
+    assert_eq!(format_age(now - day * 800), "2 years ago");
+}
+
+#[test]
+fn test_parse_utc_timestamp() {
+    use std::time::{Duration, SystemTime};
+
+    assert_eq!(
+        parse_utc_timestamp("1970-01-02 00:00:00 +0000"),
+        Some(SystemTime::UNIX_EPOCH + Duration::from_secs(86_400))
+    );
+    assert_eq!(
+        parse_utc_timestamp("2024-03-05 14:22:10 +0000"),
+        Some(SystemTime::UNIX_EPOCH + Duration::from_secs(1_709_648_530))
+    );
+    // Same moment written in another time zone
+    assert_eq!(
+        parse_utc_timestamp("2024-03-05 16:22:10 +0200"),
+        parse_utc_timestamp("2024-03-05 14:22:10 +0000")
+    );
+    assert_eq!(parse_utc_timestamp("(null)"), None);
+}
@@ -0,0 +1,256 @@
+use std::fs;
+use std::os::unix::fs::symlink;
+use std::path::{Path, PathBuf};
+
+use cleaner::{AppData, AppInfo, Homebrew};
+
+/// Lay out a prefix the way `brew install --cask tool-app` leaves it
+fn write_cask_fixture(root: &Path) -> anyhow::Result<(PathBuf, PathBuf)> {
+    let prefix = root.join("homebrew");
+    let appdir = root.join("Applications");
+    let caskroom = prefix.join("Caskroom").join("tool-app");
+
+    let app = appdir.join("Tool App.app");
+    fs::create_dir_all(app.join("Contents/MacOS"))?;
+    fs::write(app.join("Contents/MacOS/tool"), b"binary")?;
+
+    fs::create_dir_all(caskroom.join("2.1.0"))?;
+    fs::create_dir_all(caskroom.join("1.0.0"))?;
+
+    // Zapped folder of the whole vendor
+    let shared = root.join("Application Support/Vendor");
+    fs::create_dir_all(&shared)?;
+
+    // Older receipt first, the newest timestamp wins
+    let old_receipt = caskroom.join(".metadata/1.0.0/20230101000000.000/Casks");
+    fs::create_dir_all(&old_receipt)?;
+    fs::write(
+        old_receipt.join("tool-app.json"),
+        r#"{"token": "tool-app", "artifacts": [{"app": ["Old Tool.app"]}]}"#,
+    )?;
+
+    let receipt = caskroom.join(".metadata/2.1.0/20240301120000.123/Casks");
+    fs::create_dir_all(&receipt)?;
+    fs::write(
+        receipt.join("tool-app.json"),
+        r#"{
+  "token": "tool-app",
+  "version": "2.1.0",
+  "artifacts": [
+    {"app": ["Tool App.app"]},
+    {"binary": ["$APPDIR/Tool App.app/Contents/MacOS/tool", {"target": "tool"}]},
+    {"manpage": ["tool.1"]},
+    {"zap": [{"trash": ["~/Library/Caches/com.vendor.tool-missing", "~/Library/*/com.vendor.tool*", "SHARED"]}]}
+  ]
+}"#
+        .replace("SHARED", &shared.display().to_string()),
+    )?;
+
+    let bin = prefix.join("bin");
+    fs::create_dir_all(&bin)?;
+    symlink(app.join("Contents/MacOS/tool"), bin.join("tool"))?;
+    // Relative link into the Caskroom the receipt does not list
+    symlink("../Caskroom/tool-app/2.1.0/helper", bin.join("tool-helper"))?;
+    // Unrelated link stays out
+    symlink("/usr/bin/true", bin.join("other"))?;
+
+    let man = prefix.join("share/man/man1");
+    fs::create_dir_all(&man)?;
+    symlink(caskroom.join("2.1.0/tool.1"), man.join("tool.1"))?;
+
+    Ok((prefix, appdir))
+}
+
+fn app_at(path: &Path, name: &str) -> AppInfo {
+    let mut app = AppInfo::from_identity(name, "com.vendor.tool", "tool");
+    app.path = path.to_path_buf();
+    app
+}
+
+#[test]
+fn test_find_cask_lists_artifacts_symlinks_and_caskroom() -> anyhow::Result<()> {
+    let root = std::env::temp_dir().join("bristo_homebrew_cask");
+    let _ = fs::remove_dir_all(&root);
+    let (prefix, appdir) = write_cask_fixture(&root)?;
+
+    let brew = Homebrew::with_prefix(&prefix).with_appdir(&appdir);
+    let app = app_at(&appdir.join("Tool App.app"), "Tool App");
+
+    let cask = brew.find_cask(&app).expect("cask should be found");
+    assert_eq!(cask.token, "tool-app");
+    assert_eq!(cask.version.as_deref(), Some("2.1.0"));
+    assert_eq!(cask.caskroom, prefix.join("Caskroom/tool-app"));
+    assert_eq!(cask.prefix, prefix);
+
+    assert_eq!(cask.artifacts, vec![appdir.join("Tool App.app")]);
+    // Zap paths that do not exist and globs are left out, the others are kept apart
+    assert_eq!(cask.zap, vec![root.join("Application Support/Vendor")]);
+
+    assert_eq!(
+        cask.symlinks,
+        vec![
+            prefix.join("bin/tool"),
+            prefix.join("share/man/man1/tool.1"),
+            prefix.join("bin/tool-helper"),
+        ]
+    );
+
+    let paths = cask.paths();
+    assert_eq!(paths.last(), Some(&prefix.join("Caskroom/tool-app")));
+    assert_eq!(paths.len(), 5);
+    assert!(!paths.contains(&root.join("Application Support/Vendor")));
+
+    assert_eq!(cask.uninstall_command(), "brew uninstall --cask tool-app");
+    assert!(cask.warning().contains("brew uninstall --cask tool-app"));
+    assert!(cask.warning().contains("1 paths its zap lists"));
+
+    let _ = fs::remove_dir_all(&root);
+    Ok(())
+}
+
+#[test]
+fn test_find_cask_ignores_apps_of_other_sources() -> anyhow::Result<()> {
+    let root = std::env::temp_dir().join("bristo_homebrew_other");
+    let _ = fs::remove_dir_all(&root);
+    let (prefix, appdir) = write_cask_fixture(&root)?;
+
+    let brew = Homebrew::with_prefix(&prefix).with_appdir(&appdir);
+    let app = app_at(&appdir.join("Editor.app"), "Editor");
+    assert!(brew.find_cask(&app).is_none());
+
+    // No Caskroom at all
+    let brew = Homebrew::with_prefix(root.join("missing"));
+    assert!(brew.find_cask(&app).is_none());
+
+    let _ = fs::remove_dir_all(&root);
+    Ok(())
+}
+
+#[test]
+fn test_find_cask_by_token_when_bundle_is_gone() -> anyhow::Result<()> {
+    let root = std::env::temp_dir().join("bristo_homebrew_token");
+    let _ = fs::remove_dir_all(&root);
+    let (prefix, appdir) = write_cask_fixture(&root)?;
+    fs::remove_dir_all(appdir.join("Tool App.app"))?;
+
+    let brew = Homebrew::with_prefix(&prefix).with_appdir(&appdir);
+    let app = AppInfo::from_identity("Tool App", "com.vendor.tool", "tool");
+
+    let cask = brew.find_cask(&app).expect("cask should be found by token");
+    assert_eq!(cask.token, "tool-app");
+    assert!(cask.artifacts.is_empty());
+
+    let _ = fs::remove_dir_all(&root);
+    Ok(())
+}
+
+#[test]
+fn test_find_cask_skips_a_token_match_that_ships_another_app() -> anyhow::Result<()> {
+    let root = std::env::temp_dir().join("bristo_homebrew_beta");
+    let _ = fs::remove_dir_all(&root);
+    let prefix = root.join("homebrew");
+    let appdir = root.join("Applications");
+    fs::create_dir_all(appdir.join("Tool App.app"))?;
+    fs::create_dir_all(appdir.join("Tool App Beta.app"))?;
+
+    // Named like the drag installed app, ships the beta
+    let receipt = prefix.join("Caskroom/tool-app/.metadata/3.0/20240301120000.123/Casks");
+    fs::create_dir_all(&receipt)?;
+    fs::write(
+        receipt.join("tool-app.json"),
+        r#"{"token": "tool-app", "artifacts": [{"app": ["Tool App Beta.app"]}]}"#,
+    )?;
+
+    let brew = Homebrew::with_prefix(&prefix).with_appdir(&appdir);
+    let app = app_at(&appdir.join("Tool App.app"), "Tool App");
+    assert!(brew.find_cask(&app).is_none());
+
+    // The beta itself is found by its artifact
+    let beta = app_at(&appdir.join("Tool App Beta.app"), "Tool App Beta");
+    assert_eq!(
+        brew.find_cask(&beta).map(|c| c.token),
+        Some("tool-app".to_string())
+    );
+
+    // Without a receipt the token is all there is to go by
+    fs::remove_dir_all(prefix.join("Caskroom/tool-app/.metadata"))?;
+    assert_eq!(
+        brew.find_cask(&app).map(|c| c.token),
+        Some("tool-app".to_string())
+    );
+
+    let _ = fs::remove_dir_all(&root);
+    Ok(())
+}
+
+#[test]
+fn test_newest_version_is_compared_by_number() -> anyhow::Result<()> {
+    let root = std::env::temp_dir().join("bristo_homebrew_versions");
+    let _ = fs::remove_dir_all(&root);
+    let prefix = root.join("homebrew");
+    let appdir = root.join("Applications");
+    fs::create_dir_all(appdir.join("Tool.app"))?;
+
+    let caskroom = prefix.join("Caskroom/tool");
+    for (version, app) in [("9.0", "Old Tool.app"), ("10.0", "Tool.app")] {
+        fs::create_dir_all(caskroom.join(version))?;
+        let receipt = caskroom.join(format!(".metadata/{version}/20240301120000.123/Casks"));
+        fs::create_dir_all(&receipt)?;
+        fs::write(
+            receipt.join("tool.json"),
+            format!(r#"{{"token": "tool", "artifacts": [{{"app": ["{app}"]}}]}}"#),
+        )?;
+    }
+
+    let brew = Homebrew::with_prefix(&prefix).with_appdir(&appdir);
+    let cask = brew
+        .find_cask(&app_at(&appdir.join("Tool.app"), "Tool"))
+        .expect("cask should be found");
+    assert_eq!(cask.version.as_deref(), Some("10.0"));
+    assert_eq!(cask.artifacts, vec![appdir.join("Tool.app")]);
+
+    let _ = fs::remove_dir_all(&root);
+    Ok(())
+}
+
+#[test]
+fn test_cask_paths_join_the_associate_files() -> anyhow::Result<()> {
+    let root = std::env::temp_dir().join("bristo_homebrew_app_data");
+    let _ = fs::remove_dir_all(&root);
+    let (prefix, appdir) = write_cask_fixture(&root)?;
+
+    let app_path = appdir.join("Tool App.app");
+    let mut app_data = AppData {
+        app: app_at(&app_path, "Tool App"),
+        associate_files: vec![(app_path.clone(), "Tool App".to_string())],
+        ..Default::default()
+    };
+
+    app_data.find_homebrew_cask(&Homebrew::with_prefix(&prefix).with_appdir(&appdir));
+    assert!(app_data.cask.is_some());
+
+    // The app is listed once, the cask adds its links and Caskroom entry
+    assert_eq!(app_data.associate_files.len(), 5);
+    assert_eq!(
+        app_data
+            .associate_files
+            .iter()
+            .filter(|(p, _)| *p == app_path)
+            .count(),
+        1
+    );
+    assert!(app_data.total_size > 0);
+    // Zapped vendor folders are not trashed with the app
+    assert!(
+        !app_data
+            .associate_files
+            .iter()
+            .any(|(p, _)| p.starts_with(root.join("Application Support")))
+    );
+
+    app_data.reset();
+    assert!(app_data.cask.is_none());
+
+    let _ = fs::remove_dir_all(&root);
+    Ok(())
+}
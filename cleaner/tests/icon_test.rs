@@ -0,0 +1,58 @@
+use std::fs;
+use std::path::PathBuf;
+
+use cleaner::{AppIcon, AppInfo};
+
+fn fixture(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures")
+        .join(name)
+}
+
+#[test]
+fn test_icns_picks_largest_entry_within_size() -> anyhow::Result<()> {
+    let bytes = fs::read(fixture("Sample.icns"))?;
+
+    // Legacy RLE entry with its mask
+    let icon = AppIcon::from_icns(&bytes, 128)?;
+    assert_eq!((icon.width, icon.height), (32, 32));
+    assert_eq!(icon.rgba.len(), 32 * 32 * 4);
+    assert_eq!(&icon.rgba[0..4], &[10, 20, 30, 200]);
+
+    // PNG entry
+    let icon = AppIcon::from_icns(&bytes, 16)?;
+    assert_eq!((icon.width, icon.height), (16, 16));
+    assert_eq!(&icon.rgba[0..4], &[255, 0, 0, 255]);
+    assert_eq!(&icon.rgba[8 * 4..8 * 4 + 4], &[0, 0, 255, 128]);
+
+    Ok(())
+}
+
+#[test]
+fn test_icns_rejects_invalid_data() {
+    assert!(AppIcon::from_icns(b"not an icon", 128).is_err());
+    assert!(AppIcon::from_icns(b"icns\x00\x00\x00\x10icp4\xff\xff\xff\xff", 128).is_err());
+}
+
+#[test]
+fn test_icon_loaded_from_bundle_resources() -> anyhow::Result<()> {
+    let app_path = std::env::temp_dir().join("IconTest.app");
+    fs::create_dir_all(app_path.join("Contents/Resources"))?;
+    fs::copy(
+        fixture("Sample.icns"),
+        app_path.join("Contents/Resources/Sample.icns"),
+    )?;
+
+    let app_info = AppInfo {
+        path: app_path.clone(),
+        icon_file: Some("Sample".to_string()),
+        ..Default::default()
+    };
+
+    let icon = AppIcon::load(&app_info, 64)?;
+    assert_eq!(icon.width, 32);
+
+    let _ = fs::remove_dir_all(&app_path);
+
+    Ok(())
+}
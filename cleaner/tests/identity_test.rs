@@ -0,0 +1,123 @@
+use std::fs;
+
+use cleaner::{
+    AppData, CleanerError, IdentitySuggestion, InstalledApps, LocationsScan, ProcessPatterns,
+    SuggestionSource,
+};
+
+#[test]
+fn test_identity_fills_missing_fields() -> anyhow::Result<()> {
+    let app_data = AppData::from_identity("", " com.vendor.Editor ", "")?;
+
+    assert_eq!(app_data.app.bundle_id, "com.vendor.Editor");
+    assert_eq!(app_data.app.name, "Editor");
+    // Not guessed from the name, "app" of "com.vendor.app" would match too much
+    assert_eq!(app_data.app.bundle_name, "");
+    assert_eq!(app_data.app.vendor_prefix, "com.vendor");
+    assert!(app_data.app.path.as_os_str().is_empty());
+    assert!(app_data.icon.is_none());
+
+    let app_data = AppData::from_identity("Editor Pro", "com.vendor.Editor", "editord")?;
+    assert_eq!(app_data.app.name, "Editor Pro");
+    assert_eq!(app_data.app.bundle_name, "editord");
+
+    Ok(())
+}
+
+#[test]
+fn test_identity_processes_match_by_bundle_id_only() -> anyhow::Result<()> {
+    let app_data = AppData::from_identity("", "com.vendor.app", "")?;
+    let patterns = ProcessPatterns::of(&app_data.app);
+
+    assert!(patterns.matches("/usr/libexec/xpcproxy com.vendor.app", "xpcproxy", None));
+    assert!(!patterns.matches("/Applications/App Store.app", "App Store", None));
+    assert!(!patterns.matches("/usr/bin/app", "app", None));
+
+    // A given executable is not trusted for processes either
+    let app_data = AppData::from_identity("", "com.vendor.app", "Helper")?;
+    let patterns = ProcessPatterns::of(&app_data.app);
+    assert!(!patterns.matches("/usr/sbin/Helper", "Helper", None));
+
+    Ok(())
+}
+
+#[test]
+fn test_identity_rejects_invalid_bundle_ids() {
+    for bundle_id in [
+        "",
+        "Editor",
+        "com.vendor.",
+        "com vendor.app",
+        "/Applications/A.app",
+    ] {
+        let err = AppData::from_identity("", bundle_id, "").unwrap_err();
+        assert!(matches!(err, CleanerError::InvalidBundleId { .. }));
+    }
+}
+
+#[test]
+fn test_identity_scan_lists_only_leftovers() -> anyhow::Result<()> {
+    let root = std::env::temp_dir().join("bristo_identity_scan");
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(root.join("com.vendor.Editor"))?;
+    fs::create_dir_all(root.join("com.other.App"))?;
+
+    let mut app_data = AppData::from_identity("", "com.vendor.Editor", "")?;
+    let locations = LocationsScan {
+        paths: vec![root.clone()],
+    };
+    app_data.find_associate_files(&locations, |_, _| {});
+
+    // The bundle itself is gone, it is not part of the list
+    assert_eq!(
+        app_data.associate_files,
+        vec![(
+            root.join("com.vendor.Editor"),
+            "com.vendor.Editor".to_string()
+        )]
+    );
+
+    let _ = fs::remove_dir_all(&root);
+    Ok(())
+}
+
+#[test]
+fn test_suggestions_from_receipts_and_preferences() -> anyhow::Result<()> {
+    let root = std::env::temp_dir().join("bristo_identity_suggestions");
+    let _ = fs::remove_dir_all(&root);
+
+    let receipts = root.join("receipts");
+    let preferences = root.join("Preferences");
+    fs::create_dir_all(&receipts)?;
+    fs::create_dir_all(&preferences)?;
+    fs::write(receipts.join("com.vendor.pkg.Editor.bom"), "")?;
+    fs::write(receipts.join("com.vendor.pkg.Editor.plist"), "")?;
+    fs::write(receipts.join("com.apple.pkg.Safari.bom"), "")?;
+    fs::write(preferences.join("com.removed.App.plist"), "")?;
+    fs::write(preferences.join("com.installed.App.plist"), "")?;
+    fs::write(preferences.join(".GlobalPreferences.plist"), "")?;
+
+    let mut installed = InstalledApps::default();
+    installed.bundle_ids.insert("com.installed.app".to_string());
+
+    let suggestions = IdentitySuggestion::collect(&[receipts], &preferences, &installed);
+    assert_eq!(
+        suggestions,
+        vec![
+            IdentitySuggestion {
+                bundle_id: "com.removed.App".to_string(),
+                source: SuggestionSource::Preferences,
+            },
+            IdentitySuggestion {
+                bundle_id: "com.vendor.pkg.Editor".to_string(),
+                source: SuggestionSource::Receipt,
+            },
+        ]
+    );
+
+    assert!(suggestions[0].matches("Removed"));
+    assert!(!suggestions[0].matches("vendor"));
+
+    let _ = fs::remove_dir_all(&root);
+    Ok(())
+}
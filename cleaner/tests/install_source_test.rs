@@ -0,0 +1,180 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use cleaner::{AppInfo, BundleKind, HomebrewCask, InstallSource, LocationsScan};
+
+fn write_bundle(app_path: &Path, bundle_id: &str) -> anyhow::Result<()> {
+    let contents = app_path.join("Contents");
+    fs::create_dir_all(&contents)?;
+
+    let plist_content = format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>CFBundleIdentifier</key>
+    <string>{bundle_id}</string>
+</dict>
+</plist>
+"#
+    );
+    fs::write(contents.join("Info.plist"), plist_content)?;
+
+    Ok(())
+}
+
+fn sample_cask() -> HomebrewCask {
+    HomebrewCask {
+        token: "tool-app".to_string(),
+        prefix: PathBuf::from("/opt/homebrew"),
+        version: Some("2.1.0".to_string()),
+        caskroom: PathBuf::from("/opt/homebrew/Caskroom/tool-app"),
+        artifacts: Vec::new(),
+        symlinks: Vec::new(),
+        zap: Vec::new(),
+    }
+}
+
+#[test]
+fn test_app_store_receipt_is_detected_from_the_bundle() -> anyhow::Result<()> {
+    let base = std::env::temp_dir().join("bristo_install_source");
+    let _ = fs::remove_dir_all(&base);
+
+    let store_app = base.join("Store.app");
+    write_bundle(&store_app, "com.vendor.store")?;
+    let receipt_dir = store_app.join("Contents/_MASReceipt");
+    fs::create_dir_all(&receipt_dir)?;
+    fs::write(receipt_dir.join("receipt"), b"receipt")?;
+
+    let dragged_app = base.join("Dragged.app");
+    write_bundle(&dragged_app, "com.vendor.dragged")?;
+
+    assert_eq!(
+        AppInfo::from_path(&store_app)?.install_source,
+        InstallSource::AppStore
+    );
+    assert_eq!(
+        AppInfo::from_path(&dragged_app)?.install_source,
+        InstallSource::DragInstall
+    );
+
+    let _ = fs::remove_dir_all(&base);
+    Ok(())
+}
+
+#[test]
+fn test_detect_prefers_app_store_then_homebrew_then_package() {
+    let app = Path::new("/nonexistent/Tool App.app");
+    let boms = vec![
+        PathBuf::from("/private/var/db/receipts/com.vendor.tool.pkg.bom"),
+        PathBuf::from("/private/var/db/receipts/com.vendor.tool.helper.pkg.bom"),
+    ];
+
+    assert_eq!(
+        InstallSource::detect(app, BundleKind::WrappedIos, Some(&sample_cask()), &boms),
+        InstallSource::AppStore
+    );
+    assert_eq!(
+        InstallSource::detect(app, BundleKind::App, Some(&sample_cask()), &boms),
+        InstallSource::Homebrew {
+            token: "tool-app".to_string(),
+            prefix: PathBuf::from("/opt/homebrew"),
+        }
+    );
+    assert_eq!(
+        InstallSource::detect(app, BundleKind::App, None, &boms),
+        InstallSource::Package("com.vendor.tool.helper.pkg".to_string())
+    );
+    assert_eq!(
+        InstallSource::detect(app, BundleKind::App, None, &[]),
+        InstallSource::DragInstall
+    );
+}
+
+#[test]
+fn test_describe_tells_how_the_app_was_installed() {
+    assert_eq!(
+        InstallSource::AppStore.describe(),
+        "Installed from the App Store"
+    );
+    assert_eq!(
+        InstallSource::Homebrew {
+            token: "tool-app".to_string(),
+            prefix: PathBuf::from("/opt/homebrew"),
+        }
+        .describe(),
+        "Installed with Homebrew cask 'tool-app'"
+    );
+    assert_eq!(
+        InstallSource::Package("com.vendor.tool.pkg".to_string()).label(),
+        "Installer package"
+    );
+}
+
+#[test]
+fn test_locations_are_prioritized_by_source() {
+    let sample = || LocationsScan {
+        paths: vec![
+            PathBuf::from("/Users/me/Library/Caches"),
+            PathBuf::from("/Users/me/Library/Containers"),
+            PathBuf::from("/Library/Application Support"),
+            PathBuf::from("/Users/me/Library/Application Support"),
+            PathBuf::from("/usr/local/bin"),
+            PathBuf::from("/Library/LaunchDaemons"),
+            PathBuf::from("/Users/me/Library/Group Containers"),
+        ],
+    };
+
+    let mut locations = sample();
+    locations.prioritize(&InstallSource::AppStore);
+    assert_eq!(
+        &locations.paths[..2],
+        &[
+            PathBuf::from("/Users/me/Library/Containers"),
+            PathBuf::from("/Users/me/Library/Group Containers"),
+        ]
+    );
+
+    let mut locations = sample();
+    locations.prioritize(&InstallSource::Package("com.vendor.pkg".to_string()));
+    assert_eq!(
+        &locations.paths[..2],
+        &[
+            PathBuf::from("/Library/Application Support"),
+            PathBuf::from("/Library/LaunchDaemons"),
+        ]
+    );
+
+    let homebrew = |prefix: &str| InstallSource::Homebrew {
+        token: "tool-app".to_string(),
+        prefix: PathBuf::from(prefix),
+    };
+
+    let mut locations = sample();
+    locations.add_homebrew_prefix(Path::new("/usr/local"));
+    locations.prioritize(&homebrew("/usr/local"));
+    assert_eq!(locations.paths[0], PathBuf::from("/usr/local/bin"));
+    assert_eq!(locations.paths[1], PathBuf::from("/usr/local/etc"));
+    assert_eq!(locations.paths.len(), 12);
+
+    // A custom prefix comes first, the default prefixes are not special
+    let mut locations = sample();
+    locations.add_homebrew_prefix(Path::new("/Users/me/brew"));
+    locations.prioritize(&homebrew("/Users/me/brew"));
+    assert_eq!(locations.paths.len(), 13);
+    assert!(
+        locations.paths[..6]
+            .iter()
+            .all(|p| p.starts_with("/Users/me/brew"))
+    );
+    assert_eq!(
+        locations.paths[6],
+        PathBuf::from("/Users/me/Library/Caches")
+    );
+    assert!(!locations.paths[..7].contains(&PathBuf::from("/usr/local/bin")));
+
+    // Drag-installed apps keep the usual order
+    let mut locations = sample();
+    locations.prioritize(&InstallSource::DragInstall);
+    assert_eq!(locations.paths, sample().paths);
+}
@@ -0,0 +1,271 @@
+use std::fs;
+use std::path::Path;
+
+use cleaner::{
+    AppData, CancelToken, Cleaner, CleanerError, LocationsScan, MatchRule, MatchRuleSet,
+    MatchRules, ScanSetup, rules_match,
+};
+
+#[test]
+fn test_suffix_rules() {
+    let path = Path::new("/Users/me/Library/Logs/Tool Helper.log");
+
+    assert!(MatchRule::new(MatchRules::Suffix, "helper.log").matches(path));
+    assert!(!MatchRule::new(MatchRules::Suffix, "tool").matches(path));
+}
+
+#[test]
+fn test_glob_rules() {
+    let prefs = Path::new("/Users/me/Library/Preferences");
+    let glob = |pattern: &str, name: &str| {
+        MatchRule::new(MatchRules::Glob, pattern).matches(&prefs.join(name))
+    };
+
+    assert!(glob("com.vendor.*", "com.vendor.app.plist"));
+    assert!(glob("com.vendor.*", "COM.Vendor.App.plist"));
+    assert!(!glob("com.vendor.*", "com.vendorx.app.plist"));
+    assert!(!glob("com.vendor.*", "org.com.vendor.app"));
+
+    assert!(glob("app-?.log", "app-1.log"));
+    assert!(!glob("app-?.log", "app-10.log"));
+
+    assert!(glob("build[0-9]", "build7"));
+    assert!(!glob("build[!0-9]", "build7"));
+    assert!(glob("build[!0-9]", "buildx"));
+
+    // Regex characters and a lone "[" are plain characters
+    assert!(glob("a+b (1)[", "a+b (1)["));
+    assert!(!glob("a+b", "aab"));
+}
+
+#[test]
+fn test_regex_rules() {
+    let path = Path::new("/Users/me/Library/Caches/com.vendor.App42");
+
+    assert!(MatchRule::new(MatchRules::Regex, r"^com\.vendor\.app\d+$").matches(path));
+    assert!(
+        !MatchRule::new(MatchRules::Regex, r"^com\.vendor\.app\d+$")
+            .case_sensitive()
+            .matches(path)
+    );
+    // Unanchored by default
+    assert!(MatchRule::new(MatchRules::Regex, r"vendor").matches(path));
+
+    // Invalid patterns match nothing and are reported by try_new
+    assert!(!MatchRule::new(MatchRules::Regex, "(unclosed").matches(path));
+    assert!(matches!(
+        MatchRule::try_new(MatchRules::Regex, "(unclosed"),
+        Err(CleanerError::InvalidRule { .. })
+    ));
+    assert!(MatchRule::try_new(MatchRules::Glob, "com.vendor.*").is_ok());
+}
+
+#[test]
+fn test_not_rules_exclude_from_the_rest() {
+    let rules = [
+        MatchRule::new(MatchRules::Glob, "com.vendor.*"),
+        MatchRule::new(MatchRules::Equal.negate(), "com.vendor.shared"),
+    ];
+    let containers = Path::new("/Users/me/Library/Group Containers");
+
+    assert!(rules_match(&rules, &containers.join("com.vendor.app")));
+    assert!(!rules_match(&rules, &containers.join("com.vendor.shared")));
+    assert!(!rules_match(&rules, &containers.join("org.other.app")));
+
+    // Exclusions alone include nothing
+    assert!(!rules_match(&rules[1..], &containers.join("org.other.app")));
+}
+
+#[test]
+fn test_parse_rule_lines() -> anyhow::Result<()> {
+    let rule = MatchRule::parse("not case-sensitive equal com.vendor.Shared")?;
+    assert_eq!(rule.kind, MatchRules::Equal.negate());
+    assert_eq!(rule.value, "com.vendor.Shared");
+    assert!(rule.options.normalization.case_sensitive);
+
+    // The value is the rest of the line, spaces included
+    let rule = MatchRule::parse("  components  equal  Visual Studio Code ")?;
+    assert_eq!(rule.value, "Visual Studio Code");
+    assert!(rule.matches(Path::new(
+        "/Users/me/Library/Application Support/Visual Studio Code/logs/main.log"
+    )));
+
+    // A keyword after the rule is part of the value
+    let rule = MatchRule::parse("prefix not this")?;
+    assert_eq!(rule.kind, MatchRules::Prefix);
+    assert_eq!(rule.value, "not this");
+
+    for line in ["", "not", "equal", "starts com.vendor", "regex (unclosed"] {
+        assert!(
+            matches!(
+                MatchRule::parse(line),
+                Err(CleanerError::InvalidRule { .. })
+            ),
+            "{line:?}"
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_rule_set_from_file() -> anyhow::Result<()> {
+    let base = std::env::temp_dir().join("bristo_rules_file");
+    let _ = fs::remove_dir_all(&base);
+    fs::create_dir_all(&base)?;
+
+    let rules_file = base.join("rules");
+    fs::write(
+        &rules_file,
+        "# Everything of the vendor but the shared group\n\
+         [com.vendor.App]\n\
+         glob com.vendor.*\n\
+         \n\
+         not equal com.vendor.shared\n\
+         \n\
+         [com.other.Tool]\n\
+         equal Other\n",
+    )?;
+
+    let set = MatchRuleSet::from_file(&rules_file, "com.vendor.app")?;
+    assert_eq!(set.rules.len(), 2);
+    assert!(set.matches(&base.join("com.vendor.app")));
+    assert!(set.includes(&base.join("com.vendor.shared")));
+    assert!(set.excludes(&base.join("com.vendor.shared")));
+
+    // Errors tell the line
+    let err = MatchRuleSet::parse("glob com.vendor.*\nnot glob\n").unwrap_err();
+    assert!(err.to_string().contains("line 2"), "{err}");
+
+    // A typo of the app fails its scan
+    let text = "[com.vendor.App]\nglob com.vendor.*\nnot glob\n";
+    let err = MatchRuleSet::parse_for(text, "com.vendor.App").unwrap_err();
+    assert!(err.to_string().contains("line 3"), "{err}");
+
+    // Another app only gets it as a problem
+    let set = MatchRuleSet::parse_for(text, "com.other.Tool")?;
+    assert!(set.is_empty());
+    assert_eq!(set.problems.len(), 1);
+    assert!(set.problems[0].to_string().contains("line 3"));
+
+    // Every rule is written for one app
+    let set = MatchRuleSet::parse_for("glob com.vendor.*\n", "com.vendor.App")?;
+    assert!(set.is_empty());
+    assert!(set.problems[0].to_string().contains("line 1"));
+
+    assert!(matches!(
+        MatchRuleSet::from_file(&base.join("missing"), "com.vendor.App"),
+        Err(CleanerError::Io { .. })
+    ));
+
+    let _ = fs::remove_dir_all(&base);
+    Ok(())
+}
+
+#[test]
+fn test_user_rules_add_and_exclude_associate_files() -> anyhow::Result<()> {
+    let base = std::env::temp_dir().join("bristo_rules_scan");
+    let _ = fs::remove_dir_all(&base);
+    for name in [
+        "com.vendor.tool",
+        "com.vendor.tool.cache",
+        "com.vendor.sync",
+        "Other",
+    ] {
+        fs::create_dir_all(base.join(name))?;
+    }
+
+    let mut app_data = AppData::from_identity("Tool", "com.vendor.tool", "tool")?;
+    app_data.extra_rules = MatchRuleSet::parse("equal com.vendor.sync\nnot suffix .cache\n")?;
+
+    let locations = LocationsScan {
+        paths: vec![base.clone()],
+    };
+    app_data.find_associate_files(&locations, |_, _| {});
+
+    let mut found: Vec<String> = app_data
+        .associate_files
+        .iter()
+        .map(|(_, label)| label.clone())
+        .collect();
+    found.sort();
+    assert_eq!(found, vec!["com.vendor.sync", "com.vendor.tool"]);
+
+    let _ = fs::remove_dir_all(&base);
+    Ok(())
+}
+
+#[test]
+fn test_user_rules_only_apply_to_their_app() -> anyhow::Result<()> {
+    let base = std::env::temp_dir().join("bristo_rules_scoped");
+    let _ = fs::remove_dir_all(&base);
+    for name in ["com.vendor.sync", "Shared Stuff"] {
+        fs::create_dir_all(base.join(name))?;
+    }
+    let text = "[com.vendor.tool]\nequal com.vendor.sync\n\n[com.other.app]\nequal Shared Stuff\n";
+    let locations = LocationsScan {
+        paths: vec![base.clone()],
+    };
+
+    let mut app_data = AppData::from_identity("Tool", "com.vendor.tool", "tool")?;
+    app_data.extra_rules = MatchRuleSet::parse_for(text, &app_data.app.bundle_id)?;
+    app_data.find_associate_files(&locations, |_, _| {});
+    let found: Vec<&str> = app_data
+        .associate_files
+        .iter()
+        .map(|(_, label)| label.as_str())
+        .collect();
+    assert_eq!(found, vec!["com.vendor.sync"]);
+
+    // The rule of the tool lists nothing for another app
+    let mut app_data = AppData::from_identity("Player", "com.vendor.player", "player")?;
+    app_data.extra_rules = MatchRuleSet::parse_for(text, &app_data.app.bundle_id)?;
+    app_data.find_associate_files(&locations, |_, _| {});
+    assert!(app_data.associate_files.is_empty());
+
+    let _ = fs::remove_dir_all(&base);
+    Ok(())
+}
+
+#[test]
+fn test_typo_of_another_app_is_a_scan_warning() -> anyhow::Result<()> {
+    let base = std::env::temp_dir().join("bristo_rules_typo");
+    let _ = fs::remove_dir_all(&base);
+    fs::create_dir_all(base.join("Library/com.vendor.sync"))?;
+    let rules_file = base.join("rules");
+    fs::write(
+        &rules_file,
+        "[com.other.app]\nnot glob\n\n[com.vendor.tool]\nequal com.vendor.sync\n",
+    )?;
+
+    let mut setup = ScanSetup::new(LocationsScan {
+        paths: vec![base.join("Library")],
+    });
+    setup.rules_file = Some(rules_file.clone());
+
+    let mut cleaner = Cleaner {
+        app_data: AppData::from_identity("Tool", "com.vendor.tool", "tool")?,
+        ..Default::default()
+    };
+    cleaner.scan_app_data_with(&setup, None, &CancelToken::new())?;
+    assert_eq!(
+        cleaner.app_data.associate_files,
+        vec![(
+            base.join("Library/com.vendor.sync"),
+            "com.vendor.sync".to_string()
+        )]
+    );
+    assert_eq!(cleaner.app_data.warnings.len(), 1);
+    assert!(cleaner.app_data.warnings[0].contains("line 2"));
+
+    // The typo fails the scan of the app it is written for
+    let mut cleaner = Cleaner {
+        app_data: AppData::from_identity("Other", "com.other.app", "other")?,
+        ..Default::default()
+    };
+    let result = cleaner.scan_app_data_with(&setup, None, &CancelToken::new());
+    assert!(matches!(result, Err(CleanerError::InvalidRule { .. })));
+
+    let _ = fs::remove_dir_all(&base);
+    Ok(())
+}
@@ -0,0 +1,166 @@
+use proptest::prelude::*;
+use std::path::Path;
+use unicode_normalization::UnicodeNormalization;
+
+use cleaner::{MatchRule, MatchRules, MatchScope, Normalization};
+
+/// Names with accents, separators and letters that change length when lowercased
+fn name_strategy() -> impl Strategy<Value = String> {
+    proptest::collection::vec(
+        prop_oneof![
+            "[a-zA-Z0-9]",
+            Just(" ".to_string()),
+            Just("-".to_string()),
+            Just("_".to_string()),
+            Just("é".to_string()),
+            Just("e\u{301}".to_string()),
+            Just("Å".to_string()),
+            Just("ü".to_string()),
+            Just("İ".to_string()),
+            Just("ı".to_string()),
+            Just("ß".to_string()),
+            Just("\u{212A}".to_string()),
+            any::<char>().prop_map(String::from),
+        ],
+        0..16,
+    )
+    .prop_map(|parts| parts.concat())
+}
+
+proptest! {
+    #[test]
+    fn test_composed_and_decomposed_forms_normalize_alike(name in name_strategy()) {
+        let n = Normalization::default();
+        let nfc: String = name.nfc().collect();
+        let nfd: String = name.nfd().collect();
+        prop_assert_eq!(n.apply(&nfc), n.apply(&nfd));
+
+        let exact = Normalization::EXACT;
+        prop_assert_eq!(exact.apply(&nfc), exact.apply(&nfd));
+    }
+
+    #[test]
+    fn test_normalization_is_idempotent(name in name_strategy()) {
+        for n in [Normalization::default(), Normalization::EXACT] {
+            let once = n.apply(&name);
+            prop_assert_eq!(n.apply(&once), once.clone());
+        }
+    }
+
+    #[test]
+    fn test_separators_do_not_matter(words in proptest::collection::vec("[a-zA-Z]{1,8}", 1..5)) {
+        let n = Normalization::default();
+        let spaced = words.join(" ");
+        prop_assert_eq!(n.apply(&spaced), n.apply(&words.join("-")));
+        prop_assert_eq!(n.apply(&spaced), n.apply(&words.join("_")));
+        prop_assert_eq!(n.apply(&spaced), n.apply(&words.concat()));
+    }
+
+    #[test]
+    fn test_ascii_case_does_not_matter(name in "[a-zA-Z0-9 ._-]{1,24}") {
+        let n = Normalization::default();
+        prop_assert_eq!(n.apply(&name.to_uppercase()), n.apply(&name.to_lowercase()));
+
+        let path = Path::new("/Users/me/Library/Caches").join(name.to_uppercase());
+        prop_assume!(path.file_name().is_some_and(|n| n == name.to_uppercase().as_str()));
+        // Names made only of separators never match
+        prop_assume!(!n.apply(&name).is_empty());
+
+        let rule = MatchRule::new(MatchRules::Equal, name.to_lowercase());
+        prop_assert!(rule.matches(&path));
+    }
+
+    #[test]
+    fn test_a_name_contains_itself_and_its_prefixes(name in name_strategy(), cut in 0usize..16) {
+        let path = Path::new("/tmp").join(&name);
+        prop_assume!(path.file_name().is_some_and(|n| n == name.as_str()));
+        prop_assume!(!Normalization::default().apply(&name).is_empty());
+
+        prop_assert!(MatchRule::new(MatchRules::Equal, name.as_str()).matches(&path));
+        prop_assert!(MatchRule::new(MatchRules::Contain, name.as_str()).matches(&path));
+
+        let prefix: String = name.chars().take(cut.max(1)).collect();
+        if !Normalization::default().apply(&prefix).is_empty() {
+            prop_assert!(MatchRule::new(MatchRules::Prefix, prefix.as_str()).matches(&path));
+        }
+    }
+}
+
+#[test]
+fn test_spellings_of_the_same_app_match() {
+    let library = Path::new("/Users/me/Library/Application Support");
+
+    for folder in [
+        "VisualStudioCode",
+        "visual-studio-code",
+        "Visual_Studio_Code",
+    ] {
+        let rule = MatchRule::new(MatchRules::Equal, "Visual Studio Code");
+        assert!(rule.matches(&library.join(folder)), "{folder}");
+    }
+
+    // Separators are kept when asked to
+    let rule = MatchRule::new(MatchRules::Equal, "Visual Studio Code").keep_separators();
+    assert!(!rule.matches(&library.join("VisualStudioCode")));
+    assert!(rule.matches(&library.join("visual studio code")));
+}
+
+#[test]
+fn test_turkish_i_is_compared_without_locale() {
+    let n = Normalization::default();
+
+    // Dotted capital I keeps its dot as a combining mark
+    assert_eq!(n.apply("İstanbul"), n.apply("I\u{307}stanbul"));
+    assert_eq!(n.apply("İstanbul"), n.apply("i\u{307}stanbul"));
+    assert_ne!(n.apply("İstanbul"), n.apply("istanbul"));
+
+    // Dotless i is its own letter
+    assert_ne!(n.apply("ıstanbul"), n.apply("istanbul"));
+    assert_eq!(n.apply("ISTANBUL"), n.apply("istanbul"));
+
+    // Kelvin sign decomposes to the plain letter
+    assert_eq!(n.apply("\u{212A}elvin"), n.apply("kelvin"));
+}
+
+#[test]
+fn test_case_sensitive_rules_keep_the_case() {
+    let path = Path::new("/Users/me/Library/Preferences/com.Vendor.App.plist");
+
+    assert!(MatchRule::new(MatchRules::Contain, "com.vendor.app").matches(path));
+    assert!(
+        !MatchRule::new(MatchRules::Contain, "com.vendor.app")
+            .case_sensitive()
+            .matches(path)
+    );
+    assert!(
+        MatchRule::new(MatchRules::Contain, "com.Vendor.App")
+            .case_sensitive()
+            .matches(path)
+    );
+}
+
+#[test]
+fn test_component_rules_look_at_the_whole_path() {
+    let path = Path::new("/Users/me/Library/Application Support/Visual Studio Code/logs/main.log");
+
+    let rule = MatchRule::new(MatchRules::Equal, "visual-studio-code");
+    assert!(!rule.matches(path));
+    assert!(rule.in_components().matches(path));
+
+    assert_eq!(
+        MatchScope::Components.names(Path::new("/Library/Caches/App")),
+        vec!["Library", "Caches", "App"]
+    );
+    assert_eq!(
+        MatchScope::FileName.names(Path::new("/Library/Caches/App")),
+        vec!["App"]
+    );
+}
+
+#[test]
+fn test_empty_values_match_nothing() {
+    let path = Path::new("/Users/me/Library/Caches/App");
+
+    assert!(!MatchRule::new(MatchRules::Contain, "").matches(path));
+    assert!(!MatchRule::new(MatchRules::Contain, " - _").matches(path));
+}
@@ -0,0 +1,156 @@
+use std::fs;
+use std::path::Path;
+
+use cleaner::{InstalledApps, LocationsScan, bundle_id_from_name, find_orphans};
+
+fn write_app(apps_dir: &Path, name: &str, bundle_id: &str) -> anyhow::Result<()> {
+    let contents = apps_dir.join(name).join("Contents");
+    fs::create_dir_all(&contents)?;
+
+    let plist_content = format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>CFBundleIdentifier</key>
+    <string>{bundle_id}</string>
+</dict>
+</plist>
+"#
+    );
+    fs::write(contents.join("Info.plist"), plist_content)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_bundle_id_from_leftover_names() {
+    assert_eq!(
+        bundle_id_from_name("com.vendor.App.plist").as_deref(),
+        Some("com.vendor.app")
+    );
+    assert_eq!(
+        bundle_id_from_name("com.vendor.App.savedState").as_deref(),
+        Some("com.vendor.app")
+    );
+    assert_eq!(
+        bundle_id_from_name("ABCDE12345.group.com.vendor.shared").as_deref(),
+        Some("com.vendor.shared")
+    );
+    assert_eq!(
+        bundle_id_from_name("group.com.vendor.shared").as_deref(),
+        Some("com.vendor.shared")
+    );
+
+    // Plain names and vendor folders are not bundle ids
+    assert_eq!(bundle_id_from_name("Google"), None);
+    assert_eq!(bundle_id_from_name("com.vendor"), None);
+    assert_eq!(bundle_id_from_name("My App 1.2.3"), None);
+}
+
+#[test]
+fn test_installed_apps_own_their_ids() -> anyhow::Result<()> {
+    let apps_dir = std::env::temp_dir().join("bristo_orphans_installed");
+    let _ = fs::remove_dir_all(&apps_dir);
+
+    write_app(&apps_dir, "Editor.app", "com.vendor.Editor")?;
+    write_app(&apps_dir.join("Utilities"), "Tool.app", "org.tools.Tool")?;
+
+    let installed = InstalledApps::index(std::slice::from_ref(&apps_dir));
+    assert_eq!(installed.bundle_ids.len(), 2);
+
+    assert!(installed.owns("com.vendor.editor"));
+    assert!(installed.owns("com.vendor.Editor.ShareExtension"));
+    assert!(installed.owns("org.tools.tool"));
+    assert!(!installed.owns("com.vendor.editorpro"));
+    assert!(!installed.owns("com.removed.app"));
+
+    // Helpers installed next to the app share its vendor
+    assert!(installed.shares_vendor("com.vendor.helper"));
+    assert!(!installed.shares_vendor("com.vendorx.helper"));
+    assert!(!installed.shares_vendor("com.removed.app"));
+
+    let _ = fs::remove_dir_all(&apps_dir);
+    Ok(())
+}
+
+#[test]
+fn test_orphans_are_grouped_by_bundle_id() -> anyhow::Result<()> {
+    let root = std::env::temp_dir().join("bristo_orphans_scan");
+    let _ = fs::remove_dir_all(&root);
+
+    let apps_dir = root.join("Applications");
+    write_app(&apps_dir, "Editor.app", "com.vendor.Editor")?;
+
+    let support = root.join("Application Support");
+    let prefs = root.join("Preferences");
+    fs::create_dir_all(support.join("com.vendor.Editor"))?;
+    fs::create_dir_all(support.join("com.removed.App"))?;
+    fs::create_dir_all(support.join("Removed App"))?;
+    fs::create_dir_all(&prefs)?;
+    fs::write(support.join("com.removed.App/data.bin"), vec![0u8; 2000])?;
+    fs::write(prefs.join("com.removed.App.plist"), vec![0u8; 100])?;
+    fs::write(prefs.join("com.apple.finder.plist"), vec![0u8; 100])?;
+    fs::write(prefs.join("net.small.Tool.plist"), vec![0u8; 10])?;
+
+    let installed = InstalledApps::index(&[apps_dir]);
+    let locations = LocationsScan {
+        paths: vec![support.clone(), prefs.clone()],
+    };
+
+    let orphans = find_orphans(&locations, &installed);
+
+    let ids: Vec<&str> = orphans.iter().map(|g| g.bundle_id.as_str()).collect();
+    assert_eq!(ids, vec!["com.removed.app", "net.small.tool"]);
+
+    let removed = &orphans[0];
+    assert_eq!(removed.size, 2100);
+    assert!(removed.modified.is_some());
+
+    let mut paths = removed.paths.clone();
+    paths.sort();
+    assert_eq!(
+        paths,
+        vec![
+            support.join("com.removed.App"),
+            prefs.join("com.removed.App.plist"),
+        ]
+    );
+
+    let _ = fs::remove_dir_all(&root);
+    Ok(())
+}
+
+#[test]
+fn test_sibling_helpers_of_installed_apps_are_uncertain() -> anyhow::Result<()> {
+    let root = std::env::temp_dir().join("bristo_orphans_sibling");
+    let _ = fs::remove_dir_all(&root);
+
+    let apps_dir = root.join("Applications");
+    write_app(&apps_dir, "Docker.app", "com.docker.docker")?;
+
+    let daemons = root.join("LaunchDaemons");
+    fs::create_dir_all(&daemons)?;
+    fs::write(daemons.join("com.docker.vmnetd.plist"), vec![0u8; 10])?;
+    fs::write(daemons.join("com.removed.helper.plist"), vec![0u8; 5])?;
+
+    let installed = InstalledApps::index(&[apps_dir]);
+    let locations = LocationsScan {
+        paths: vec![daemons.clone()],
+    };
+
+    let orphans = find_orphans(&locations, &installed);
+    let groups: Vec<(&str, bool)> = orphans
+        .iter()
+        .map(|g| (g.bundle_id.as_str(), g.uncertain))
+        .collect();
+
+    // The helper of the installed app comes last and is flagged
+    assert_eq!(
+        groups,
+        vec![("com.removed.helper", false), ("com.docker.vmnetd", true)]
+    );
+
+    let _ = fs::remove_dir_all(&root);
+    Ok(())
+}
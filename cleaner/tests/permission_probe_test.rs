@@ -0,0 +1,89 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use cleaner::{Access, Capability, PermissionProbe, PermissionReport, ProbeResult};
+
+fn setup_home(name: &str) -> PathBuf {
+    let home = std::env::temp_dir().join(format!("bristo_probe_{}", name));
+    let _ = fs::remove_dir_all(&home);
+    fs::create_dir_all(home.join("Library/Containers/com.other.app/Data")).unwrap();
+    fs::create_dir_all(home.join("Library/Mail/V10")).unwrap();
+    home
+}
+
+fn access_of(report: &PermissionReport, capability: Capability) -> Access {
+    report
+        .results
+        .iter()
+        .find(|r| r.capability == capability)
+        .map(|r| r.access)
+        .unwrap()
+}
+
+#[test]
+fn test_probe_reports_readable_and_missing_locations() {
+    let home = setup_home("readable");
+
+    let probe = PermissionProbe::with_home(&home);
+    let container = probe
+        .checks
+        .iter()
+        .find(|c| c.capability == Capability::AppContainers)
+        .and_then(|c| c.probe.clone());
+    assert_eq!(
+        container,
+        Some(home.join("Library/Containers/com.other.app"))
+    );
+
+    let report = probe.run();
+    assert_eq!(
+        access_of(&report, Capability::AppContainers),
+        Access::Granted
+    );
+    assert_eq!(access_of(&report, Capability::Mail), Access::Granted);
+    assert_eq!(access_of(&report, Capability::Safari), Access::NotPresent);
+    assert!(report.available().contains(&Capability::Mail));
+
+    let _ = fs::remove_dir_all(&home);
+}
+
+#[test]
+fn test_io_errors_map_to_access() {
+    assert_eq!(
+        Access::from_io_error(&io::Error::from(io::ErrorKind::PermissionDenied)),
+        Access::Denied
+    );
+    assert_eq!(
+        Access::from_io_error(&io::Error::from(io::ErrorKind::NotFound)),
+        Access::NotPresent
+    );
+}
+
+#[test]
+fn test_denied_scope_blocks_paths() {
+    let report = PermissionReport {
+        results: vec![
+            ProbeResult {
+                capability: Capability::AppContainers,
+                access: Access::Denied,
+                scope: vec![
+                    PathBuf::from("/Users/me/Library/Containers"),
+                    PathBuf::from("/Users/me/Library/Group Containers"),
+                ],
+            },
+            ProbeResult {
+                capability: Capability::Mail,
+                access: Access::Granted,
+                scope: vec![PathBuf::from("/Users/me/Library/Mail")],
+            },
+        ],
+    };
+
+    assert!(report.is_limited());
+    assert_eq!(report.denied(), vec![Capability::AppContainers]);
+    assert!(report.is_blocked(Path::new("/Users/me/Library/Containers/com.vendor.app")));
+    assert!(!report.is_blocked(Path::new("/Users/me/Library/Mail/V10")));
+    assert!(!report.is_blocked(Path::new("/Users/me/Library/Caches/com.vendor.app")));
+    assert!(!PermissionReport::default().is_limited());
+}
@@ -0,0 +1,185 @@
+use std::cell::RefCell;
+use std::path::PathBuf;
+
+use cleaner::{
+    AppData, Cleaner, CleanerError, ElevatedOutcome, Elevator, Result, TrashFailure,
+    elevation_candidates, parse_removal_output, removal_script,
+};
+
+/// Records what it was asked to remove and fails the paths it was told to
+struct FakeElevator {
+    fail: Vec<PathBuf>,
+    cancel: bool,
+    calls: RefCell<Vec<Vec<PathBuf>>>,
+}
+
+impl FakeElevator {
+    fn new(fail: &[&str]) -> Self {
+        Self {
+            fail: fail.iter().map(PathBuf::from).collect(),
+            cancel: false,
+            calls: RefCell::new(Vec::new()),
+        }
+    }
+}
+
+impl Elevator for FakeElevator {
+    fn remove(&self, paths: &[PathBuf]) -> Result<Vec<(PathBuf, ElevatedOutcome)>> {
+        self.calls.borrow_mut().push(paths.to_vec());
+
+        if self.cancel {
+            return Err(CleanerError::Elevation {
+                reason: "Authorization cancelled".to_string(),
+            });
+        }
+
+        Ok(paths
+            .iter()
+            .map(|p| {
+                let outcome = if self.fail.contains(p) {
+                    ElevatedOutcome::Failed("rm exited with 1".to_string())
+                } else {
+                    ElevatedOutcome::Removed
+                };
+                (p.clone(), outcome)
+            })
+            .collect())
+    }
+}
+
+fn sample_failures() -> Vec<(PathBuf, TrashFailure)> {
+    vec![
+        (
+            PathBuf::from("/Library/LaunchDaemons/com.vendor.app.helper.plist"),
+            TrashFailure::PermissionDenied { root_owned: true },
+        ),
+        (
+            PathBuf::from("/Library/PrivilegedHelperTools/com.vendor.app.helper"),
+            TrashFailure::PermissionDenied { root_owned: true },
+        ),
+        (
+            PathBuf::from("/Users/me/Library/Containers/com.vendor.app"),
+            TrashFailure::PermissionDenied { root_owned: false },
+        ),
+        (
+            PathBuf::from("/Users/me/Library/Caches/com.vendor.app"),
+            TrashFailure::InUse,
+        ),
+    ]
+}
+
+#[test]
+fn test_only_root_owned_items_are_elevated() {
+    let candidates = elevation_candidates(&sample_failures());
+
+    assert_eq!(
+        candidates,
+        vec![
+            PathBuf::from("/Library/LaunchDaemons/com.vendor.app.helper.plist"),
+            PathBuf::from("/Library/PrivilegedHelperTools/com.vendor.app.helper"),
+        ]
+    );
+}
+
+#[test]
+fn test_elevated_results_are_merged_back() {
+    let elevator = FakeElevator::new(&["/Library/PrivilegedHelperTools/com.vendor.app.helper"]);
+
+    let remaining = Cleaner::remove_with_admin(sample_failures(), &elevator).unwrap();
+
+    // Authorization is asked once for all root-owned items
+    assert_eq!(elevator.calls.borrow().len(), 1);
+    assert_eq!(elevator.calls.borrow()[0].len(), 2);
+
+    assert_eq!(remaining.len(), 3);
+    assert!(remaining.contains(&(
+        PathBuf::from("/Library/PrivilegedHelperTools/com.vendor.app.helper"),
+        TrashFailure::AdminRemovalFailed {
+            reason: "rm exited with 1".to_string()
+        }
+    )));
+    assert!(
+        !remaining
+            .iter()
+            .any(|(p, _)| p.ends_with("com.vendor.app.helper.plist"))
+    );
+
+    // The list of the app only keeps what is still there
+    let mut app_data = AppData {
+        associate_files: sample_failures()
+            .into_iter()
+            .map(|(p, _)| {
+                let label = p.file_name().unwrap().to_string_lossy().to_string();
+                (p, label)
+            })
+            .collect(),
+        ..Default::default()
+    };
+    app_data.retain_failed(&remaining);
+    assert_eq!(app_data.associate_files.len(), 3);
+    assert_eq!(app_data.associate_files[0].1, "com.vendor.app.helper");
+}
+
+#[test]
+fn test_cancelled_authorization_keeps_everything() {
+    let mut elevator = FakeElevator::new(&[]);
+    elevator.cancel = true;
+
+    let err = Cleaner::remove_with_admin(sample_failures(), &elevator).unwrap_err();
+    assert!(matches!(err, CleanerError::Elevation { .. }));
+
+    // Nothing to elevate, the elevator is not even asked
+    let elevator = FakeElevator::new(&[]);
+    let failed = vec![(PathBuf::from("/tmp/in-use"), TrashFailure::InUse)];
+    let remaining = Cleaner::remove_with_admin(failed.clone(), &elevator).unwrap();
+    assert_eq!(remaining, failed);
+    assert!(elevator.calls.borrow().is_empty());
+}
+
+#[test]
+fn test_removal_script_quotes_and_parses() {
+    let paths = vec![
+        PathBuf::from("/Library/LaunchDaemons/com.vendor.app.plist"),
+        PathBuf::from("/usr/local/bin/it's here"),
+    ];
+
+    let script = removal_script(&paths);
+    assert_eq!(
+        script,
+        "/bin/rm -rf -- '/Library/LaunchDaemons/com.vendor.app.plist'; echo $?; \
+         /bin/rm -rf -- '/usr/local/bin/it'\\''s here'; echo $?"
+    );
+
+    let outcomes = parse_removal_output(&paths, "0\n1\n").unwrap();
+    assert_eq!(outcomes[0].1, ElevatedOutcome::Removed);
+    assert_eq!(
+        outcomes[1].1,
+        ElevatedOutcome::Failed("rm exited with 1".to_string())
+    );
+}
+
+#[test]
+fn test_removal_output_with_carriage_returns() {
+    let paths = vec![
+        PathBuf::from("/Library/LaunchDaemons/a.plist"),
+        PathBuf::from("/Library/LaunchDaemons/b.plist"),
+        PathBuf::from("/Library/PrivilegedHelperTools/c"),
+    ];
+
+    // What `do shell script` returns when it alters the line endings
+    let outcomes = parse_removal_output(&paths, "0\r0\r1").unwrap();
+    assert_eq!(outcomes.len(), 3);
+    assert_eq!(outcomes[0].1, ElevatedOutcome::Removed);
+    assert_eq!(outcomes[1].1, ElevatedOutcome::Removed);
+    assert_eq!(
+        outcomes[2].1,
+        ElevatedOutcome::Failed("rm exited with 1".to_string())
+    );
+
+    assert!(parse_removal_output(&paths, "0\r\n0\r\n1\r\n").is_ok());
+
+    // Codes that do not add up are not matched to paths at all
+    let err = parse_removal_output(&paths, "0\r0").unwrap_err();
+    assert!(matches!(err, CleanerError::Elevation { .. }));
+    assert!(parse_removal_output(&paths, "0\n0\n0\n0").is_err());
+}
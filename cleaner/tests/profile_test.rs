@@ -0,0 +1,246 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use cleaner::{AppData, AppProfile, CleanerError, LocationsScan, ProfileDb};
+
+fn profile_reason(text: &str) -> String {
+    match AppProfile::parse(text, Path::new("test.toml")) {
+        Err(CleanerError::Profile { reason, .. }) => reason,
+        other => panic!("expected a profile error, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_builtin_profiles_are_valid() -> anyhow::Result<()> {
+    let db = ProfileDb::builtin()?;
+
+    for bundle_id in [
+        "com.google.Chrome",
+        "com.microsoft.VSCode",
+        "com.docker.docker",
+    ] {
+        let profile = db.find(bundle_id).expect(bundle_id);
+        assert_eq!(profile.bundle_id, bundle_id);
+        assert_eq!(profile.validate(), Ok(()));
+    }
+
+    // Bundle ids are found whatever their case
+    assert!(db.find("COM.GOOGLE.CHROME").is_some());
+    assert!(db.find("com.vendor.unknown").is_none());
+
+    Ok(())
+}
+
+#[test]
+fn test_validator_reports_every_problem() {
+    let reason = profile_reason(
+        r#"
+        bundle_id = "Not An Id"
+        paths = ["relative/path", "~/Library/../..", "/"]
+        globs = ["~/Library/[oops"]
+        launchd_labels = ["com.vendor agent"]
+        rules = ["starts com.vendor"]
+        never_delete = ["/System/Library"]
+        "#,
+    );
+
+    for problem in [
+        "'Not An Id' is not a bundle id",
+        "paths 'relative/path': must be absolute or start with ~/",
+        "paths '~/Library/../..': must not contain ..",
+        "paths '/': is a root folder",
+        "globs '~/Library/[oops'",
+        "launchd_labels 'com.vendor agent' is not a label",
+        "unknown rule 'starts'",
+        "never_delete '/System/Library': is part of macOS",
+    ] {
+        assert!(reason.contains(problem), "{problem:?} not in {reason:?}");
+    }
+
+    // A profile must find something
+    let reason = profile_reason(r#"bundle_id = "com.vendor.tool""#);
+    assert!(reason.contains("nothing to find"), "{reason}");
+}
+
+#[test]
+fn test_unknown_fields_and_bad_toml_are_rejected() {
+    let reason = profile_reason(
+        r#"
+        bundle_id = "com.vendor.tool"
+        path = ["~/.tool"]
+        "#,
+    );
+    assert!(reason.contains("unknown field `path`"), "{reason}");
+
+    let reason = profile_reason(r#"bundle_id = "com.vendor.tool"#);
+    assert!(!reason.is_empty());
+}
+
+#[test]
+fn test_resolve_paths_expands_home_globs_and_labels() -> anyhow::Result<()> {
+    let home = std::env::temp_dir().join("bristo_profile_home");
+    let _ = fs::remove_dir_all(&home);
+    for dir in [
+        ".tool",
+        "Library/Containers/com.vendor.tool",
+        "Library/Containers/com.vendor.tool.helper",
+        "Library/Containers/com.vendor.shared",
+        "Library/LaunchAgents",
+    ] {
+        fs::create_dir_all(home.join(dir))?;
+    }
+    fs::write(
+        home.join("Library/LaunchAgents/com.vendor.agent.plist"),
+        b"",
+    )?;
+
+    let profile = AppProfile::parse(
+        r#"
+        bundle_id = "com.vendor.tool"
+        paths = ["~/.tool", "~/.missing"]
+        globs = ["~/Library/Containers/com.vendor.*"]
+        launchd_labels = ["com.vendor.agent", "com.vendor.missing"]
+        never_delete = ["~/Library/Containers/com.vendor.shared"]
+        "#,
+        Path::new("test.toml"),
+    )?;
+
+    let mut found = profile.resolve_paths(&home);
+    found.sort();
+    let mut expected: Vec<PathBuf> = [
+        ".tool",
+        "Library/Containers/com.vendor.tool",
+        "Library/Containers/com.vendor.tool.helper",
+        "Library/LaunchAgents/com.vendor.agent.plist",
+    ]
+    .iter()
+    .map(|p| home.join(p))
+    .collect();
+    expected.sort();
+    assert_eq!(found, expected);
+
+    let _ = fs::remove_dir_all(&home);
+    Ok(())
+}
+
+#[test]
+fn test_load_dir_and_user_profiles_override_builtin() -> anyhow::Result<()> {
+    let dir = std::env::temp_dir().join("bristo_profile_dir");
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir)?;
+
+    fs::write(
+        dir.join("chrome.toml"),
+        "bundle_id = \"com.google.Chrome\"\npaths = [\"~/Chrome Profiles\"]\n",
+    )?;
+    fs::write(dir.join("notes.txt"), "not a profile")?;
+
+    let mut db = ProfileDb::builtin()?;
+    db.merge(ProfileDb::load_dir(&dir)?);
+    assert_eq!(
+        db.find("com.google.chrome").map(|p| p.paths.clone()),
+        Some(vec!["~/Chrome Profiles".to_string()])
+    );
+    assert!(db.find("com.docker.docker").is_some());
+
+    // Two profiles for the same app in one folder, the second is skipped
+    fs::write(
+        dir.join("chrome_again.toml"),
+        "bundle_id = \"com.google.chrome\"\npaths = [\"~/Other\"]\n",
+    )?;
+    let db = ProfileDb::load_dir(&dir)?;
+    assert_eq!(
+        db.find("com.google.chrome").map(|p| p.paths.clone()),
+        Some(vec!["~/Chrome Profiles".to_string()])
+    );
+    let (path, err) = &db.problems[0];
+    assert_eq!(path, &dir.join("chrome_again.toml"));
+    assert!(err.to_string().contains("already exists"), "{err}");
+
+    let _ = fs::remove_dir_all(&dir);
+    Ok(())
+}
+
+#[test]
+fn test_bad_user_profile_is_skipped_and_reported() -> anyhow::Result<()> {
+    let dir = std::env::temp_dir().join("bristo_profile_bad");
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir)?;
+
+    fs::write(
+        dir.join("good.toml"),
+        "bundle_id = \"com.vendor.good\"\npaths = [\"~/Good\"]\n",
+    )?;
+    fs::write(
+        dir.join("bad.toml"),
+        "bundle_id = \"com.vendor.bad\"\npaths = [\n",
+    )?;
+
+    let db = ProfileDb::load_dir(&dir)?;
+    assert!(db.find("com.vendor.good").is_some());
+    assert!(db.find("com.vendor.bad").is_none());
+    assert_eq!(db.problems.len(), 1);
+    assert_eq!(db.problems[0].0, dir.join("bad.toml"));
+
+    // The problems stay with the database they are merged into
+    let mut merged = ProfileDb::builtin()?;
+    merged.merge(db);
+    assert!(merged.find("com.vendor.good").is_some());
+    assert_eq!(merged.problems.len(), 1);
+
+    let _ = fs::remove_dir_all(&dir);
+    Ok(())
+}
+
+#[test]
+fn test_profile_is_merged_into_the_scan() -> anyhow::Result<()> {
+    let base = std::env::temp_dir().join("bristo_profile_scan");
+    let _ = fs::remove_dir_all(&base);
+    for name in ["Vendor/Tool", "Vendor/Shared", "tool-sync", "Other"] {
+        fs::create_dir_all(base.join(name))?;
+    }
+
+    let vendor = base.join("Vendor");
+    let profile = AppProfile::parse(
+        &format!(
+            "bundle_id = \"com.vendor.tool\"\n\
+             paths = [\"{}\"]\n\
+             rules = [\"equal tool-sync\"]\n\
+             never_delete = [\"{}\"]\n",
+            vendor.join("Tool").display(),
+            vendor.display(),
+        ),
+        Path::new("test.toml"),
+    )?;
+    let mut db = ProfileDb::default();
+    db.profiles
+        .insert(profile.bundle_id.to_lowercase(), profile.clone());
+
+    let mut app_data = AppData::from_identity("Vendor", "com.vendor.tool", "tool")?;
+    app_data.find_profile(&db);
+    assert_eq!(app_data.profile, Some(profile));
+
+    let locations = LocationsScan {
+        paths: vec![base.clone()],
+    };
+    app_data.find_associate_files(&locations, |_, _| {});
+
+    let mut found: Vec<PathBuf> = app_data
+        .associate_files
+        .iter()
+        .map(|(p, _)| p.clone())
+        .collect();
+    found.sort();
+    // "Vendor" matches the app name but holds the shared folder
+    assert_eq!(
+        found,
+        vec![base.join("Vendor/Tool"), base.join("tool-sync")]
+    );
+
+    app_data.reset();
+    assert!(app_data.profile.is_none());
+    assert!(app_data.extra_rules.is_empty());
+
+    let _ = fs::remove_dir_all(&base);
+    Ok(())
+}
@@ -0,0 +1,252 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use cleaner::{
+    AppData, CancelToken, Cleaner, Homebrew, LocationsScan, ProfileDb, Refusal, SafetyGuard,
+    ScanSetup, TrashFailure, TrashReport,
+};
+
+fn sample_guard() -> SafetyGuard {
+    let locations = LocationsScan {
+        paths: vec![
+            PathBuf::from("/Users/me/Library/Caches"),
+            PathBuf::from("/Users/me/Library/Application Support"),
+            PathBuf::from("/Library/Application Support"),
+        ],
+    };
+    SafetyGuard::new(&locations, Path::new("/Users/me"))
+}
+
+#[test]
+fn test_guard_refuses_shared_folders() {
+    let guard = sample_guard();
+    let check = |path: &str| guard.check(Path::new(path));
+
+    assert_eq!(check("/Users/me/Library/Caches"), Some(Refusal::ScanRoot));
+    assert_eq!(check("/Users/me/Library/Caches/"), Some(Refusal::ScanRoot));
+    assert_eq!(
+        check("/Users/me/Library/Caches/../Caches"),
+        Some(Refusal::ScanRoot)
+    );
+    assert_eq!(
+        check("/Library/Application Support"),
+        Some(Refusal::ScanRoot)
+    );
+    assert_eq!(check("/Users/me/Library"), Some(Refusal::HoldsScanRoot));
+    assert_eq!(check("/Library"), Some(Refusal::HoldsScanRoot));
+    assert_eq!(check("/Users/me"), Some(Refusal::Home));
+    assert_eq!(check("/Users"), Some(Refusal::Home));
+    assert_eq!(check("/"), Some(Refusal::Home));
+    assert_eq!(check(""), Some(Refusal::Home));
+    assert_eq!(check("/System/Library/Caches"), Some(Refusal::System));
+
+    // What is inside the shared folders can go
+    assert_eq!(check("/Users/me/Library/Caches/com.vendor.tool"), None);
+    assert_eq!(check("/Library/Application Support/Tool"), None);
+    assert_eq!(check("/Users/me/.tool"), None);
+}
+
+#[test]
+fn test_allowlisted_paths_are_never_touched() {
+    let guard = sample_guard().with_allowlist([PathBuf::from(
+        "/Users/me/Library/Application Support/Vendor/Shared",
+    )]);
+    let check = |path: &str| guard.check(Path::new(path));
+
+    let support = "/Users/me/Library/Application Support";
+    assert_eq!(
+        check(&format!("{support}/Vendor/Shared")),
+        Some(Refusal::Allowlisted)
+    );
+    // Inside it and the folders holding it
+    assert_eq!(
+        check(&format!("{support}/Vendor/Shared/settings.json")),
+        Some(Refusal::Allowlisted)
+    );
+    assert_eq!(
+        check(&format!("{support}/Vendor")),
+        Some(Refusal::Allowlisted)
+    );
+    // Its siblings can go
+    assert_eq!(check(&format!("{support}/Vendor/Tool")), None);
+}
+
+#[test]
+fn test_profile_shared_folders_keep_only_themselves_and_their_parents() -> anyhow::Result<()> {
+    let home = std::env::temp_dir().join("bristo_guard_chrome");
+    let _ = fs::remove_dir_all(&home);
+    let support = home.join("Library/Application Support");
+    for dir in [
+        support.join("Google/Chrome/Default"),
+        support.join("Google/Drive"),
+        home.join("Library/Caches/Google/Chrome"),
+        home.join("Library/Google/GoogleSoftwareUpdate"),
+    ] {
+        fs::create_dir_all(dir)?;
+    }
+
+    let db = ProfileDb::builtin()?;
+    let profile = db.find("com.google.Chrome").expect("chrome profile");
+    let locations = LocationsScan {
+        paths: vec![support.clone(), home.join("Library/Caches")],
+    };
+    let guard = SafetyGuard::new(&locations, &home).with_shared(profile.never_delete_paths(&home));
+
+    // The paths of the profile are all allowed
+    let listed = profile.resolve_paths(&home);
+    assert_eq!(
+        listed,
+        vec![
+            support.join("Google/Chrome"),
+            home.join("Library/Caches/Google/Chrome"),
+        ]
+    );
+    let (allowed, refused) = guard.partition(&listed);
+    assert_eq!(allowed, listed);
+    assert!(refused.is_empty());
+    assert_eq!(guard.check(&support.join("Google/Chrome/Default")), None);
+
+    // The vendor folder and the updater serve the other Google apps
+    assert_eq!(guard.check(&support.join("Google")), Some(Refusal::Shared));
+    assert_eq!(
+        guard.check(&home.join("Library/Google/GoogleSoftwareUpdate")),
+        Some(Refusal::Shared)
+    );
+    assert_eq!(
+        guard.check(&home.join("Library/Google")),
+        Some(Refusal::Shared)
+    );
+
+    // The user allowlist still keeps what is inside its paths
+    let guard = guard.with_allowlist([support.join("Google/Drive")]);
+    assert_eq!(
+        guard.check(&support.join("Google/Drive/settings.json")),
+        Some(Refusal::Allowlisted)
+    );
+
+    let _ = fs::remove_dir_all(&home);
+    Ok(())
+}
+
+#[test]
+fn test_links_to_a_scan_root_are_refused() -> anyhow::Result<()> {
+    let base = std::env::temp_dir().join("bristo_guard_links");
+    let _ = fs::remove_dir_all(&base);
+    let root = base.join("Caches");
+    fs::create_dir_all(root.join("com.vendor.tool"))?;
+    std::os::unix::fs::symlink(&root, base.join("Caches Link"))?;
+
+    let locations = LocationsScan {
+        paths: vec![root.clone()],
+    };
+    let guard = SafetyGuard::new(&locations, Path::new("/Users/me"));
+
+    assert_eq!(
+        guard.check(&base.join("Caches Link")),
+        Some(Refusal::ScanRoot)
+    );
+    assert_eq!(guard.check(&root.join("com.vendor.tool")), None);
+
+    let _ = fs::remove_dir_all(&base);
+    Ok(())
+}
+
+#[test]
+fn test_refused_items_are_reported_apart_from_failures() -> anyhow::Result<()> {
+    let guard = sample_guard();
+    let paths = vec![
+        PathBuf::from("/Users/me/Library"),
+        PathBuf::from("/System/Library"),
+    ];
+
+    // Nothing is tried when everything is refused
+    let report = Cleaner::trash_guarded(&paths, &guard, None, &CancelToken::new())?;
+    assert!(report.failed.is_empty());
+    assert_eq!(
+        report.refused,
+        vec![
+            (paths[0].clone(), Refusal::HoldsScanRoot),
+            (paths[1].clone(), Refusal::System),
+        ]
+    );
+    assert!(!report.is_clean());
+
+    let (allowed, refused) = guard.partition(&[
+        PathBuf::from("/Users/me/Library/Caches/com.vendor.tool"),
+        PathBuf::from("/Users/me/Library/Caches"),
+    ]);
+    assert_eq!(
+        allowed,
+        vec![PathBuf::from("/Users/me/Library/Caches/com.vendor.tool")]
+    );
+    assert_eq!(refused.len(), 1);
+
+    Ok(())
+}
+
+#[test]
+fn test_scan_roots_never_match_and_unremoved_items_stay() -> anyhow::Result<()> {
+    let base = std::env::temp_dir().join("bristo_guard_scan").join("Tool");
+    let _ = fs::remove_dir_all(&base);
+    fs::create_dir_all(base.join("Tool"))?;
+    fs::create_dir_all(base.join("tool.cache"))?;
+
+    // The base folder is named like the app but is a scan root
+    let mut app_data = AppData::from_identity("Tool", "com.vendor.tool", "tool")?;
+    let locations = LocationsScan {
+        paths: vec![base.clone()],
+    };
+    app_data.find_associate_files(&locations, |_, _| {});
+
+    let found: Vec<PathBuf> = app_data
+        .associate_files
+        .iter()
+        .map(|(p, _)| p.clone())
+        .collect();
+    assert_eq!(found, vec![base.join("Tool")]);
+
+    let report = TrashReport {
+        failed: vec![(base.join("Tool"), TrashFailure::InUse)],
+        ..Default::default()
+    };
+    app_data.retain_unremoved(&report);
+    assert_eq!(app_data.associate_files.len(), 1);
+
+    app_data.retain_unremoved(&TrashReport::default());
+    assert!(app_data.associate_files.is_empty());
+
+    let _ = fs::remove_dir_all(base.parent().unwrap());
+    Ok(())
+}
+
+#[test]
+fn test_homebrew_prefix_roots_of_the_scan_are_refused() -> anyhow::Result<()> {
+    let base = std::env::temp_dir().join("bristo_guard_brew");
+    let _ = fs::remove_dir_all(&base);
+    let prefix = base.join("homebrew");
+    fs::create_dir_all(prefix.join("Caskroom/tool/1.0"))?;
+    fs::create_dir_all(prefix.join("bin"))?;
+    fs::create_dir_all(prefix.join("opt/tool"))?;
+    fs::create_dir_all(base.join("Library"))?;
+
+    let mut setup = ScanSetup::new(LocationsScan {
+        paths: vec![base.join("Library")],
+    });
+    setup.homebrew = Some(Homebrew::with_prefix(&prefix).with_appdir(base.join("Applications")));
+
+    // The bundle is gone, the cask is found by its token
+    let mut cleaner = Cleaner {
+        app_data: AppData::from_identity("Tool", "com.vendor.tool", "tool")?,
+        ..Default::default()
+    };
+    cleaner.scan_app_data_with(&setup, None, &CancelToken::new())?;
+
+    // Walked for the cask, never trashed themselves
+    let guard = cleaner.safety_guard()?;
+    assert_eq!(guard.check(&prefix.join("bin")), Some(Refusal::ScanRoot));
+    assert_eq!(guard.check(&prefix.join("opt")), Some(Refusal::ScanRoot));
+    assert_eq!(guard.check(&prefix.join("opt/tool")), None);
+
+    let _ = fs::remove_dir_all(&base);
+    Ok(())
+}
@@ -0,0 +1,86 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+use cleaner::{AppData, CancelToken, LocationsScan, ScanEstimate};
+
+fn sample_roots(name: &str) -> anyhow::Result<(PathBuf, LocationsScan)> {
+    let base = std::env::temp_dir().join(name);
+    let _ = fs::remove_dir_all(&base);
+    fs::create_dir_all(base.join("Caches/com.vendor.tool/data"))?;
+    fs::create_dir_all(base.join("Preferences"))?;
+    fs::write(base.join("Caches/com.vendor.tool/data/cache.db"), b"")?;
+    fs::write(base.join("Preferences/com.vendor.tool.plist"), b"")?;
+    fs::write(base.join("Preferences/com.other.app.plist"), b"")?;
+
+    let locations = LocationsScan {
+        paths: vec![
+            base.join("Caches"),
+            base.join("Preferences"),
+            base.join("Missing"),
+        ],
+    };
+    Ok((base, locations))
+}
+
+#[test]
+fn test_unknown_roots_are_guessed_from_their_top_entries() -> anyhow::Result<()> {
+    let (base, locations) = sample_roots("bristo_estimate_count")?;
+
+    // Only the first level is counted, it is not remembered
+    let estimate = ScanEstimate::default();
+    assert_eq!(estimate.total(&locations, &CancelToken::new()), Some(3));
+    assert_eq!(estimate.count(&base.join("Caches")), None);
+
+    // The scan walks deeper, what it walked is the next total
+    let mut app_data = AppData::from_identity("Tool", "com.vendor.tool", "tool")?;
+    app_data.find_associate_files(&locations, |_, _| {});
+    assert_eq!(app_data.scan_counts.values().sum::<usize>(), 5);
+    assert_eq!(
+        app_data.scan_counts.get(&base.join("Preferences")),
+        Some(&2)
+    );
+
+    let mut estimate = estimate;
+    estimate.record(&app_data.scan_counts);
+    assert_eq!(estimate.total(&locations, &CancelToken::new()), Some(5));
+    assert_eq!(estimate.count(&base.join("Caches")), Some(3));
+
+    let _ = fs::remove_dir_all(&base);
+    Ok(())
+}
+
+#[test]
+fn test_earlier_scans_are_used_and_saved() -> anyhow::Result<()> {
+    let (base, locations) = sample_roots("bristo_estimate_saved")?;
+
+    // A known root is not counted again, what the last scan walked is used
+    let mut estimate = ScanEstimate::default();
+    estimate.record(&HashMap::from([(base.join("Caches"), 40)]));
+    assert_eq!(estimate.total(&locations, &CancelToken::new()), Some(42));
+
+    let cache = base.join("cache/scan_counts.json");
+    estimate.save_to(&cache)?;
+    assert_eq!(ScanEstimate::load_from(&cache), estimate);
+
+    // A broken cache is only counted again
+    fs::write(&cache, "not json")?;
+    assert_eq!(ScanEstimate::load_from(&cache), ScanEstimate::default());
+
+    let _ = fs::remove_dir_all(&base);
+    Ok(())
+}
+
+#[test]
+fn test_cancelled_count_gives_no_total() -> anyhow::Result<()> {
+    let (base, locations) = sample_roots("bristo_estimate_cancel")?;
+    let cancel = CancelToken::new();
+    cancel.cancel();
+
+    let estimate = ScanEstimate::default();
+    assert_eq!(estimate.total(&locations, &cancel), None);
+    assert_eq!(estimate.count(&base.join("Caches")), None);
+
+    let _ = fs::remove_dir_all(&base);
+    Ok(())
+}
@@ -86,8 +86,79 @@ fn test_kill_processes_safe() -> anyhow::Result<()> {
         bundle_id: "com.example.test".to_string(),
         bundle_name: "NonExistent".to_string(),
         organization: "example".to_string(),
+        ..Default::default()
     };
     let processes = AppProcess::find_app_processes(&app_info);
     AppProcess::kill_app_processes(&app_info.name, &processes)?; // Safe: no processes exist
     Ok(())
 }
+
+#[test]
+fn test_appinfo_metadata_from_plist() -> anyhow::Result<()> {
+    let app_path = std::env::temp_dir().join("MetadataTest.app");
+    fs::create_dir_all(app_path.join("Contents"))?;
+
+    let plist_content = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>CFBundleIdentifier</key>
+    <string>com.example.metadata</string>
+    <key>CFBundleExecutable</key>
+    <string>Metadata</string>
+    <key>CFBundleName</key>
+    <string>Meta</string>
+    <key>CFBundleShortVersionString</key>
+    <string>2.4.1</string>
+    <key>CFBundleVersion</key>
+    <string>241</string>
+    <key>LSMinimumSystemVersion</key>
+    <string>12.0</string>
+    <key>CFBundleIconFile</key>
+    <string>AppIcon</string>
+</dict>
+</plist>
+"#;
+    fs::write(app_path.join("Contents/Info.plist"), plist_content)?;
+
+    let app_info = cleaner::AppInfo::from_path(&app_path)?;
+    assert_eq!(app_info.short_name.as_deref(), Some("Meta"));
+    assert_eq!(app_info.version.as_deref(), Some("2.4.1"));
+    assert_eq!(app_info.build_version.as_deref(), Some("241"));
+    assert_eq!(app_info.min_system_version.as_deref(), Some("12.0"));
+    assert_eq!(app_info.icon_file.as_deref(), Some("AppIcon"));
+    // Not signed, so no signature details
+    assert!(app_info.team_id.is_none());
+    assert!(!app_info.sandboxed);
+
+    let _ = fs::remove_dir_all(&app_path);
+
+    Ok(())
+}
+
+#[test]
+fn test_group_containers_match_by_bundle_id_and_group() {
+    let app_info = cleaner::AppInfo {
+        name: "Office".to_string(),
+        bundle_id: "com.example.office".to_string(),
+        bundle_name: "Office".to_string(),
+        team_id: Some("ABCDE12345".to_string()),
+        app_groups: vec!["group.com.example.shared".to_string()],
+        ..Default::default()
+    };
+
+    let containers = PathBuf::from("/Users/me/Library/Group Containers");
+    assert!(app_info.associate_path_matches(&containers.join("ABCDE12345.com.example.office")));
+    assert!(app_info.associate_path_matches(&containers.join("group.com.example.shared")));
+    assert!(!app_info.associate_path_matches(&containers.join("ZZZZZ99999.Suite")));
+
+    // Other containers of the team are shared with its other apps
+    assert!(!app_info.associate_path_matches(&containers.join("ABCDE12345.Suite")));
+    assert!(!app_info.associate_path_matches(&containers.join("ABCDE12345.com.example.mail")));
+
+    // Team prefix only counts inside Group Containers
+    assert!(
+        !app_info
+            .associate_path_matches(&PathBuf::from("/Users/me/Library/Caches/ABCDE12345.Other"))
+    );
+}
@@ -0,0 +1,96 @@
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use cleaner::{AppData, CancelToken, Cleaner, CleanerError, LocationsScan, ScanSetup};
+use status::StatusEmitter;
+use status::recording::Recording;
+use status::status_event::{Phase, Step};
+
+// Scan roots with a receipt and two files of the tool, nothing of the real home
+fn sample_setup(name: &str) -> anyhow::Result<(PathBuf, ScanSetup)> {
+    let base = std::env::temp_dir().join(name);
+    let _ = fs::remove_dir_all(&base);
+    fs::create_dir_all(base.join("Caches/com.bristo.phasetool"))?;
+    fs::create_dir_all(base.join("Preferences"))?;
+    fs::create_dir_all(base.join("receipts"))?;
+    fs::write(base.join("Preferences/com.bristo.phasetool.plist"), b"")?;
+    fs::write(base.join("Preferences/com.other.app.plist"), b"")?;
+    fs::write(base.join("receipts/com.bristo.phasetool.bom"), b"")?;
+
+    let mut setup = ScanSetup::new(LocationsScan {
+        paths: vec![
+            base.join("Caches"),
+            base.join("Preferences"),
+            base.join("receipts"),
+        ],
+    });
+    setup.estimate_cache = Some(base.join("cache/scan_counts.json"));
+    Ok((base, setup))
+}
+
+// Without the processes lookup of Cleaner::from_identity
+fn sample_cleaner() -> anyhow::Result<Cleaner> {
+    Ok(Cleaner {
+        app_data: AppData::from_identity("Phase Tool", "com.bristo.phasetool", "")?,
+        ..Default::default()
+    })
+}
+
+#[test]
+fn test_scan_reports_its_phases_in_order() -> anyhow::Result<()> {
+    let (base, setup) = sample_setup("bristo_phase_order")?;
+    let recorder = Arc::new(Recording::new());
+    let status = StatusEmitter::new(recorder.clone());
+
+    let mut cleaner = sample_cleaner()?;
+    cleaner.scan_app_data_with(&setup, Some(&status), &CancelToken::new())?;
+
+    assert_eq!(
+        recorder.steps(),
+        vec![Step::ScanningBom, Step::ScanningFiles]
+    );
+
+    let phases: Vec<Phase> = recorder
+        .events()
+        .into_iter()
+        .filter_map(|e| e.phase)
+        .collect();
+    assert!(phases.contains(&Phase::ScanningBom { found: Some(1) }));
+    assert_eq!(
+        phases.last(),
+        Some(&Phase::ScanningFiles { found: Some(3) })
+    );
+
+    // The caller ends the operation, the scan never does
+    let events = recorder.events();
+    assert!(events.iter().all(|e| !e.is_terminal()));
+    assert!(events.iter().all(|e| e.operation == status.operation()));
+
+    // What was walked is kept where the setup says
+    assert!(base.join("cache/scan_counts.json").is_file());
+
+    let _ = fs::remove_dir_all(&base);
+    Ok(())
+}
+
+#[test]
+fn test_cancelled_scan_stops_reporting() -> anyhow::Result<()> {
+    let (base, setup) = sample_setup("bristo_phase_cancel")?;
+    let recorder = Arc::new(Recording::new());
+    let status = StatusEmitter::new(recorder.clone());
+    let cancel = CancelToken::new();
+    cancel.cancel();
+
+    let mut cleaner = sample_cleaner()?;
+    let result = cleaner
+        .scan_app_data_with(&setup, Some(&status), &cancel)
+        .map(|_| ());
+
+    assert_eq!(result, Err(CleanerError::Cancelled));
+    assert_eq!(recorder.steps(), vec![Step::ScanningBom]);
+    assert!(!base.join("cache").exists());
+
+    let _ = fs::remove_dir_all(&base);
+    Ok(())
+}
@@ -0,0 +1,87 @@
+use std::path::Path;
+
+use cleaner::{TrashFailure, TrashRemedy, is_sip_protected};
+
+#[test]
+fn test_cocoa_errors_are_classified() {
+    let path = Path::new("/tmp/bristo-trash-does-not-exist");
+
+    assert_eq!(
+        TrashFailure::from_ns_error("NSCocoaErrorDomain", 4, path),
+        TrashFailure::NotFound
+    );
+    assert_eq!(
+        TrashFailure::from_ns_error("NSCocoaErrorDomain", 642, path),
+        TrashFailure::ReadOnlyVolume
+    );
+    assert_eq!(
+        TrashFailure::from_ns_error("NSCocoaErrorDomain", 255, path),
+        TrashFailure::InUse
+    );
+    assert_eq!(
+        TrashFailure::from_ns_error("NSCocoaErrorDomain", 513, path),
+        TrashFailure::PermissionDenied { root_owned: false }
+    );
+    assert_eq!(
+        TrashFailure::from_ns_error("NSCocoaErrorDomain", 9999, path),
+        TrashFailure::Other {
+            domain: "NSCocoaErrorDomain".to_string(),
+            code: 9999
+        }
+    );
+}
+
+#[test]
+fn test_posix_errors_are_classified() {
+    let path = Path::new("/tmp/bristo-trash-does-not-exist");
+
+    assert_eq!(
+        TrashFailure::from_ns_error("NSPOSIXErrorDomain", 2, path),
+        TrashFailure::NotFound
+    );
+    assert_eq!(
+        TrashFailure::from_ns_error("NSPOSIXErrorDomain", 16, path),
+        TrashFailure::InUse
+    );
+    assert_eq!(
+        TrashFailure::from_ns_error("NSPOSIXErrorDomain", 30, path),
+        TrashFailure::ReadOnlyVolume
+    );
+}
+
+#[test]
+fn test_permission_denied_by_owner_and_sip() {
+    // Owned by root on every unix
+    assert_eq!(
+        TrashFailure::from_ns_error("NSPOSIXErrorDomain", 13, Path::new("/")),
+        TrashFailure::PermissionDenied { root_owned: true }
+    );
+    assert_eq!(
+        TrashFailure::from_ns_error("NSPOSIXErrorDomain", 1, Path::new("/usr/bin/true")),
+        TrashFailure::SipProtected
+    );
+
+    assert!(is_sip_protected(Path::new("/System/Library/Foo")));
+    assert!(!is_sip_protected(Path::new("/usr/local/bin/foo")));
+    assert!(!is_sip_protected(Path::new(
+        "/Library/LaunchDaemons/foo.plist"
+    )));
+}
+
+#[test]
+fn test_each_failure_has_a_remedy() {
+    assert_eq!(
+        TrashFailure::PermissionDenied { root_owned: true }.remedy(),
+        TrashRemedy::RetryWithAdmin
+    );
+    assert_eq!(
+        TrashFailure::PermissionDenied { root_owned: false }.remedy(),
+        TrashRemedy::OpenFullDiskAccess
+    );
+    assert_eq!(TrashFailure::NotFound.remedy(), TrashRemedy::None);
+    assert_eq!(TrashFailure::InUse.remedy(), TrashRemedy::RevealInFinder);
+    assert_eq!(
+        TrashFailure::SipProtected.remedy(),
+        TrashRemedy::RevealInFinder
+    );
+}
@@ -0,0 +1,96 @@
+use std::path::Path;
+
+use cleaner::{AppInfo, Vendor};
+
+#[test]
+fn test_vendor_from_bundle_id() {
+    let vendor = Vendor::from_bundle_id("com.company.app");
+    assert_eq!(vendor.prefix, "com.company");
+    assert_eq!(vendor.name, "company");
+
+    let vendor = Vendor::from_bundle_id("com.company.division.app");
+    assert_eq!(vendor.prefix, "com.company.division");
+    assert_eq!(vendor.name, "company");
+
+    let vendor = Vendor::from_bundle_id("io.github.user.app");
+    assert_eq!(vendor.prefix, "io.github.user");
+    assert_eq!(vendor.name, "user");
+
+    // Nothing usable
+    assert_eq!(Vendor::from_bundle_id("app"), Vendor::default());
+    assert_eq!(Vendor::from_bundle_id("com.app"), Vendor::default());
+    assert_eq!(
+        Vendor::from_bundle_id("com.github.Electron"),
+        Vendor::default()
+    );
+}
+
+#[test]
+fn test_vendor_prefix_matching() {
+    let app_info = AppInfo {
+        name: "Editor".to_string(),
+        bundle_id: "io.github.user.editor".to_string(),
+        bundle_name: "Editor".to_string(),
+        organization: "user".to_string(),
+        vendor_prefix: "io.github.user".to_string(),
+        ..Default::default()
+    };
+
+    let library = Path::new("/Users/me/Library/Application Support");
+    assert!(app_info.associate_path_matches(&library.join("io.github.user.editor")));
+    assert!(app_info.associate_path_matches(&library.join("io.github.user.editor-updater")));
+
+    // Same vendor but another app
+    assert!(!app_info.associate_path_matches(&library.join("io.github.user.viewer")));
+    // Bare vendor word is not enough anymore
+    assert!(!app_info.associate_path_matches(&library.join("user")));
+    assert!(!app_info.associate_path_matches(&library.join("github")));
+}
+
+#[test]
+fn test_vendor_from_signature() {
+    let vendor = Vendor::from_signature("com.company.app", Some("EQHXZ8M8AV"));
+    assert_eq!(vendor.prefix, "com.company");
+    assert_eq!(vendor.name, "company");
+    assert_eq!(vendor.team_id.as_deref(), Some("EQHXZ8M8AV"));
+
+    // The bundle id says nothing, the signature still tells who made it
+    let vendor = Vendor::from_signature("com.github.Electron", Some("EQHXZ8M8AV"));
+    assert_eq!(vendor.prefix, "");
+    assert_eq!(vendor.name, "EQHXZ8M8AV");
+    assert_eq!(vendor.strip_team("EQHXZ8M8AV.shared"), Some("shared"));
+    assert_eq!(vendor.strip_team("EQHXZ8M8AVX.shared"), None);
+
+    assert_eq!(
+        Vendor::from_signature("com.app", Some(" ")),
+        Vendor::default()
+    );
+}
+
+#[test]
+fn test_team_id_matching() {
+    let app_info = AppInfo {
+        name: "Chat".to_string(),
+        bundle_id: "com.github.Electron".to_string(),
+        bundle_name: "Chat".to_string(),
+        team_id: Some("EQHXZ8M8AV".to_string()),
+        ..Default::default()
+    };
+
+    let library = Path::new("/Users/me/Library");
+    assert!(
+        app_info.associate_path_matches(
+            &library.join("Group Containers/EQHXZ8M8AV.com.github.Electron")
+        )
+    );
+    assert!(
+        app_info
+            .associate_path_matches(&library.join("Application Support/EQHXZ8M8AV.chat-helper"))
+    );
+
+    // Another team, a container the team shares with its other apps,
+    // or the team's entries elsewhere that are not about the app
+    assert!(!app_info.associate_path_matches(&library.join("Group Containers/UBF8T346G9.shared")));
+    assert!(!app_info.associate_path_matches(&library.join("Group Containers/EQHXZ8M8AV.shared")));
+    assert!(!app_info.associate_path_matches(&library.join("Application Support/EQHXZ8M8AV.mail")));
+}
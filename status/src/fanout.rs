@@ -0,0 +1,41 @@
+use std::sync::Arc;
+
+use crate::StatusHandler;
+use crate::status_event::StatusEvent;
+
+/// Sends every event to several handlers, like the GUI channel and a log file
+#[derive(Clone, Default)]
+pub struct Fanout {
+    handlers: Vec<Arc<dyn StatusHandler>>,
+}
+
+impl Fanout {
+    pub fn new(handlers: Vec<Arc<dyn StatusHandler>>) -> Self {
+        Self { handlers }
+    }
+
+    pub fn with(mut self, handler: Arc<dyn StatusHandler>) -> Self {
+        self.handlers.push(handler);
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.handlers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.handlers.is_empty()
+    }
+}
+
+impl StatusHandler for Fanout {
+    fn handle_event(&self, event: StatusEvent) {
+        // Handlers get the event in the order they were added
+        if let Some((last, first)) = self.handlers.split_last() {
+            for handler in first {
+                handler.handle_event(event.clone());
+            }
+            last.handle_event(event);
+        }
+    }
+}
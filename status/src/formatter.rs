@@ -0,0 +1,129 @@
+use serde::Serialize;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::status_event::StatusEvent;
+
+/// Turns an event into the text shown to the user or written to a log.
+/// The GUI status line, the handlers and the command line share these
+pub trait StatusFormatter {
+    fn format(&self, event: &StatusEvent) -> String;
+}
+
+/// The status line: scope, phase, message, progress and path,
+/// always in that order and joined by the event's separator
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Compact {
+    percentage: bool,
+}
+
+impl Compact {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Follow the progress with the percentage done, like "256/1024 (25.0%)"
+    pub fn with_percentage(mut self, percentage: bool) -> Self {
+        self.percentage = percentage;
+        self
+    }
+}
+
+impl StatusFormatter for Compact {
+    fn format(&self, event: &StatusEvent) -> String {
+        with_scope(event, parts(event, self.percentage).join(event.separator()))
+    }
+}
+
+/// Everything known about the event, prefixed with its operation,
+/// for the stderr and log file lines
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Verbose;
+
+impl StatusFormatter for Verbose {
+    fn format(&self, event: &StatusEvent) -> String {
+        let mut parts = parts(event, true);
+
+        if let Some(rate) = event.rate {
+            parts.push(format!("{:.0} items/s", rate));
+        }
+        if let Some(eta) = event.eta {
+            parts.push(format!("{:.0} s left", eta.as_secs_f64().ceil()));
+        }
+
+        format!(
+            "op {}: {}",
+            event.operation.0,
+            with_scope(event, parts.join(event.separator()))
+        )
+    }
+}
+
+/// One JSON object, with the time it was written when one is given
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Json {
+    time: Option<SystemTime>,
+}
+
+#[derive(Serialize)]
+struct JsonLine<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    time: Option<f64>,
+    #[serde(flatten)]
+    event: &'a StatusEvent,
+}
+
+impl Json {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a "time" field, in seconds since the Unix epoch
+    pub fn at(time: SystemTime) -> Self {
+        Self { time: Some(time) }
+    }
+}
+
+impl StatusFormatter for Json {
+    fn format(&self, event: &StatusEvent) -> String {
+        let time = self.time.map(|time| {
+            time.duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs_f64())
+                .unwrap_or_default()
+        });
+        // Plain data, serializing it can not fail
+        serde_json::to_string(&JsonLine { time, event }).unwrap_or_default()
+    }
+}
+
+/// The fields of an event that are set, in the order they are shown
+pub(crate) fn parts(event: &StatusEvent, percentage: bool) -> Vec<String> {
+    let progress = event.current.map(|current| {
+        let mut progress = match event.total {
+            Some(total) => format!("{}/{}", current, total),
+            None => current.to_string(),
+        };
+        if let Some(fraction) = event.fraction().filter(|_| percentage) {
+            progress.push_str(&format!(" ({:.1}%)", fraction * 100.0));
+        }
+        progress
+    });
+
+    [
+        event.phase.as_ref().map(|p| p.to_string()),
+        event.message.clone(),
+        progress,
+        event.path.as_ref().map(|p| p.display().to_string()),
+    ]
+    .into_iter()
+    .flatten()
+    .collect()
+}
+
+// Like "App 2/5 › Scanning files"
+fn with_scope(event: &StatusEvent, text: String) -> String {
+    match event.scope_label() {
+        Some(scope) if text.is_empty() => scope,
+        Some(scope) => format!("{} › {}", scope, text),
+        None => text,
+    }
+}
@@ -1,11 +1,21 @@
+pub mod fanout;
+pub mod formatter;
+pub mod line_format;
+pub mod log_file_handler;
+pub mod recording;
+mod scope;
 pub mod status_channel;
 pub mod status_event;
+pub mod stderr_handler;
+pub mod throughput;
 
-use status_channel::StatusChannel;
-use status_event::StatusEvent;
+use fanout::Fanout;
+use scope::Scope;
+use status_channel::{StatusReceiver, status_channel};
+use status_event::{OperationId, Phase, StatusEvent};
 use std::path::PathBuf;
 use std::sync::Arc;
-use tokio::sync::mpsc;
+use throughput::Throughput;
 
 pub trait StatusHandler: Send + Sync {
     fn handle_event(&self, event: StatusEvent);
@@ -15,20 +25,52 @@ pub trait StatusHandler: Send + Sync {
 pub struct StatusEmitter {
     emitter: Arc<dyn StatusHandler>,
     event: StatusEvent,
+    scope: Scope,
 }
 
 impl StatusEmitter {
+    /// Every emitter starts a new operation, its events share the operation id
     pub fn new(emitter: Arc<dyn StatusHandler>) -> Self {
         Self {
             emitter,
-            event: StatusEvent::new(),
+            event: StatusEvent::new().with_operation(OperationId::next()),
+            scope: Scope::root(),
         }
     }
 
-    pub fn with_stage(&self, stage: impl Into<String>) -> Self {
+    /// Emitter of a step of this operation, like one app of a batch.
+    /// It covers the next `weight` of this emitter's progress, between 0 and 1,
+    /// and its events carry the name after the names of the scopes above
+    pub fn child(&self, name: impl Into<String>, weight: f32) -> Self {
+        let mut event = StatusEvent::new()
+            .with_operation(self.event.operation)
+            .with_separator(self.event.separator());
+        event.scope = self.event.scope.clone();
+        event.scope.push(name.into());
+
+        Self {
+            emitter: self.emitter.clone(),
+            event,
+            scope: self.scope.child(weight),
+        }
+    }
+
+    pub fn operation(&self) -> OperationId {
+        self.event.operation
+    }
+
+    /// Continue an operation started by another emitter
+    pub fn with_operation(&self, operation: OperationId) -> Self {
+        let mut new = self.clone();
+
+        new.event = new.event.with_operation(operation);
+        new
+    }
+
+    pub fn with_phase(&self, phase: Phase) -> Self {
         let mut new = self.clone();
 
-        new.event = new.event.with_stage(stage);
+        new.event = new.event.with_phase(phase);
         new
     }
 
@@ -60,22 +102,59 @@ impl StatusEmitter {
         new
     }
 
+    /// Progress with the speed so far and, with a total, the time left
+    pub fn with_progress(
+        &self,
+        current: usize,
+        total: Option<usize>,
+        throughput: &Throughput,
+    ) -> Self {
+        let mut new = self.with_current(current);
+
+        if let Some(total) = total {
+            new.event = new.event.with_total(total);
+            if let Some(eta) = throughput.eta(current, total) {
+                new.event = new.event.with_eta(eta);
+            }
+        }
+        if let Some(rate) = throughput.rate(current) {
+            new.event = new.event.with_rate(rate);
+        }
+        new
+    }
+
+    /// Text put between the fields when the events are shown
     pub fn with_separator(&self, sep: impl Into<String>) -> Self {
         let mut new = self.clone();
-        new.event = new.event.with_message(sep);
+
+        new.event = new.event.with_separator(sep);
         new
     }
 
     pub fn emit(&self) {
-        let event = self.event.clone();
+        let mut event = self.event.clone();
+        event.overall = self.scope.overall(&event);
         self.emitter.handle_event(event);
     }
 }
 
-pub fn setup_status_emitter(buffer: usize) -> (StatusEmitter, mpsc::Receiver<StatusEvent>) {
-    let (tx, rx) = mpsc::channel::<StatusEvent>(buffer);
-    let handler = Arc::new(StatusChannel::new(tx));
-    let emitter = StatusEmitter::new(handler);
+/// Emitter of a new operation and the receiver of its events.
+/// Up to `buffer` events wait for the receiver, progress ticks are coalesced
+pub fn setup_status_emitter(buffer: usize) -> (StatusEmitter, StatusReceiver) {
+    let (tx, rx) = status_channel(buffer);
+    let emitter = StatusEmitter::new(Arc::new(tx));
+
+    (emitter, rx)
+}
+
+/// Like setup_status_emitter, the events also go to the other handlers
+pub fn setup_status_emitter_with(
+    buffer: usize,
+    others: Vec<Arc<dyn StatusHandler>>,
+) -> (StatusEmitter, StatusReceiver) {
+    let (tx, rx) = status_channel(buffer);
+    let fanout = Fanout::new(others).with(Arc::new(tx));
+    let emitter = StatusEmitter::new(Arc::new(fanout));
 
     (emitter, rx)
 }
@@ -0,0 +1,28 @@
+use std::time::SystemTime;
+
+use crate::formatter::{Json, StatusFormatter, Verbose};
+use crate::status_event::StatusEvent;
+
+/// How the stderr and log file handlers write an event, one per line
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum LineFormat {
+    /// Like "op 3: Scanning files 256/1024 (25.0%) 4 s left"
+    #[default]
+    Human,
+    /// One JSON object per line with the time it was written, for tools
+    Json,
+}
+
+impl LineFormat {
+    pub fn format(&self, event: &StatusEvent) -> String {
+        self.format_at(event, SystemTime::now())
+    }
+
+    /// The line of an event written at the given time, no newline
+    pub fn format_at(&self, event: &StatusEvent, time: SystemTime) -> String {
+        match self {
+            LineFormat::Human => Verbose.format(event),
+            LineFormat::Json => Json::at(time).format(event),
+        }
+    }
+}
@@ -0,0 +1,118 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::StatusHandler;
+use crate::line_format::LineFormat;
+use crate::status_event::StatusEvent;
+
+/// Appends every event to a log file. Once the file would grow past its limit
+/// it is rotated to "name.1", the older ones shift up to "name.<keep>"
+pub struct LogFileHandler {
+    path: PathBuf,
+    format: LineFormat,
+    max_bytes: u64,
+    keep: usize,
+    file: Mutex<Option<(File, u64)>>,
+}
+
+impl LogFileHandler {
+    /// Opens or creates the file and its folder, new lines go at its end
+    pub fn new(path: &Path, format: LineFormat) -> io::Result<Self> {
+        let handler = Self {
+            path: path.to_path_buf(),
+            format,
+            max_bytes: 1024 * 1024,
+            keep: 3,
+            file: Mutex::new(None),
+        };
+        let opened = handler.open()?;
+        *handler.lock() = Some(opened);
+        Ok(handler)
+    }
+
+    /// Size the file may reach before it is rotated
+    pub fn max_bytes(mut self, max_bytes: u64) -> Self {
+        self.max_bytes = max_bytes;
+        self
+    }
+
+    /// Rotated files kept next to the current one, 0 keeps none
+    pub fn keep(mut self, keep: usize) -> Self {
+        self.keep = keep;
+        self
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The rotated file of the given age, 1 being the newest
+    pub fn rotated_path(&self, age: usize) -> PathBuf {
+        let mut name = self.path.as_os_str().to_owned();
+        name.push(format!(".{age}"));
+        PathBuf::from(name)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Option<(File, u64)>> {
+        self.file.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn open(&self) -> io::Result<(File, u64)> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        let size = file.metadata()?.len();
+        Ok((file, size))
+    }
+
+    fn rotate(&self) -> io::Result<(File, u64)> {
+        if self.keep == 0 {
+            fs::remove_file(&self.path)?;
+        } else {
+            for age in (1..self.keep).rev() {
+                let older = self.rotated_path(age);
+                if older.exists() {
+                    fs::rename(&older, self.rotated_path(age + 1))?;
+                }
+            }
+            fs::rename(&self.path, self.rotated_path(1))?;
+        }
+        self.open()
+    }
+
+    fn write_line(&self, line: &str) -> io::Result<()> {
+        let mut slot = self.lock();
+        let len = line.len() as u64 + 1;
+
+        // A line longer than the limit still gets a file of its own
+        let full = matches!(&*slot, Some((_, size)) if *size > 0 && size + len > self.max_bytes);
+        if full {
+            *slot = None;
+            *slot = Some(self.rotate()?);
+        }
+        // Reopened after a failed rotation
+        if slot.is_none() {
+            *slot = Some(self.open()?);
+        }
+
+        let Some((file, size)) = slot.as_mut() else {
+            return Ok(());
+        };
+        writeln!(file, "{line}")?;
+        *size += len;
+        Ok(())
+    }
+}
+
+impl StatusHandler for LogFileHandler {
+    fn handle_event(&self, event: StatusEvent) {
+        // Logging must never stop the operation, a failed write is left out
+        let _ = self.write_line(&self.format.format(&event));
+    }
+}
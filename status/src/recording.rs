@@ -0,0 +1,51 @@
+use std::sync::{Mutex, MutexGuard};
+
+use crate::StatusHandler;
+use crate::status_event::{StatusEvent, Step};
+
+/// Keeps every event in memory, to assert on what an operation reported
+#[derive(Default)]
+pub struct Recording {
+    events: Mutex<Vec<StatusEvent>>,
+}
+
+impl Recording {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Vec<StatusEvent>> {
+        self.events.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// The events so far, in the order they were emitted
+    pub fn events(&self) -> Vec<StatusEvent> {
+        self.lock().clone()
+    }
+
+    /// Steps in the order they were reported, progress in the same step counted once
+    pub fn steps(&self) -> Vec<Step> {
+        let mut steps: Vec<Step> = self.lock().iter().filter_map(StatusEvent::step).collect();
+        steps.dedup();
+        steps
+    }
+
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    /// Forget the events so far
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+}
+
+impl StatusHandler for Recording {
+    fn handle_event(&self, event: StatusEvent) {
+        self.lock().push(event);
+    }
+}
@@ -0,0 +1,67 @@
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use crate::status_event::{StatusEvent, Step};
+
+/// Part of the whole operation an emitter reports on, between 0 and 1.
+/// The root covers all of it, each child a share of its parent
+#[derive(Debug, Clone)]
+pub(crate) struct Scope {
+    start: f32,
+    span: f32,
+    nested: bool,
+    /// Share of the span already given to children
+    given: Arc<Mutex<f32>>,
+    /// Furthest the whole operation got, shared by all its scopes
+    reached: Arc<Mutex<f32>>,
+}
+
+fn lock(value: &Mutex<f32>) -> MutexGuard<'_, f32> {
+    value.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+impl Scope {
+    pub(crate) fn root() -> Self {
+        Self {
+            start: 0.0,
+            span: 1.0,
+            nested: false,
+            given: Arc::new(Mutex::new(0.0)),
+            reached: Arc::new(Mutex::new(0.0)),
+        }
+    }
+
+    /// The next `weight` of this scope, children follow each other.
+    /// A weight past what is left gets only what is left
+    pub(crate) fn child(&self, weight: f32) -> Self {
+        let mut given = lock(&self.given);
+        let share = weight.clamp(0.0, (1.0 - *given).max(0.0));
+        let start = self.start + *given * self.span;
+        *given += share;
+
+        Self {
+            start,
+            span: share * self.span,
+            nested: true,
+            given: Arc::new(Mutex::new(0.0)),
+            reached: self.reached.clone(),
+        }
+    }
+
+    /// Part of the whole operation done once this event is reported, it never goes back.
+    /// None for an operation without children, its own fraction tells it all
+    pub(crate) fn overall(&self, event: &StatusEvent) -> Option<f32> {
+        if !self.nested && *lock(&self.given) == 0.0 {
+            return None;
+        }
+
+        let measured = match (event.fraction(), event.step()) {
+            (_, Some(Step::Done)) => self.start + self.span,
+            (Some(fraction), _) => self.start + fraction * self.span,
+            _ => self.start,
+        };
+
+        let mut reached = lock(&self.reached);
+        *reached = reached.max(measured).min(1.0);
+        Some(*reached)
+    }
+}
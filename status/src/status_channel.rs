@@ -1,19 +1,137 @@
-use tokio::sync::mpsc::Sender;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use tokio::sync::Notify;
 
 use crate::StatusHandler;
-use crate::status_event::StatusEvent;
+use crate::status_event::{StatusEvent, Step};
 
+/// Delivers status events to one receiver without losing the ones that matter.
+///
+/// Progress ticks, events that only move `current` in the running phase, are
+/// coalesced: a slow receiver gets the latest tick of each phase. Every other
+/// event goes through a bounded queue and is never dropped, the emitting thread
+/// waits for room instead. Events arrive in the order they were emitted
 pub struct StatusChannel {
-    sender: Sender<StatusEvent>,
+    shared: Arc<Shared>,
 }
 
-impl StatusChannel {
-    pub fn new(sender: Sender<StatusEvent>) -> Self {
-        Self { sender }
+pub struct StatusReceiver {
+    shared: Arc<Shared>,
+}
+
+struct Shared {
+    state: Mutex<State>,
+    /// Room was made in the queue or the receiver is gone
+    room: Condvar,
+    /// An event is waiting or the channel closed
+    ready: Notify,
+}
+
+struct State {
+    capacity: usize,
+    next_seq: u64,
+    /// Events that are never dropped, with their emit order
+    queue: VecDeque<(u64, StatusEvent)>,
+    /// Latest tick of each phase, with the emit order of that tick
+    ticks: HashMap<Step, (u64, StatusEvent)>,
+    /// Step of the last event, a tick of another step is a phase change
+    last_step: Option<Step>,
+    sender_closed: bool,
+    receiver_closed: bool,
+}
+
+/// Channel of at most `capacity` queued events, ticks not counted
+pub fn status_channel(capacity: usize) -> (StatusChannel, StatusReceiver) {
+    let shared = Arc::new(Shared {
+        state: Mutex::new(State {
+            capacity: capacity.max(1),
+            next_seq: 0,
+            queue: VecDeque::new(),
+            ticks: HashMap::new(),
+            last_step: None,
+            sender_closed: false,
+            receiver_closed: false,
+        }),
+        room: Condvar::new(),
+        ready: Notify::new(),
+    });
+
+    (
+        StatusChannel {
+            shared: shared.clone(),
+        },
+        StatusReceiver { shared },
+    )
+}
+
+impl Shared {
+    fn lock(&self) -> MutexGuard<'_, State> {
+        // A panic while holding the lock leaves the queue usable
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl State {
+    /// Step of a progress tick, None for events that must not be dropped
+    fn tick_step(&self, event: &StatusEvent) -> Option<Step> {
+        let phase = event.phase.as_ref()?;
+        let step = phase.step();
+        (event.current.is_some() && phase.is_running() && self.last_step == Some(step))
+            .then_some(step)
+    }
+
+    /// Earliest waiting event, queued or tick
+    fn take(&mut self) -> Option<StatusEvent> {
+        let queued = self.queue.front().map(|(seq, _)| *seq);
+        let tick = self
+            .ticks
+            .iter()
+            .min_by_key(|(_, (seq, _))| *seq)
+            .map(|(step, (seq, _))| (*step, *seq));
+
+        match (queued, tick) {
+            (Some(q), Some((_, t))) if q < t => self.queue.pop_front().map(|(_, e)| e),
+            (_, Some((step, _))) => self.ticks.remove(&step).map(|(_, e)| e),
+            (Some(_), None) => self.queue.pop_front().map(|(_, e)| e),
+            (None, None) => None,
+        }
     }
+}
 
+impl StatusChannel {
     fn send_event(&self, event: StatusEvent) {
-        let _ = self.sender.try_send(event);
+        let mut state = self.shared.lock();
+        if state.receiver_closed {
+            return;
+        }
+
+        if let Some(step) = state.tick_step(&event) {
+            let seq = state.next_seq;
+            state.next_seq += 1;
+            state.ticks.insert(step, (seq, event));
+        } else {
+            // Blocks the emitting thread until the receiver catches up
+            while state.queue.len() >= state.capacity && !state.receiver_closed {
+                state = self
+                    .shared
+                    .room
+                    .wait(state)
+                    .unwrap_or_else(|e| e.into_inner());
+            }
+            if state.receiver_closed {
+                return;
+            }
+
+            let seq = state.next_seq;
+            state.next_seq += 1;
+            if let Some(step) = event.step() {
+                state.last_step = Some(step);
+            }
+            state.queue.push_back((seq, event));
+        }
+
+        drop(state);
+        self.shared.ready.notify_one();
     }
 }
 
@@ -22,3 +140,51 @@ impl StatusHandler for StatusChannel {
         self.send_event(event);
     }
 }
+
+impl Drop for StatusChannel {
+    fn drop(&mut self) {
+        self.shared.lock().sender_closed = true;
+        self.shared.ready.notify_one();
+    }
+}
+
+impl StatusReceiver {
+    /// Next event, None once every emitter is gone and all was received
+    pub async fn recv(&mut self) -> Option<StatusEvent> {
+        loop {
+            let notified = self.shared.ready.notified();
+            {
+                let mut state = self.shared.lock();
+                if let Some(event) = state.take() {
+                    drop(state);
+                    self.shared.room.notify_all();
+                    return Some(event);
+                }
+                if state.sender_closed {
+                    return None;
+                }
+            }
+            notified.await;
+        }
+    }
+
+    /// Next event if one is waiting
+    pub fn try_recv(&mut self) -> Option<StatusEvent> {
+        let event = self.shared.lock().take();
+        if event.is_some() {
+            self.shared.room.notify_all();
+        }
+        event
+    }
+}
+
+impl Drop for StatusReceiver {
+    fn drop(&mut self) {
+        let mut state = self.shared.lock();
+        state.receiver_closed = true;
+        state.queue.clear();
+        state.ticks.clear();
+        drop(state);
+        self.shared.room.notify_all();
+    }
+}
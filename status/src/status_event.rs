@@ -1,102 +1,289 @@
+use serde::{Serialize, Serializer};
+use std::fmt;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum Field {
-    Stage,
-    Current,
-    Total,
-    Message,
-    Path,
+use crate::formatter::parts;
+
+/// Step of an operation, the phase without its payload.
+/// Steps are compared to tell a phase change from progress in the same phase
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+pub enum Step {
+    ResolvingApp,
+    ScanningProcesses,
+    KillingProcesses,
+    ScanningBom,
+    ScanningFiles,
+    Trashing,
+    ExportingBom,
+    Done,
+    Failed,
+}
+
+impl Step {
+    /// Steps of scanning a dropped app, in the order they run
+    pub const SCAN: [Step; 5] = [
+        Step::ResolvingApp,
+        Step::ScanningProcesses,
+        Step::ScanningBom,
+        Step::ScanningFiles,
+        Step::Done,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Step::ResolvingApp => "Resolving app",
+            Step::ScanningProcesses => "Scanning processes",
+            Step::KillingProcesses => "Killing processes",
+            Step::ScanningBom => "Scanning BOM logs",
+            Step::ScanningFiles => "Scanning files",
+            Step::Trashing => "Moving to Trash",
+            Step::ExportingBom => "Exporting BOM logs",
+            Step::Done => "Done",
+            Step::Failed => "Failed",
+        }
+    }
+
+    /// Nothing follows a terminal step in the same operation
+    pub fn is_terminal(&self) -> bool {
+        matches!(self, Step::Done | Step::Failed)
+    }
+}
+
+impl fmt::Display for Step {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.label())
+    }
+}
+
+/// Phase of an operation with what it found.
+/// Counts are None while the phase runs and set when it is finished
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "step")]
+pub enum Phase {
+    ResolvingApp { path: Option<PathBuf> },
+    ScanningProcesses { found: Option<usize> },
+    KillingProcesses { killed: Option<usize> },
+    ScanningBom { found: Option<usize> },
+    ScanningFiles { found: Option<usize> },
+    Trashing { trashed: Option<usize> },
+    ExportingBom { saved: Option<usize> },
+    Done,
+    Failed { reason: String },
+}
+
+impl Phase {
+    pub fn step(&self) -> Step {
+        match self {
+            Phase::ResolvingApp { .. } => Step::ResolvingApp,
+            Phase::ScanningProcesses { .. } => Step::ScanningProcesses,
+            Phase::KillingProcesses { .. } => Step::KillingProcesses,
+            Phase::ScanningBom { .. } => Step::ScanningBom,
+            Phase::ScanningFiles { .. } => Step::ScanningFiles,
+            Phase::Trashing { .. } => Step::Trashing,
+            Phase::ExportingBom { .. } => Step::ExportingBom,
+            Phase::Done => Step::Done,
+            Phase::Failed { .. } => Step::Failed,
+        }
+    }
+
+    /// Still running, the payload is only set once the phase is finished
+    pub fn is_running(&self) -> bool {
+        match self {
+            Phase::ResolvingApp { path } => path.is_none(),
+            Phase::ExportingBom { saved } => saved.is_none(),
+            Phase::Done | Phase::Failed { .. } => false,
+            phase => phase.count().is_none(),
+        }
+    }
+
+    /// The count of a finished phase
+    pub fn count(&self) -> Option<usize> {
+        match self {
+            Phase::ScanningProcesses { found }
+            | Phase::ScanningBom { found }
+            | Phase::ScanningFiles { found } => *found,
+            Phase::KillingProcesses { killed } => *killed,
+            Phase::Trashing { trashed } => *trashed,
+            Phase::ExportingBom { saved } => *saved,
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Phase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Phase::Failed { reason } => write!(f, "Failed: {}", reason),
+            Phase::KillingProcesses { killed: Some(n) } => write!(f, "Killed {} processes", n),
+            Phase::Trashing { trashed: Some(n) } => write!(f, "Moved {} items to Trash", n),
+            Phase::ExportingBom { saved: Some(n) } => write!(f, "Saved {} BOM logs", n),
+            phase => match phase.count() {
+                Some(n) => write!(f, "{}: {} found", phase.step(), n),
+                None => write!(f, "{}", phase.step()),
+            },
+        }
+    }
 }
 
-#[derive(Debug, Default, Clone)]
+/// Identifies the operation an event belongs to, like one scan or one move to Trash
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
+pub struct OperationId(pub u64);
+
+impl OperationId {
+    /// A new id, unique in this process
+    pub fn next() -> Self {
+        static NEXT: AtomicU64 = AtomicU64::new(1);
+        Self(NEXT.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
 pub struct StatusEvent {
-    pub stage: Option<String>,
+    pub operation: OperationId,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub phase: Option<Phase>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub current: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub total: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub message: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub path: Option<PathBuf>,
+    /// Entries done per second so far
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rate: Option<f64>,
+    /// Time left, only known with a total
+    #[serde(
+        rename = "eta_secs",
+        serialize_with = "serialize_secs",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub eta: Option<Duration>,
+
+    /// Names of the scopes the event was emitted in, outermost first
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub scope: Vec<String>,
+    /// Part of the whole operation done, with the progress of nested scopes
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub overall: Option<f32>,
 
-    order: Vec<Field>,
+    /// Put between the fields when the event is shown as text
+    #[serde(skip)]
     separator: String,
 }
 
-impl StatusEvent {
-    pub fn new() -> Self {
+fn serialize_secs<S: Serializer>(eta: &Option<Duration>, s: S) -> Result<S::Ok, S::Error> {
+    match eta {
+        Some(eta) => s.serialize_f64(eta.as_secs_f64()),
+        None => s.serialize_none(),
+    }
+}
+
+impl Default for StatusEvent {
+    fn default() -> Self {
         Self {
+            operation: OperationId::default(),
+            phase: None,
+            current: None,
+            total: None,
+            message: None,
+            path: None,
+            rate: None,
+            eta: None,
+            scope: Vec::new(),
+            overall: None,
             separator: " ".to_string(),
-            ..Default::default()
         }
     }
+}
 
-    pub fn with_stage(mut self, stage: impl Into<String>) -> Self {
-        if self.stage.is_none() {
-            self.order.push(Field::Stage);
-        }
-        self.stage = Some(stage.into());
+impl StatusEvent {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_operation(mut self, operation: OperationId) -> Self {
+        self.operation = operation;
+        self
+    }
+
+    pub fn with_phase(mut self, phase: Phase) -> Self {
+        self.phase = Some(phase);
         self
     }
 
+    pub fn step(&self) -> Option<Step> {
+        self.phase.as_ref().map(Phase::step)
+    }
+
+    /// Done or Failed, the operation is over
+    pub fn is_terminal(&self) -> bool {
+        self.step().is_some_and(|s| s.is_terminal())
+    }
+
     pub fn with_current(mut self, current: usize) -> Self {
-        if self.current.is_none() {
-            self.order.push(Field::Current);
-        }
         self.current = Some(current);
         self
     }
 
     pub fn with_total(mut self, total: usize) -> Self {
-        if self.total.is_none() {
-            self.order.push(Field::Total);
-        }
         self.total = Some(total);
         self
     }
 
     pub fn with_message(mut self, msg: impl Into<String>) -> Self {
-        if self.message.is_none() {
-            self.order.push(Field::Message);
-        }
         self.message = Some(msg.into());
         self
     }
 
     pub fn with_path(mut self, path: PathBuf) -> Self {
-        if self.path.is_none() {
-            self.order.push(Field::Path);
-        }
         self.path = Some(path);
         self
     }
 
+    pub fn with_rate(mut self, rate: f64) -> Self {
+        self.rate = Some(rate);
+        self
+    }
+
+    pub fn with_eta(mut self, eta: Duration) -> Self {
+        self.eta = Some(eta);
+        self
+    }
+
+    /// Part done between 0 and 1, None without a total.
+    /// An estimated total can be passed, it never goes past 1
+    pub fn fraction(&self) -> Option<f32> {
+        match (self.current, self.total) {
+            (Some(current), Some(total)) if total > 0 => {
+                Some((current.min(total) as f32 / total as f32).clamp(0.0, 1.0))
+            }
+            _ => None,
+        }
+    }
+
+    /// Like "App 2/5 › Scanning", None outside of any scope
+    pub fn scope_label(&self) -> Option<String> {
+        (!self.scope.is_empty()).then(|| self.scope.join(" › "))
+    }
+
+    /// Text put between the fields, a space by default
     pub fn with_separator(mut self, sep: impl Into<String>) -> Self {
         self.separator = sep.into();
         self
     }
+
+    pub fn separator(&self) -> &str {
+        &self.separator
+    }
 }
 
+/// The phase, message, progress and path that are set, in that order
 impl std::fmt::Display for StatusEvent {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let mut first = true;
-
-        for field in &self.order {
-            let value: Option<String> = match field {
-                Field::Stage => self.stage.clone(),
-                Field::Message => self.message.clone(),
-                Field::Current => self.current.map(|v| v.to_string()),
-                Field::Total => self.total.map(|v| v.to_string()),
-                Field::Path => self.path.as_ref().map(|p| p.display().to_string()),
-            };
-
-            if let Some(v) = value {
-                if !first {
-                    write!(f, "{}", self.separator)?;
-                }
-                write!(f, "{v}")?;
-                first = false;
-            }
-        }
-
-        Ok(())
+        write!(f, "{}", parts(self, false).join(self.separator()))
     }
 }
@@ -0,0 +1,24 @@
+use std::io::Write;
+
+use crate::StatusHandler;
+use crate::line_format::LineFormat;
+use crate::status_event::StatusEvent;
+
+/// Writes every event to stderr, for the command line and debugging
+#[derive(Debug, Default, Clone, Copy)]
+pub struct StderrHandler {
+    format: LineFormat,
+}
+
+impl StderrHandler {
+    pub fn new(format: LineFormat) -> Self {
+        Self { format }
+    }
+}
+
+impl StatusHandler for StderrHandler {
+    fn handle_event(&self, event: StatusEvent) {
+        // Nowhere left to report a failed write to stderr
+        let _ = writeln!(std::io::stderr().lock(), "{}", self.format.format(&event));
+    }
+}
@@ -0,0 +1,42 @@
+use std::time::{Duration, Instant};
+
+/// Speed of a running phase, measured from when it started
+#[derive(Debug, Clone, Copy)]
+pub struct Throughput {
+    started: Instant,
+}
+
+impl Throughput {
+    pub fn start() -> Self {
+        Self::since(Instant::now())
+    }
+
+    pub fn since(started: Instant) -> Self {
+        Self { started }
+    }
+
+    /// Entries per second, None before anything was done
+    pub fn rate(&self, current: usize) -> Option<f64> {
+        self.rate_at(current, Instant::now())
+    }
+
+    pub fn rate_at(&self, current: usize, now: Instant) -> Option<f64> {
+        let elapsed = now.saturating_duration_since(self.started).as_secs_f64();
+        if current == 0 || elapsed <= 0.0 {
+            return None;
+        }
+        Some(current as f64 / elapsed)
+    }
+
+    /// Time left at the speed so far. An estimated total can be passed already,
+    /// the time left is zero then
+    pub fn eta(&self, current: usize, total: usize) -> Option<Duration> {
+        self.eta_at(current, total, Instant::now())
+    }
+
+    pub fn eta_at(&self, current: usize, total: usize, now: Instant) -> Option<Duration> {
+        let rate = self.rate_at(current, now)?;
+        let left = total.saturating_sub(current) as f64;
+        Some(Duration::from_secs_f64(left / rate))
+    }
+}
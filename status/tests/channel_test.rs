@@ -0,0 +1,154 @@
+use std::time::Duration;
+
+use status::setup_status_emitter;
+use status::status_channel::StatusReceiver;
+use status::status_event::{Phase, StatusEvent, Step};
+
+fn drain(rx: &mut StatusReceiver) -> Vec<StatusEvent> {
+    std::iter::from_fn(|| rx.try_recv()).collect()
+}
+
+fn scanning() -> Phase {
+    Phase::ScanningFiles { found: None }
+}
+
+#[test]
+fn test_ticks_are_coalesced_and_the_rest_kept() {
+    let (emitter, mut rx) = setup_status_emitter(10);
+
+    emitter.with_phase(scanning()).emit();
+    for current in 1..=100 {
+        emitter.with_phase(scanning()).with_current(current).emit();
+    }
+    emitter
+        .with_phase(Phase::ScanningFiles { found: Some(4) })
+        .emit();
+    emitter.with_phase(Phase::Done).emit();
+
+    let events = drain(&mut rx);
+    assert_eq!(events.len(), 4);
+    assert_eq!(events[0].current, None);
+    // Only the latest tick is left
+    assert_eq!(events[1].current, Some(100));
+    assert_eq!(events[2].phase.as_ref().and_then(Phase::count), Some(4));
+    assert_eq!(events[3].step(), Some(Step::Done));
+}
+
+#[test]
+fn test_a_tick_of_a_new_phase_is_a_phase_change() {
+    let (emitter, mut rx) = setup_status_emitter(10);
+
+    emitter
+        .with_phase(Phase::ScanningBom { found: None })
+        .with_current(1)
+        .emit();
+    for current in 1..=3 {
+        emitter.with_phase(scanning()).with_current(current).emit();
+    }
+
+    let events = drain(&mut rx);
+    let seen: Vec<(Option<Step>, Option<usize>)> =
+        events.iter().map(|e| (e.step(), e.current)).collect();
+    assert_eq!(
+        seen,
+        vec![
+            (Some(Step::ScanningBom), Some(1)),
+            (Some(Step::ScanningFiles), Some(1)),
+            (Some(Step::ScanningFiles), Some(3)),
+        ]
+    );
+}
+
+#[test]
+fn test_events_keep_the_emit_order() {
+    let (emitter, mut rx) = setup_status_emitter(10);
+
+    emitter.with_phase(scanning()).emit();
+    emitter.with_phase(scanning()).with_current(5).emit();
+    emitter.with_message("Halfway").emit();
+    emitter.with_phase(scanning()).with_current(6).emit();
+
+    // The newer tick replaces the older one and comes after the message
+    let events = drain(&mut rx);
+    let currents: Vec<Option<usize>> = events.iter().map(|e| e.current).collect();
+    assert_eq!(currents, vec![None, None, Some(6)]);
+    assert_eq!(events[1].message.as_deref(), Some("Halfway"));
+}
+
+#[tokio::test]
+async fn test_slow_receiver_misses_nothing_important() {
+    let (emitter, mut rx) = setup_status_emitter(2);
+
+    let producer = std::thread::spawn(move || {
+        for round in 0..20 {
+            emitter
+                .with_phase(scanning())
+                .with_message(format!("Round {round}"))
+                .emit();
+            for current in 1..=50 {
+                emitter.with_phase(scanning()).with_current(current).emit();
+            }
+            emitter
+                .with_phase(Phase::ScanningFiles { found: Some(round) })
+                .emit();
+        }
+        emitter.with_phase(Phase::Done).emit();
+    });
+
+    let mut events = Vec::new();
+    while let Some(event) = rx.recv().await {
+        events.push(event);
+        tokio::time::sleep(Duration::from_micros(200)).await;
+    }
+    producer.join().unwrap();
+
+    let rounds: Vec<String> = events.iter().filter_map(|e| e.message.clone()).collect();
+    let expected: Vec<String> = (0..20).map(|round| format!("Round {round}")).collect();
+    assert_eq!(rounds, expected);
+
+    let found: Vec<usize> = events
+        .iter()
+        .filter_map(|e| e.phase.as_ref().and_then(Phase::count))
+        .collect();
+    assert_eq!(found, (0..20).collect::<Vec<_>>());
+
+    // Ticks of a round come between its start and its end
+    let mut last = 0;
+    for event in &events {
+        match (event.message.is_some(), event.current) {
+            (true, _) => last = 0,
+            (false, Some(current)) => {
+                assert!(current > last);
+                last = current;
+            }
+            _ => {}
+        }
+    }
+
+    assert_eq!(events.last().and_then(StatusEvent::step), Some(Step::Done));
+    assert_eq!(events.iter().filter(|e| e.is_terminal()).count(), 1);
+}
+
+#[test]
+fn test_dropped_receiver_never_blocks_the_emitter() {
+    let (emitter, rx) = setup_status_emitter(1);
+    drop(rx);
+
+    for _ in 0..10 {
+        emitter.with_phase(scanning()).emit();
+    }
+    emitter.with_phase(Phase::Done).emit();
+}
+
+#[tokio::test]
+async fn test_receiver_ends_with_the_emitters() {
+    let (emitter, mut rx) = setup_status_emitter(10);
+    let clone = emitter.clone();
+
+    emitter.with_phase(Phase::Done).emit();
+    drop(emitter);
+    assert!(rx.recv().await.is_some());
+
+    drop(clone);
+    assert!(rx.recv().await.is_none());
+}
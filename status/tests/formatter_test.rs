@@ -0,0 +1,106 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, UNIX_EPOCH};
+
+use status::StatusEmitter;
+use status::formatter::{Compact, Json, StatusFormatter, Verbose};
+use status::recording::Recording;
+use status::status_event::{OperationId, Phase, StatusEvent};
+
+#[test]
+fn test_display_does_not_follow_builder_order() {
+    let forward = StatusEvent::new()
+        .with_phase(Phase::Trashing { trashed: None })
+        .with_message("Caches")
+        .with_current(3)
+        .with_total(8)
+        .with_path(PathBuf::from("/tmp/a"));
+    let backward = StatusEvent::new()
+        .with_path(PathBuf::from("/tmp/a"))
+        .with_total(8)
+        .with_current(3)
+        .with_message("Caches")
+        .with_phase(Phase::Trashing { trashed: None });
+
+    assert_eq!(forward.to_string(), "Moving to Trash Caches 3/8 /tmp/a");
+    assert_eq!(forward.to_string(), backward.to_string());
+    assert_eq!(
+        Compact::new().format(&forward),
+        Compact::new().format(&backward)
+    );
+    assert_eq!(Verbose.format(&forward), Verbose.format(&backward));
+}
+
+#[test]
+fn test_separator_keeps_the_message() {
+    let recording = Arc::new(Recording::new());
+    StatusEmitter::new(recording.clone())
+        .with_message("Scanning")
+        .with_separator(" · ")
+        .with_current(4)
+        .emit();
+
+    let event = &recording.events()[0];
+    assert_eq!(event.message.as_deref(), Some("Scanning"));
+    assert_eq!(event.separator(), " · ");
+    assert_eq!(event.to_string(), "Scanning · 4");
+
+    // Scopes keep the separator of the emitter they come from
+    let recording = Arc::new(Recording::new());
+    StatusEmitter::new(recording.clone())
+        .with_separator(" | ")
+        .child("Safari", 1.0)
+        .with_phase(Phase::ScanningBom { found: Some(2) })
+        .with_message("2 receipts")
+        .emit();
+    assert_eq!(
+        Compact::new().format(&recording.events()[0]),
+        "Safari › Scanning BOM logs: 2 found | 2 receipts"
+    );
+}
+
+#[test]
+fn test_compact_and_verbose() {
+    let event = StatusEvent::new()
+        .with_operation(OperationId(5))
+        .with_phase(Phase::ScanningFiles { found: None })
+        .with_current(50)
+        .with_total(200)
+        .with_rate(24.6)
+        .with_eta(Duration::from_millis(6200));
+
+    assert_eq!(Compact::new().format(&event), "Scanning files 50/200");
+    assert_eq!(
+        Compact::new().with_percentage(true).format(&event),
+        "Scanning files 50/200 (25.0%)"
+    );
+    assert_eq!(
+        Verbose.format(&event),
+        "op 5: Scanning files 50/200 (25.0%) 25 items/s 7 s left"
+    );
+
+    // Nothing set but the scope
+    let mut event = StatusEvent::new();
+    assert_eq!(Compact::new().format(&event), "");
+    event.scope = vec!["Batch".into(), "Safari".into()];
+    assert_eq!(Compact::new().format(&event), "Batch › Safari");
+}
+
+#[test]
+fn test_json_with_and_without_time() {
+    let event = StatusEvent::new()
+        .with_operation(OperationId(9))
+        .with_message("Done")
+        .with_separator(" · ");
+
+    let json: serde_json::Value = serde_json::from_str(&Json::new().format(&event)).unwrap();
+    assert_eq!(json["operation"], 9);
+    assert_eq!(json["message"], "Done");
+    assert!(json.get("time").is_none());
+    // The separator only matters for text
+    assert!(json.get("separator").is_none());
+
+    let time = UNIX_EPOCH + Duration::from_secs(42);
+    let json: serde_json::Value = serde_json::from_str(&Json::at(time).format(&event)).unwrap();
+    assert_eq!(json["time"], 42.0);
+}
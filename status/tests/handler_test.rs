@@ -0,0 +1,122 @@
+use std::fs;
+use std::sync::Arc;
+use std::time::{Duration, UNIX_EPOCH};
+
+use status::StatusEmitter;
+use status::fanout::Fanout;
+use status::line_format::LineFormat;
+use status::log_file_handler::LogFileHandler;
+use status::recording::Recording;
+use status::status_event::{OperationId, Phase, StatusEvent, Step};
+
+fn sample_event() -> StatusEvent {
+    StatusEvent::new()
+        .with_operation(OperationId(7))
+        .with_phase(Phase::ScanningFiles { found: None })
+        .with_current(256)
+        .with_total(1024)
+        .with_eta(Duration::from_millis(1500))
+}
+
+#[test]
+fn test_human_and_json_lines() {
+    let event = sample_event();
+    let time = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+
+    assert_eq!(
+        LineFormat::Human.format_at(&event, time),
+        "op 7: Scanning files 256/1024 (25.0%) 2 s left"
+    );
+
+    let json: serde_json::Value =
+        serde_json::from_str(&LineFormat::Json.format_at(&event, time)).unwrap();
+    assert_eq!(json["time"], 1_700_000_000.0);
+    assert_eq!(json["operation"], 7);
+    assert_eq!(json["phase"]["step"], "ScanningFiles");
+    assert_eq!(json["phase"]["found"], serde_json::Value::Null);
+    assert_eq!(json["current"], 256);
+    assert_eq!(json["eta_secs"], 1.5);
+    // Fields that are not set are left out
+    assert!(json.get("message").is_none());
+}
+
+#[test]
+fn test_fanout_sends_to_every_handler() {
+    let first = Arc::new(Recording::new());
+    let second = Arc::new(Recording::new());
+    let fanout = Fanout::new(vec![first.clone()]).with(second.clone());
+    assert_eq!(fanout.len(), 2);
+
+    let emitter = StatusEmitter::new(Arc::new(fanout));
+    emitter
+        .with_phase(Phase::ScanningBom { found: None })
+        .emit();
+    emitter.with_phase(Phase::Done).emit();
+
+    assert_eq!(first.steps(), vec![Step::ScanningBom, Step::Done]);
+    assert_eq!(second.len(), 2);
+    assert_eq!(first.events()[1].operation, second.events()[1].operation);
+
+    first.clear();
+    assert!(first.is_empty());
+
+    // Nothing to send to is fine
+    StatusEmitter::new(Arc::new(Fanout::default()))
+        .with_phase(Phase::Done)
+        .emit();
+}
+
+#[test]
+fn test_log_file_appends_and_rotates() -> std::io::Result<()> {
+    let dir = std::env::temp_dir().join("bristo_status_log");
+    let _ = fs::remove_dir_all(&dir);
+    let path = dir.join("logs/status.log");
+
+    let handler = Arc::new(
+        LogFileHandler::new(&path, LineFormat::Json)?
+            .max_bytes(400)
+            .keep(2),
+    );
+    let emitter = StatusEmitter::new(handler.clone());
+    for current in 0..20 {
+        emitter
+            .with_phase(Phase::Trashing { trashed: None })
+            .with_current(current)
+            .emit();
+    }
+
+    // Every line is a whole event and no file grew past the limit
+    let mut lines = 0;
+    for file in [
+        path.clone(),
+        handler.rotated_path(1),
+        handler.rotated_path(2),
+    ] {
+        let text = fs::read_to_string(&file)?;
+        assert!(text.len() <= 400, "{} too big", file.display());
+        for line in text.lines() {
+            serde_json::from_str::<serde_json::Value>(line).unwrap();
+            lines += 1;
+        }
+    }
+    assert!(lines < 20);
+    assert!(!handler.rotated_path(3).exists());
+
+    // The newest event is at the end of the current file
+    let text = fs::read_to_string(&path)?;
+    let last: serde_json::Value = serde_json::from_str(text.lines().last().unwrap()).unwrap();
+    assert_eq!(last["current"], 19);
+
+    // Reopening appends
+    let before = text.lines().count();
+    let handler = LogFileHandler::new(&path, LineFormat::Human)?;
+    StatusEmitter::new(Arc::new(handler))
+        .with_phase(Phase::Done)
+        .emit();
+    let text = fs::read_to_string(&path)?;
+    assert_eq!(text.lines().count(), before + 1);
+    assert!(text.ends_with("Done\n"));
+
+    let _ = fs::remove_dir_all(&dir);
+    Ok(())
+}
@@ -0,0 +1,125 @@
+use std::sync::Arc;
+
+use status::recording::Recording;
+use status::status_event::{OperationId, Phase, Step};
+use status::{StatusEmitter, setup_status_emitter};
+
+fn recording_emitter() -> (StatusEmitter, Arc<Recording>) {
+    let recorder = Arc::new(Recording::new());
+    (StatusEmitter::new(recorder.clone()), recorder)
+}
+
+#[test]
+fn test_phases_map_to_steps() {
+    let phases = [
+        Phase::ResolvingApp { path: None },
+        Phase::ScanningProcesses { found: Some(2) },
+        Phase::KillingProcesses { killed: None },
+        Phase::ScanningBom { found: None },
+        Phase::ScanningFiles { found: Some(7) },
+        Phase::Trashing { trashed: Some(3) },
+        Phase::ExportingBom { saved: None },
+        Phase::Done,
+        Phase::Failed {
+            reason: "Access denied".to_string(),
+        },
+    ];
+    let steps: Vec<Step> = phases.iter().map(Phase::step).collect();
+    assert_eq!(
+        steps,
+        vec![
+            Step::ResolvingApp,
+            Step::ScanningProcesses,
+            Step::KillingProcesses,
+            Step::ScanningBom,
+            Step::ScanningFiles,
+            Step::Trashing,
+            Step::ExportingBom,
+            Step::Done,
+            Step::Failed,
+        ]
+    );
+
+    let terminal: Vec<bool> = steps.iter().map(Step::is_terminal).collect();
+    assert_eq!(terminal.iter().filter(|t| **t).count(), 2);
+    assert!(Step::Done.is_terminal() && Step::Failed.is_terminal());
+
+    assert_eq!(phases[1].count(), Some(2));
+    assert_eq!(phases[3].count(), None);
+    assert_eq!(phases[4].to_string(), "Scanning files: 7 found");
+    assert_eq!(phases[8].to_string(), "Failed: Access denied");
+}
+
+#[test]
+fn test_events_keep_their_order_and_operation() {
+    let (emitter, recorder) = recording_emitter();
+
+    emitter
+        .with_phase(Phase::ScanningFiles { found: None })
+        .emit();
+    for current in 1..=3 {
+        emitter
+            .with_phase(Phase::ScanningFiles { found: None })
+            .with_current(current)
+            .emit();
+    }
+    emitter
+        .with_phase(Phase::ScanningFiles { found: Some(3) })
+        .emit();
+    emitter.with_phase(Phase::Done).emit();
+
+    let events = recorder.events();
+    let phases: Vec<Phase> = events.iter().filter_map(|e| e.phase.clone()).collect();
+    assert_eq!(phases.len(), 6);
+    assert_eq!(phases[4], Phase::ScanningFiles { found: Some(3) });
+    assert_eq!(phases[5], Phase::Done);
+
+    let currents: Vec<usize> = events.iter().filter_map(|e| e.current).collect();
+    assert_eq!(currents, vec![1, 2, 3]);
+
+    // Only the last event ends the operation
+    assert!(events.iter().rev().skip(1).all(|e| !e.is_terminal()));
+    assert!(events.iter().all(|e| e.operation == emitter.operation()));
+}
+
+#[test]
+fn test_operations_are_told_apart() {
+    let (first, recorder) = recording_emitter();
+    let (second, _) = recording_emitter();
+    assert_ne!(first.operation(), second.operation());
+    assert_ne!(first.operation(), OperationId::default());
+
+    // Another emitter can continue the operation of the first
+    let continued = StatusEmitter::new(recorder.clone()).with_operation(first.operation());
+    first.with_phase(Phase::ResolvingApp { path: None }).emit();
+    continued
+        .with_phase(Phase::ScanningBom { found: None })
+        .emit();
+
+    let events = recorder.events();
+    assert_eq!(events[0].operation, events[1].operation);
+    assert_eq!(events[1].step(), Some(Step::ScanningBom));
+}
+
+#[test]
+fn test_channel_delivers_the_phases() {
+    let (emitter, mut rx) = setup_status_emitter(10);
+
+    emitter
+        .with_phase(Phase::KillingProcesses { killed: None })
+        .emit();
+    emitter
+        .with_phase(Phase::KillingProcesses { killed: Some(1) })
+        .with_message("All processes killed")
+        .emit();
+
+    let started = rx.try_recv().expect("started event");
+    let finished = rx.try_recv().expect("finished event");
+    assert_eq!(
+        started.phase,
+        Some(Phase::KillingProcesses { killed: None })
+    );
+    assert_eq!(finished.phase.as_ref().and_then(Phase::count), Some(1));
+    assert_eq!(finished.message.as_deref(), Some("All processes killed"));
+    assert_eq!(started.operation, finished.operation);
+}
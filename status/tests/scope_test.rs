@@ -0,0 +1,114 @@
+use std::sync::Arc;
+
+use status::StatusEmitter;
+use status::recording::Recording;
+use status::status_event::Phase;
+
+fn recording_emitter() -> (StatusEmitter, Arc<Recording>) {
+    let recorder = Arc::new(Recording::new());
+    (StatusEmitter::new(recorder.clone()), recorder)
+}
+
+fn close(a: Option<f32>, b: f32) -> bool {
+    a.is_some_and(|a| (a - b).abs() < 1e-4)
+}
+
+#[test]
+fn test_children_roll_up_into_the_parent() {
+    let (batch, recorder) = recording_emitter();
+
+    let apps: Vec<StatusEmitter> = (1..=5)
+        .map(|i| batch.child(format!("App {i}/5"), 0.2))
+        .collect();
+
+    // The first app is done, the second scans its files
+    apps[0].with_phase(Phase::Done).emit();
+    let scanning = apps[1].child("Scanning files", 0.5);
+    scanning
+        .with_phase(Phase::ScanningFiles { found: None })
+        .with_current(40)
+        .with_total(100)
+        .emit();
+
+    let events = recorder.events();
+    assert!(close(events[0].overall, 0.2));
+    // 40% of the first half of the second app
+    assert!(close(events[1].overall, 0.2 + 0.2 * 0.5 * 0.4));
+    assert_eq!(events[1].scope, vec!["App 2/5", "Scanning files"]);
+    assert_eq!(
+        events[1].scope_label().as_deref(),
+        Some("App 2/5 › Scanning files")
+    );
+
+    // Every scope reports on the same operation
+    assert!(events.iter().all(|e| e.operation == batch.operation()));
+
+    batch.with_phase(Phase::Done).emit();
+    assert!(close(recorder.events()[2].overall, 1.0));
+}
+
+#[test]
+fn test_overall_progress_never_goes_back() {
+    let (operation, recorder) = recording_emitter();
+    let kill = operation.child("Kill", 0.2);
+    let scan = operation.child("Scan", 0.8);
+
+    kill.with_phase(Phase::KillingProcesses { killed: None })
+        .with_current(1)
+        .with_total(2)
+        .emit();
+    scan.with_phase(Phase::ScanningFiles { found: None })
+        .with_current(50)
+        .with_total(100)
+        .emit();
+    // A late event of an earlier step does not move the bar back
+    kill.with_phase(Phase::KillingProcesses { killed: Some(2) })
+        .emit();
+
+    let overall: Vec<f32> = recorder
+        .events()
+        .iter()
+        .map(|e| e.overall.unwrap())
+        .collect();
+    assert!(close(Some(overall[0]), 0.1));
+    assert!(close(Some(overall[1]), 0.6));
+    assert!(close(Some(overall[2]), 0.6));
+}
+
+#[test]
+fn test_weights_past_the_parent_are_cut() {
+    let (operation, recorder) = recording_emitter();
+    let first = operation.child("First", 0.7);
+    let second = operation.child("Second", 0.7);
+
+    first.with_phase(Phase::Done).emit();
+    second.with_phase(Phase::Done).emit();
+    let third = operation.child("Third", 0.5);
+    third
+        .with_phase(Phase::Trashing { trashed: None })
+        .with_current(1)
+        .with_total(2)
+        .emit();
+
+    let events = recorder.events();
+    assert!(close(events[0].overall, 0.7));
+    assert!(close(events[1].overall, 1.0));
+    assert!(close(events[2].overall, 1.0));
+}
+
+#[test]
+fn test_flat_events_have_no_overall() {
+    let (emitter, recorder) = recording_emitter();
+
+    emitter
+        .with_phase(Phase::ScanningFiles { found: None })
+        .with_current(1)
+        .with_total(4)
+        .emit();
+
+    let events = recorder.events();
+    assert_eq!(events[0].overall, None);
+    assert!(events[0].scope.is_empty());
+    assert_eq!(events[0].scope_label(), None);
+    assert_eq!(events[0].fraction(), Some(0.25));
+}
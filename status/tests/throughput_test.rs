@@ -0,0 +1,57 @@
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use status::StatusEmitter;
+use status::recording::Recording;
+use status::status_event::{Phase, StatusEvent};
+use status::throughput::Throughput;
+
+#[test]
+fn test_rate_and_time_left() {
+    let started = Instant::now();
+    let throughput = Throughput::since(started);
+    let later = started + Duration::from_secs(4);
+
+    assert_eq!(throughput.rate_at(0, later), None);
+    assert_eq!(throughput.rate_at(100, started), None);
+    assert_eq!(throughput.rate_at(1000, later), Some(250.0));
+
+    // 1000 of 3000 in 4 seconds, 2000 more take 8 seconds
+    assert_eq!(
+        throughput.eta_at(1000, 3000, later),
+        Some(Duration::from_secs(8))
+    );
+    // An estimated total that was passed leaves no time
+    assert_eq!(throughput.eta_at(3500, 3000, later), Some(Duration::ZERO));
+}
+
+#[test]
+fn test_fraction_needs_a_total() {
+    let event = StatusEvent::new().with_current(50);
+    assert_eq!(event.fraction(), None);
+    assert_eq!(event.clone().with_total(0).fraction(), None);
+    assert_eq!(event.clone().with_total(200).fraction(), Some(0.25));
+    assert_eq!(event.with_total(40).fraction(), Some(1.0));
+}
+
+#[test]
+fn test_progress_carries_rate_and_eta() {
+    let recorder = Arc::new(Recording::new());
+    let emitter = StatusEmitter::new(recorder.clone());
+    let throughput = Throughput::since(Instant::now() - Duration::from_secs(2));
+
+    let scanning = emitter.with_phase(Phase::ScanningFiles { found: None });
+    scanning.with_progress(256, Some(1024), &throughput).emit();
+    scanning.with_progress(512, None, &throughput).emit();
+
+    let events = recorder.events();
+    assert_eq!(events[0].current, Some(256));
+    assert_eq!(events[0].total, Some(1024));
+    assert!(events[0].rate.is_some_and(|r| r > 0.0));
+    assert!(events[0].eta.is_some());
+
+    // Without a total there is a speed but no time left
+    assert_eq!(events[1].total, None);
+    assert!(events[1].rate.is_some());
+    assert_eq!(events[1].eta, None);
+}
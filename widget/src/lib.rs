@@ -1,3 +1,4 @@
 pub mod button_style;
 pub mod drop_file;
 pub mod list_view;
+pub mod progress_bar;
@@ -0,0 +1,131 @@
+use iced::{
+    Background, Border, Color, Element, Length, alignment,
+    widget::{Column, Container, Row, Text, container},
+};
+
+/// Portions the bar is split in, fine enough for a smooth fill
+const PORTIONS: u16 = 1000;
+
+pub struct ProgressBar {
+    fraction: Option<f32>,
+    label: Option<String>,
+    width: Length,
+    height: f32,
+    text_size: u32,
+    bar_color: Color,
+    track_color: Color,
+}
+
+impl ProgressBar {
+    // The fraction is the part done between 0 and 1,
+    // without one the bar is only the track with its label
+    pub fn new(fraction: Option<f32>) -> Self {
+        Self {
+            fraction: fraction.map(|f| f.clamp(0.0, 1.0)),
+            label: None,
+            width: Length::Fill,
+            height: 6.0,
+            text_size: 11,
+            bar_color: Color::from_rgb8(90, 160, 255),
+            track_color: Color {
+                r: 1.0,
+                g: 1.0,
+                b: 1.0,
+                a: 0.08,
+            },
+        }
+    }
+
+    pub fn label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    pub fn width(mut self, w: Length) -> Self {
+        self.width = w;
+        self
+    }
+
+    pub fn height(mut self, h: f32) -> Self {
+        self.height = h;
+        self
+    }
+
+    pub fn text_size(mut self, size: u32) -> Self {
+        self.text_size = size;
+        self
+    }
+
+    pub fn bar_color(mut self, color: Color) -> Self {
+        self.bar_color = color;
+        self
+    }
+
+    pub fn track_color(mut self, color: Color) -> Self {
+        self.track_color = color;
+        self
+    }
+
+    // =====================viewer===============
+    pub fn view<M: 'static>(self) -> Element<'static, M> {
+        let done = self
+            .fraction
+            .map(|f| (f * PORTIONS as f32).round() as u16)
+            .unwrap_or(0);
+
+        // A portion of zero would still take its minimum size
+        let mut fill = Row::new().width(Length::Fill).height(Length::Fill);
+        if done > 0 {
+            let bar_color = self.bar_color;
+            fill = fill.push(
+                Container::new(Row::new())
+                    .width(Length::FillPortion(done))
+                    .height(Length::Fill)
+                    .style(move |_| bar_style(bar_color)),
+            );
+        }
+        if done < PORTIONS {
+            fill = fill.push(
+                Container::new(Row::new())
+                    .width(Length::FillPortion(PORTIONS - done))
+                    .height(Length::Fill),
+            );
+        }
+
+        let track_color = self.track_color;
+        let track = Container::new(fill)
+            .width(Length::Fill)
+            .height(Length::Fixed(self.height))
+            .style(move |_| bar_style(track_color));
+
+        let mut column = Column::new().push(track).width(self.width).spacing(3);
+        if let Some(label) = self.label {
+            column = column.push(
+                Text::new(label)
+                    .size(self.text_size)
+                    .width(Length::Fill)
+                    .align_x(alignment::Horizontal::Center)
+                    .color(Color::from_rgb8(200, 200, 200)),
+            );
+        }
+
+        Container::new(column)
+            .width(self.width)
+            .align_y(alignment::Vertical::Center)
+            .into()
+    }
+}
+
+fn bar_style(color: Color) -> container::Style {
+    container::Style {
+        background: Some(Background::Color(color)),
+        text_color: None,
+        border: Border {
+            color: Color::TRANSPARENT,
+            width: 0.0,
+            radius: 3.0.into(),
+        },
+        snap: false,
+        shadow: Default::default(),
+    }
+}